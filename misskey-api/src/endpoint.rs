@@ -43,6 +43,7 @@ macro_rules! impl_offset_pagination {
 pub mod admin;
 pub mod announcements;
 pub mod antennas;
+pub mod ap;
 pub mod blocking;
 pub mod charts;
 pub mod clips;
@@ -50,7 +51,12 @@ pub mod drive;
 #[allow(clippy::module_inception)]
 pub mod endpoint;
 pub mod endpoints;
+pub mod federation;
 pub mod following;
+#[cfg(any(docsrs, not(feature = "13-10-0")))]
+#[cfg_attr(docsrs, doc(cfg(not(feature = "13-10-0"))))]
+pub mod games;
+pub mod hashtags;
 pub mod i;
 pub mod messaging;
 pub mod meta;
@@ -78,3 +84,15 @@ pub mod server_info;
 #[cfg(feature = "12-67-0")]
 #[cfg_attr(docsrs, doc(cfg(feature = "12-67-0")))]
 pub mod ping;
+
+#[cfg(feature = "12-79-0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+pub mod gallery;
+
+#[cfg(feature = "13-10-0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+pub mod renote_mute;
+
+#[cfg(feature = "13-10-0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+pub mod roles;
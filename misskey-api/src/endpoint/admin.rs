@@ -1,21 +1,23 @@
 pub mod abuse_user_reports;
 pub mod accounts;
 pub mod announcements;
+pub mod delete_all_files_of_a_user;
 pub mod delete_logs;
+pub mod drive;
 pub mod emoji;
+pub mod federation;
 pub mod get_table_stats;
 pub mod invite;
 pub mod logs;
-pub mod moderators;
+pub mod queue;
+pub mod relays;
 pub mod reset_password;
 pub mod resync_chart;
 pub mod server_info;
 pub mod show_moderation_logs;
 pub mod show_user;
 pub mod show_users;
-pub mod silence_user;
 pub mod suspend_user;
-pub mod unsilence_user;
 pub mod unsuspend_user;
 pub mod update_meta;
 pub mod vacuum;
@@ -24,6 +26,10 @@ pub mod vacuum;
 #[cfg_attr(docsrs, doc(cfg(feature = "12-13-0")))]
 pub mod promo;
 
+#[cfg(feature = "13-10-0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+pub mod roles;
+
 #[cfg(any(docsrs, not(feature = "12-49-0")))]
 #[cfg_attr(docsrs, doc(cfg(not(feature = "12-49-0"))))]
 pub mod remove_abuse_user_report;
@@ -31,3 +37,15 @@ pub mod remove_abuse_user_report;
 #[cfg(feature = "12-49-0")]
 #[cfg_attr(docsrs, doc(cfg(feature = "12-49-0")))]
 pub mod resolve_abuse_user_report;
+
+#[cfg(any(docsrs, not(feature = "13-10-0")))]
+#[cfg_attr(docsrs, doc(cfg(not(feature = "13-10-0"))))]
+pub mod moderators;
+
+#[cfg(any(docsrs, not(feature = "13-10-0")))]
+#[cfg_attr(docsrs, doc(cfg(not(feature = "13-10-0"))))]
+pub mod silence_user;
+
+#[cfg(any(docsrs, not(feature = "13-10-0")))]
+#[cfg_attr(docsrs, doc(cfg(not(feature = "13-10-0"))))]
+pub mod unsilence_user;
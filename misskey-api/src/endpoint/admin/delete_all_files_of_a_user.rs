@@ -0,0 +1,29 @@
+use crate::model::{id::Id, user::User};
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub user_id: Id<User>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "admin/delete-all-files-of-a-user";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, HttpClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let (user, user_client) = client.admin.create_user().await;
+        user_client.create_text_file("test.txt", "test").await;
+
+        client.admin.test(Request { user_id: user.id }).await;
+    }
+}
@@ -0,0 +1,4 @@
+pub mod clean_remote_files;
+pub mod cleanup;
+pub mod files;
+pub mod show_file;
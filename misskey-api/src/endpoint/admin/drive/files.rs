@@ -0,0 +1,103 @@
+use crate::model::{drive::DriveFile, id::Id, user::UserOrigin};
+
+use mime::Mime;
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+
+#[derive(Serialize, Default, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    /// 1 .. 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub limit: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub since_id: Option<Id<DriveFile>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub until_id: Option<Id<DriveFile>>,
+    /// Wildcards such as `image/*` are allowed.
+    #[serde(
+        rename = "type",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::serde::serialize_string_option"
+    )]
+    #[builder(default, setter(strip_option))]
+    pub type_: Option<Mime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub origin: Option<UserOrigin>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    pub hostname: Option<String>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<DriveFile>;
+    const ENDPOINT: &'static str = "admin/drive/files";
+}
+
+impl_pagination!(Request, DriveFile);
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::model::user::UserOrigin;
+    use crate::test::{ClientExt, HttpClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client.admin.test(Request::default()).await;
+    }
+
+    #[tokio::test]
+    async fn request_with_limit() {
+        let client = TestClient::new();
+        client
+            .admin
+            .test(Request::builder().limit(100).build())
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_with_options() {
+        let client = TestClient::new();
+        client
+            .admin
+            .test(
+                Request::builder()
+                    .type_(mime::IMAGE_STAR)
+                    .origin(UserOrigin::Local)
+                    .build(),
+            )
+            .await;
+        client
+            .admin
+            .test(
+                Request::builder()
+                    .origin(UserOrigin::Remote)
+                    .hostname("example.com")
+                    .build(),
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_paginate() {
+        let client = TestClient::new();
+        let file = client.create_text_file("test.txt", "test").await;
+
+        client
+            .admin
+            .test(
+                Request::builder()
+                    .since_id(file.id)
+                    .until_id(file.id)
+                    .build(),
+            )
+            .await;
+    }
+}
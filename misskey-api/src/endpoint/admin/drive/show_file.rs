@@ -0,0 +1,85 @@
+use crate::model::{
+    drive::{DriveFile, DriveFileProperties, DriveFolder},
+    id::Id,
+    user::User,
+};
+
+use chrono::{DateTime, Utc};
+use mime::Mime;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Request {
+    #[serde(rename_all = "camelCase")]
+    WithFileId { file_id: Id<DriveFile> },
+    #[serde(rename_all = "camelCase")]
+    WithUrl { url: Url },
+}
+
+/// The file record including the information only visible to moderators.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    pub id: Id<DriveFile>,
+    pub created_at: DateTime<Utc>,
+    pub user_id: Option<Id<User>>,
+    pub user_host: Option<String>,
+    pub md5: String,
+    pub name: String,
+    #[serde(rename = "type", with = "crate::serde::string")]
+    pub type_: Mime,
+    pub size: u64,
+    pub comment: Option<String>,
+    pub properties: DriveFileProperties,
+    pub stored_internal: bool,
+    pub url: Option<Url>,
+    pub thumbnail_url: Option<Url>,
+    pub webpublic_url: Option<Url>,
+    pub access_key: Option<String>,
+    pub thumbnail_access_key: Option<String>,
+    pub webpublic_access_key: Option<String>,
+    /// The URI of the file on the remote instance.
+    pub uri: Option<String>,
+    /// The URL from which the remote file was fetched.
+    pub src: Option<String>,
+    pub folder_id: Option<Id<DriveFolder>>,
+    pub is_sensitive: bool,
+    pub is_link: bool,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Response;
+    const ENDPOINT: &'static str = "admin/drive/show-file";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, HttpClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request_with_file_id() {
+        let client = TestClient::new();
+        let file = client.create_text_file("test.txt", "test").await;
+
+        client
+            .admin
+            .test(Request::WithFileId { file_id: file.id })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_with_url() {
+        let client = TestClient::new();
+        let file = client.create_text_file("test.txt", "test").await;
+
+        client
+            .admin
+            .test(Request::WithUrl {
+                url: file.url.unwrap(),
+            })
+            .await;
+    }
+}
@@ -0,0 +1,4 @@
+pub mod delete_all_files;
+pub mod refresh_remote_instance_metadata;
+pub mod remove_all_following;
+pub mod update_instance;
@@ -0,0 +1,50 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub host: String,
+    pub is_suspended: bool,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "admin/federation/update-instance";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let instances = client
+            .admin
+            .test(
+                crate::endpoint::federation::instances::Request::builder()
+                    .limit(1)
+                    .build(),
+            )
+            .await;
+
+        // the instance must be known to the server
+        if let Some(instance) = instances.into_iter().next() {
+            client
+                .admin
+                .test(Request {
+                    host: instance.host.clone(),
+                    is_suspended: true,
+                })
+                .await;
+            client
+                .admin
+                .test(Request {
+                    host: instance.host,
+                    is_suspended: false,
+                })
+                .await;
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod clear;
+pub mod deliver_delayed;
+pub mod inbox_delayed;
+pub mod jobs;
+pub mod stats;
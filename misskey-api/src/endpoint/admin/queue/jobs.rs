@@ -0,0 +1,70 @@
+use crate::model::queue::{QueueDomain, QueueJob, QueueJobState};
+
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+
+#[derive(Serialize, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    pub domain: QueueDomain,
+    pub state: QueueJobState,
+    /// 1 .. 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub limit: Option<u8>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<QueueJob>;
+    const ENDPOINT: &'static str = "admin/queue/jobs";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::model::queue::{QueueDomain, QueueJobState};
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client
+            .admin
+            .test(
+                Request::builder()
+                    .domain(QueueDomain::Deliver)
+                    .state(QueueJobState::Delayed)
+                    .build(),
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_with_options() {
+        let client = TestClient::new();
+        for domain in [
+            QueueDomain::Deliver,
+            QueueDomain::Inbox,
+            QueueDomain::Db,
+            QueueDomain::ObjectStorage,
+        ] {
+            for state in [
+                QueueJobState::Active,
+                QueueJobState::Waiting,
+                QueueJobState::Delayed,
+            ] {
+                client
+                    .admin
+                    .test(
+                        Request::builder()
+                            .domain(domain)
+                            .state(state)
+                            .limit(100)
+                            .build(),
+                    )
+                    .await;
+            }
+        }
+    }
+}
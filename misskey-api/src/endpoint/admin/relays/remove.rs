@@ -0,0 +1,33 @@
+use serde::Serialize;
+use url::Url;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub inbox: Url,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "admin/relays/remove";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let inbox = format!("https://{}.example.com/inbox", ulid_crate::Ulid::new());
+        let relay = client
+            .admin
+            .test(crate::endpoint::admin::relays::add::Request {
+                inbox: inbox.parse().unwrap(),
+            })
+            .await;
+
+        client.admin.test(Request { inbox: relay.inbox }).await;
+    }
+}
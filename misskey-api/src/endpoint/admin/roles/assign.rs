@@ -0,0 +1,78 @@
+use crate::model::{id::Id, role::Role, user::User};
+
+use chrono::{serde::ts_milliseconds_option, DateTime, Utc};
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+
+#[derive(Serialize, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    pub role_id: Id<Role>,
+    pub user_id: Id<User>,
+    /// The user is unassigned from the role after this time.
+    /// `None` means the assignment does not expire.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "ts_milliseconds_option"
+    )]
+    #[builder(default, setter(strip_option, into))]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "admin/roles/assign";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let (user, _) = client.admin.create_user().await;
+        let role = client
+            .admin
+            .test(
+                crate::endpoint::admin::roles::create::Request::builder()
+                    .name("test")
+                    .build(),
+            )
+            .await;
+
+        client
+            .admin
+            .test(Request {
+                role_id: role.id,
+                user_id: user.id,
+                expires_at: None,
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_with_expires_at() {
+        let client = TestClient::new();
+        let (user, _) = client.admin.create_user().await;
+        let role = client
+            .admin
+            .test(
+                crate::endpoint::admin::roles::create::Request::builder()
+                    .name("test")
+                    .build(),
+            )
+            .await;
+
+        client
+            .admin
+            .test(Request {
+                role_id: role.id,
+                user_id: user.id,
+                expires_at: Some(chrono::Utc::now() + chrono::Duration::hours(1)),
+            })
+            .await;
+    }
+}
@@ -0,0 +1,99 @@
+use crate::model::role::{Role, RoleCondFormula, RolePolicies, RoleTarget};
+
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+use url::Url;
+
+#[derive(Serialize, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(default, setter(into))]
+    pub description: String,
+    #[builder(default, setter(strip_option, into))]
+    pub color: Option<String>,
+    #[builder(default, setter(strip_option))]
+    pub icon_url: Option<Url>,
+    #[builder(default = RoleTarget::Manual)]
+    pub target: RoleTarget,
+    #[builder(default)]
+    pub cond_formula: RoleCondFormula,
+    #[builder(default)]
+    pub is_public: bool,
+    #[builder(default)]
+    pub is_moderator: bool,
+    #[builder(default)]
+    pub is_administrator: bool,
+    #[builder(default)]
+    pub as_badge: bool,
+    #[builder(default)]
+    pub can_edit_members_by_moderator: bool,
+    #[builder(default)]
+    pub display_order: i64,
+    #[builder(default)]
+    pub policies: RolePolicies,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Role;
+    const ENDPOINT: &'static str = "admin/roles/create";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::model::role::{RoleCondFormula, RolePolicies, RolePolicy, RoleTarget};
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client
+            .admin
+            .test(Request::builder().name("test").build())
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_with_options() {
+        let client = TestClient::new();
+        client
+            .admin
+            .test(Request {
+                name: "test".to_string(),
+                description: "description".to_string(),
+                color: Some("#ff0000".to_string()),
+                icon_url: Some(client.avatar_url().await),
+                target: RoleTarget::Conditional,
+                cond_formula: RoleCondFormula::And {
+                    values: vec![
+                        RoleCondFormula::IsLocal,
+                        RoleCondFormula::Not {
+                            value: Box::new(RoleCondFormula::CreatedLessThan { sec: 86400 }),
+                        },
+                        RoleCondFormula::Or {
+                            values: vec![
+                                RoleCondFormula::FollowersMoreThanOrEq { value: 10 },
+                                RoleCondFormula::FollowingLessThanOrEq { value: 100 },
+                            ],
+                        },
+                    ],
+                },
+                is_public: true,
+                is_moderator: false,
+                is_administrator: false,
+                as_badge: true,
+                can_edit_members_by_moderator: true,
+                display_order: 1,
+                policies: RolePolicies {
+                    ltl_available: Some(RolePolicy::new(false)),
+                    drive_capacity_mb: Some(RolePolicy::new(1024)),
+                    rate_limit_factor: Some(RolePolicy::new(0.5)),
+                    ..RolePolicies::default()
+                },
+            })
+            .await;
+    }
+}
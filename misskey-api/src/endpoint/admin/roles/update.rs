@@ -0,0 +1,80 @@
+use crate::model::{
+    id::Id,
+    role::{Role, RoleCondFormula, RolePolicies, RoleTarget},
+};
+
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+use url::Url;
+
+#[derive(Serialize, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    pub role_id: Id<Role>,
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(setter(into))]
+    pub description: String,
+    #[builder(default, setter(strip_option, into))]
+    pub color: Option<String>,
+    #[builder(default, setter(strip_option))]
+    pub icon_url: Option<Url>,
+    pub target: RoleTarget,
+    pub cond_formula: RoleCondFormula,
+    pub is_public: bool,
+    pub is_moderator: bool,
+    pub is_administrator: bool,
+    pub as_badge: bool,
+    pub can_edit_members_by_moderator: bool,
+    pub display_order: i64,
+    pub policies: RolePolicies,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "admin/roles/update";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::model::role::{RoleCondFormula, RolePolicies, RolePolicy, RoleTarget};
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let role = client
+            .admin
+            .test(
+                crate::endpoint::admin::roles::create::Request::builder()
+                    .name("test")
+                    .build(),
+            )
+            .await;
+
+        client
+            .admin
+            .test(Request {
+                role_id: role.id,
+                name: "updated".to_string(),
+                description: "updated".to_string(),
+                color: None,
+                icon_url: None,
+                target: RoleTarget::Conditional,
+                cond_formula: RoleCondFormula::IsRemote,
+                is_public: false,
+                is_moderator: true,
+                is_administrator: false,
+                as_badge: false,
+                can_edit_members_by_moderator: false,
+                display_order: 0,
+                policies: RolePolicies {
+                    can_invite: Some(RolePolicy::new(true)),
+                    ..RolePolicies::default()
+                },
+            })
+            .await;
+    }
+}
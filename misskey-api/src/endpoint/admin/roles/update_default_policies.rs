@@ -0,0 +1,37 @@
+use crate::model::role::Policies;
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub policies: Policies,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "admin/roles/update-default-policies";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::model::role::Policies;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client
+            .admin
+            .test(Request {
+                policies: Policies {
+                    gtl_available: Some(true),
+                    ltl_available: Some(true),
+                    can_public_note: Some(true),
+                    ..Policies::default()
+                },
+            })
+            .await;
+    }
+}
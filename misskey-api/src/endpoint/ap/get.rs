@@ -0,0 +1,29 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub uri: String,
+}
+
+impl misskey_core::Request for Request {
+    type Response = serde_json::Value;
+    const ENDPOINT: &'static str = "ap/get";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    use misskey_test::env;
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let user = client.user.me().await;
+        let uri = env::api_url().join(&format!("/users/{}", user.id)).unwrap();
+
+        client.test(Request { uri: uri.into() }).await;
+    }
+}
@@ -0,0 +1,25 @@
+use crate::model::{following::Following, id::Id, user::User};
+
+use serde::Deserialize;
+
+pub mod followers;
+pub mod following;
+pub mod instances;
+pub mod show_instance;
+pub mod update_remote_user;
+pub mod users;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FollowingWithFollowee {
+    #[serde(flatten)]
+    pub following: Following,
+    pub followee: User,
+}
+
+impl crate::PaginationItem for FollowingWithFollowee {
+    type Id = Id<Following>;
+    fn item_id(&self) -> Id<Following> {
+        self.following.id
+    }
+}
@@ -0,0 +1,52 @@
+use crate::endpoint::federation::FollowingWithFollowee;
+use crate::model::{following::Following, id::Id};
+
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+
+#[derive(Serialize, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    #[builder(setter(into))]
+    pub host: String,
+    /// 1 .. 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub limit: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub since_id: Option<Id<Following>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub until_id: Option<Id<Following>>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<FollowingWithFollowee>;
+    const ENDPOINT: &'static str = "federation/following";
+}
+
+impl_pagination!(Request, FollowingWithFollowee);
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client
+            .test(Request::builder().host("example.com").build())
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_with_limit() {
+        let client = TestClient::new();
+        client
+            .test(Request::builder().host("example.com").limit(100).build())
+            .await;
+    }
+}
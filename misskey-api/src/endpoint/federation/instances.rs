@@ -0,0 +1,114 @@
+use crate::model::{
+    instance::{Instance, InstanceSortKey},
+    sort::SortOrder,
+};
+
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+
+#[derive(Serialize, Default, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option, into))]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub blocked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub not_responding: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub suspended: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub federating: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub subscribing: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub publishing: Option<bool>,
+    /// 1 .. 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub limit: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub sort: Option<SortOrder<InstanceSortKey>>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<Instance>;
+    const ENDPOINT: &'static str = "federation/instances";
+}
+
+impl_offset_pagination!(Request, Instance);
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client.test(Request::default()).await;
+    }
+
+    #[tokio::test]
+    async fn request_with_options() {
+        let client = TestClient::new();
+        client
+            .test(
+                Request::builder()
+                    .host("example.com")
+                    .blocked(false)
+                    .not_responding(false)
+                    .suspended(false)
+                    .federating(true)
+                    .subscribing(true)
+                    .publishing(true)
+                    .build(),
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_with_limit() {
+        let client = TestClient::new();
+        client.test(Request::builder().limit(100).build()).await;
+    }
+
+    #[tokio::test]
+    async fn request_with_offset() {
+        let client = TestClient::new();
+        client.test(Request::builder().offset(5).build()).await;
+    }
+
+    #[tokio::test]
+    async fn request_with_sort() {
+        use crate::model::{instance::InstanceSortKey, sort::SortOrder};
+
+        let client = TestClient::new();
+        client
+            .test(
+                Request::builder()
+                    .sort(SortOrder::Descending(InstanceSortKey::PubSub))
+                    .build(),
+            )
+            .await;
+        client
+            .test(
+                Request::builder()
+                    .sort(SortOrder::Ascending(InstanceSortKey::LastCommunicatedAt))
+                    .build(),
+            )
+            .await;
+    }
+}
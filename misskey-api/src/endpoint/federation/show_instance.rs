@@ -0,0 +1,30 @@
+use crate::model::instance::Instance;
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub host: String,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Option<Instance>;
+    const ENDPOINT: &'static str = "federation/show-instance";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client
+            .test(Request {
+                host: "example.com".to_string(),
+            })
+            .await;
+    }
+}
@@ -0,0 +1,36 @@
+use crate::model::{id::Id, user::User};
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub user_id: Id<User>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "federation/update-remote-user";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    use misskey_core::Client;
+
+    // the test instance does not federate, so only the rejection of local users is tested
+    #[tokio::test]
+    async fn request_with_local_user() {
+        let client = TestClient::new();
+        let user = client.user.me().await;
+
+        let result = client
+            .user
+            .request(Request { user_id: user.id })
+            .await
+            .unwrap();
+        assert!(result.into_result().is_err());
+    }
+}
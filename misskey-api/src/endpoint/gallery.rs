@@ -0,0 +1,3 @@
+pub mod featured;
+pub mod popular;
+pub mod posts;
@@ -0,0 +1,42 @@
+use crate::model::{gallery::GalleryPost, id::Id};
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub post_id: Id<GalleryPost>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "gallery/posts/unlike";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let url = client.admin.avatar_url().await;
+        let file = client.admin.upload_from_url(url).await;
+        let post = client
+            .admin
+            .test(
+                crate::endpoint::gallery::posts::create::Request::builder()
+                    .title("gallery post")
+                    .file_ids(vec![file.id])
+                    .build(),
+            )
+            .await;
+
+        client
+            .user
+            .test(crate::endpoint::gallery::posts::like::Request { post_id: post.id })
+            .await;
+        client.user.test(Request { post_id: post.id }).await;
+    }
+}
@@ -0,0 +1,3 @@
+pub mod games;
+pub mod invitations;
+pub mod r#match;
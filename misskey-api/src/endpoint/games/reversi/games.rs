@@ -0,0 +1,84 @@
+use crate::model::{id::Id, reversi::ReversiGame};
+
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+
+pub mod show;
+pub mod surrender;
+
+#[derive(Serialize, Default, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    /// 1 .. 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub limit: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub since_id: Option<Id<ReversiGame>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub until_id: Option<Id<ReversiGame>>,
+    /// Lists only the games played by the user logged in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub my: Option<bool>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<ReversiGame>;
+    const ENDPOINT: &'static str = "games/reversi/games";
+}
+
+impl_pagination!(Request, ReversiGame);
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client.test(Request::default()).await;
+    }
+
+    #[tokio::test]
+    async fn request_with_options() {
+        let client = TestClient::new();
+        client
+            .test(Request {
+                limit: Some(100),
+                since_id: None,
+                until_id: None,
+                my: Some(true),
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_paginate() {
+        let client = TestClient::new();
+        let (user, user_client) = client.admin.create_user().await;
+        let admin = client.admin.me().await;
+        client
+            .admin
+            .test(crate::endpoint::games::reversi::r#match::Request { user_id: user.id })
+            .await;
+        user_client
+            .test(crate::endpoint::games::reversi::r#match::Request { user_id: admin.id })
+            .await;
+
+        let games = client.admin.test(Request::default()).await;
+        client
+            .admin
+            .test(Request {
+                limit: None,
+                since_id: Some(games[0].id),
+                until_id: Some(games[0].id),
+                my: None,
+            })
+            .await;
+    }
+}
@@ -0,0 +1,37 @@
+use crate::model::{id::Id, reversi::ReversiGame};
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub game_id: Id<ReversiGame>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ReversiGame;
+    const ENDPOINT: &'static str = "games/reversi/games/show";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let (user, user_client) = client.admin.create_user().await;
+        let admin = client.admin.me().await;
+        client
+            .admin
+            .test(crate::endpoint::games::reversi::r#match::Request { user_id: user.id })
+            .await;
+        let game = user_client
+            .test(crate::endpoint::games::reversi::r#match::Request { user_id: admin.id })
+            .await
+            .unwrap();
+
+        client.admin.test(Request { game_id: game.id }).await;
+    }
+}
@@ -0,0 +1,30 @@
+use crate::model::reversi::ReversiMatching;
+
+use serde::Serialize;
+
+#[derive(Serialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<ReversiMatching>;
+    const ENDPOINT: &'static str = "games/reversi/invitations";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let (_, user_client) = client.admin.create_user().await;
+        let admin = client.admin.me().await;
+        user_client
+            .test(crate::endpoint::games::reversi::r#match::Request { user_id: admin.id })
+            .await;
+
+        client.admin.test(Request::default()).await;
+    }
+}
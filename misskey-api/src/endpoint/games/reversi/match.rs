@@ -0,0 +1,37 @@
+use crate::model::{id::Id, reversi::ReversiGame, user::User};
+
+use serde::Serialize;
+
+pub mod cancel;
+
+/// Invites the user to a game, or accepts the invitation from the user.
+///
+/// The response is the game created when accepting the invitation, and `None` when inviting.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub user_id: Id<User>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Option<ReversiGame>;
+    const ENDPOINT: &'static str = "games/reversi/match";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let (user, user_client) = client.admin.create_user().await;
+        let admin = client.admin.me().await;
+
+        let invited = client.admin.test(Request { user_id: user.id }).await;
+        assert!(invited.is_none());
+        let game = user_client.test(Request { user_id: admin.id }).await;
+        assert!(game.is_some());
+    }
+}
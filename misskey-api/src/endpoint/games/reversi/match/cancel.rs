@@ -0,0 +1,29 @@
+use serde::Serialize;
+
+/// Cancels all the invitations sent by the user logged in.
+#[derive(Serialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "games/reversi/match/cancel";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let (user, _) = client.admin.create_user().await;
+        client
+            .admin
+            .test(crate::endpoint::games::reversi::r#match::Request { user_id: user.id })
+            .await;
+
+        client.admin.test(Request::default()).await;
+    }
+}
@@ -0,0 +1,5 @@
+pub mod list;
+pub mod search;
+pub mod show;
+pub mod trend;
+pub mod users;
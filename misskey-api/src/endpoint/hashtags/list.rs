@@ -0,0 +1,67 @@
+use crate::model::{
+    hashtag::{Hashtag, HashtagSortKey},
+    sort::SortOrder,
+};
+
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+
+#[derive(Serialize, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    /// 1 .. 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub limit: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub attached_to_user_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub attached_to_local_user_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub attached_to_remote_user_only: Option<bool>,
+    pub sort: SortOrder<HashtagSortKey>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<Hashtag>;
+    const ENDPOINT: &'static str = "hashtags/list";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::model::{hashtag::HashtagSortKey, sort::SortOrder};
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client
+            .test(
+                Request::builder()
+                    .sort(SortOrder::Descending(HashtagSortKey::MentionedUsers))
+                    .build(),
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_with_options() {
+        let client = TestClient::new();
+        client
+            .test(
+                Request::builder()
+                    .limit(100)
+                    .attached_to_user_only(true)
+                    .attached_to_local_user_only(true)
+                    .attached_to_remote_user_only(false)
+                    .sort(SortOrder::Ascending(HashtagSortKey::AttachedLocalUsers))
+                    .build(),
+            )
+            .await;
+    }
+}
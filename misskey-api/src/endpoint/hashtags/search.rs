@@ -0,0 +1,52 @@
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+
+#[derive(Serialize, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    #[builder(setter(into))]
+    pub query: String,
+    /// 1 .. 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub limit: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub offset: Option<u64>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<String>;
+    const ENDPOINT: &'static str = "hashtags/search";
+}
+
+impl_offset_pagination!(Request, String);
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client.test(Request::builder().query("tag").build()).await;
+    }
+
+    #[tokio::test]
+    async fn request_with_limit() {
+        let client = TestClient::new();
+        client
+            .test(Request::builder().query("tag").limit(100).build())
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_with_offset() {
+        let client = TestClient::new();
+        client
+            .test(Request::builder().query("tag").offset(5).build())
+            .await;
+    }
+}
@@ -0,0 +1,33 @@
+use crate::model::{hashtag::Hashtag, note::Tag};
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub tag: Tag,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Hashtag;
+    const ENDPOINT: &'static str = "hashtags/show";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client
+            .create_note(Some("#misskey_rs_hashtags_show"), None, None)
+            .await;
+        client
+            .test(Request {
+                tag: "misskey_rs_hashtags_show".into(),
+            })
+            .await;
+    }
+}
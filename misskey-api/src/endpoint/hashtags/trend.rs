@@ -0,0 +1,33 @@
+use crate::model::note::Tag;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendingHashtag {
+    pub tag: Tag,
+    /// The number of users who used the tag in each of the recent time windows, latest first.
+    pub chart: Vec<u64>,
+    pub users_count: u64,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<TrendingHashtag>;
+    const ENDPOINT: &'static str = "hashtags/trend";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client.test(Request::default()).await;
+    }
+}
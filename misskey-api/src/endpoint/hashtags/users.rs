@@ -0,0 +1,97 @@
+use crate::model::{
+    note::Tag,
+    sort::SortOrder,
+    user::{User, UserOrigin, UserSortKey},
+};
+
+use serde::Serialize;
+use thiserror::Error;
+use typed_builder::TypedBuilder;
+
+#[derive(Serialize, PartialEq, Eq, Clone, Debug, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum UserState {
+    All,
+    Alive,
+}
+
+#[derive(Debug, Error, Clone)]
+#[error("invalid user state")]
+pub struct ParseUserStateError {
+    _priv: (),
+}
+
+impl std::str::FromStr for UserState {
+    type Err = ParseUserStateError;
+
+    fn from_str(s: &str) -> Result<UserState, Self::Err> {
+        match s {
+            "all" | "All" => Ok(UserState::All),
+            "alive" | "Alive" => Ok(UserState::Alive),
+            _ => Err(ParseUserStateError { _priv: () }),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    #[builder(setter(into))]
+    pub tag: Tag,
+    /// 1 .. 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub limit: Option<u8>,
+    pub sort: SortOrder<UserSortKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub state: Option<UserState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub origin: Option<UserOrigin>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<User>;
+    const ENDPOINT: &'static str = "hashtags/users";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Request, UserState};
+    use crate::model::{
+        sort::SortOrder,
+        user::{UserOrigin, UserSortKey},
+    };
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client
+            .test(
+                Request::builder()
+                    .tag("misskey")
+                    .sort(SortOrder::Descending(UserSortKey::Follower))
+                    .build(),
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_with_options() {
+        let client = TestClient::new();
+        client
+            .test(
+                Request::builder()
+                    .tag("misskey")
+                    .limit(100)
+                    .sort(SortOrder::Ascending(UserSortKey::UpdatedAt))
+                    .state(UserState::Alive)
+                    .origin(UserOrigin::Local)
+                    .build(),
+            )
+            .await;
+    }
+}
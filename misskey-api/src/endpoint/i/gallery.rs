@@ -0,0 +1,2 @@
+pub mod likes;
+pub mod posts;
@@ -0,0 +1,77 @@
+use crate::model::{gallery::GalleryLike, id::Id};
+
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+
+#[derive(Serialize, Default, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    /// 1 .. 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub limit: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub since_id: Option<Id<GalleryLike>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default, setter(strip_option))]
+    pub until_id: Option<Id<GalleryLike>>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<GalleryLike>;
+    const ENDPOINT: &'static str = "i/gallery/likes";
+}
+
+impl_pagination!(Request, GalleryLike);
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client.test(Request::default()).await;
+    }
+
+    #[tokio::test]
+    async fn request_with_limit() {
+        let client = TestClient::new();
+        client.test(Request::builder().limit(100).build()).await;
+    }
+
+    #[tokio::test]
+    async fn request_paginate() {
+        let client = TestClient::new();
+        let url = client.admin.avatar_url().await;
+        let file = client.admin.upload_from_url(url).await;
+        let post = client
+            .admin
+            .test(
+                crate::endpoint::gallery::posts::create::Request::builder()
+                    .title("gallery post")
+                    .file_ids(vec![file.id])
+                    .build(),
+            )
+            .await;
+        client
+            .user
+            .test(crate::endpoint::gallery::posts::like::Request { post_id: post.id })
+            .await;
+
+        let likes = client.user.test(Request::default()).await;
+
+        client
+            .user
+            .test(
+                Request::builder()
+                    .since_id(likes[0].id)
+                    .until_id(likes[0].id)
+                    .build(),
+            )
+            .await;
+    }
+}
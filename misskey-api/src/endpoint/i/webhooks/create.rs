@@ -0,0 +1,65 @@
+use crate::model::webhook::{Webhook, WebhookEventType};
+
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+use url::Url;
+
+#[derive(Serialize, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    /// [ 1 .. 100 ] characters
+    #[builder(setter(into))]
+    pub name: String,
+    pub url: Url,
+    #[builder(setter(into))]
+    pub secret: String,
+    #[builder(default, setter(into))]
+    pub on: Vec<WebhookEventType>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = Webhook;
+    const ENDPOINT: &'static str = "i/webhooks/create";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::model::webhook::WebhookEventType;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client
+            .test(Request {
+                name: "test".to_string(),
+                url: "https://example.com/webhook".parse().unwrap(),
+                secret: "secret".to_string(),
+                on: vec![WebhookEventType::Note, WebhookEventType::Followed],
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn request_with_all_events() {
+        let client = TestClient::new();
+        client
+            .test(Request {
+                name: "test".to_string(),
+                url: "https://example.com/webhook".parse().unwrap(),
+                secret: "secret".to_string(),
+                on: vec![
+                    WebhookEventType::Note,
+                    WebhookEventType::Reply,
+                    WebhookEventType::Renote,
+                    WebhookEventType::Mention,
+                    WebhookEventType::Follow,
+                    WebhookEventType::Followed,
+                    WebhookEventType::Unfollow,
+                ],
+            })
+            .await;
+    }
+}
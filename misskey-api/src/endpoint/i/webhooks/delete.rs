@@ -0,0 +1,39 @@
+use crate::model::{id::Id, webhook::Webhook};
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub webhook_id: Id<Webhook>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "i/webhooks/delete";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let webhook = client
+            .test(crate::endpoint::i::webhooks::create::Request {
+                name: "test".to_string(),
+                url: "https://example.com/webhook".parse().unwrap(),
+                secret: "secret".to_string(),
+                on: Vec::new(),
+            })
+            .await;
+
+        client
+            .test(Request {
+                webhook_id: webhook.id,
+            })
+            .await;
+    }
+}
@@ -0,0 +1,33 @@
+use crate::model::webhook::Webhook;
+
+use serde::Serialize;
+
+#[derive(Serialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {}
+
+impl misskey_core::Request for Request {
+    type Response = Vec<Webhook>;
+    const ENDPOINT: &'static str = "i/webhooks/list";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        client
+            .test(crate::endpoint::i::webhooks::create::Request {
+                name: "test".to_string(),
+                url: "https://example.com/webhook".parse().unwrap(),
+                secret: "secret".to_string(),
+                on: Vec::new(),
+            })
+            .await;
+
+        client.test(Request::default()).await;
+    }
+}
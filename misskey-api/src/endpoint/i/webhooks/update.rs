@@ -0,0 +1,60 @@
+use crate::model::{
+    id::Id,
+    webhook::{Webhook, WebhookEventType},
+};
+
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+use url::Url;
+
+#[derive(Serialize, Debug, Clone, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(doc)]
+pub struct Request {
+    pub webhook_id: Id<Webhook>,
+    /// [ 1 .. 100 ] characters
+    #[builder(setter(into))]
+    pub name: String,
+    pub url: Url,
+    #[builder(setter(into))]
+    pub secret: String,
+    #[builder(setter(into))]
+    pub on: Vec<WebhookEventType>,
+    pub active: bool,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "i/webhooks/update";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::model::webhook::WebhookEventType;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let webhook = client
+            .test(crate::endpoint::i::webhooks::create::Request {
+                name: "test".to_string(),
+                url: "https://example.com/webhook".parse().unwrap(),
+                secret: "secret".to_string(),
+                on: Vec::new(),
+            })
+            .await;
+
+        client
+            .test(Request {
+                webhook_id: webhook.id,
+                name: "test2".to_string(),
+                url: "https://example.com/webhook2".parse().unwrap(),
+                secret: "secret2".to_string(),
+                on: vec![WebhookEventType::Mention],
+                active: false,
+            })
+            .await;
+    }
+}
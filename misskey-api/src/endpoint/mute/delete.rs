@@ -26,6 +26,8 @@ mod tests {
             .user
             .test(crate::endpoint::mute::create::Request {
                 user_id: user.id.clone(),
+                #[cfg(feature = "12-108-0")]
+                expires_at: None,
             })
             .await;
 
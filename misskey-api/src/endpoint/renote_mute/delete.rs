@@ -0,0 +1,32 @@
+use crate::model::{id::Id, user::User};
+
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub user_id: Id<User>,
+}
+
+impl misskey_core::Request for Request {
+    type Response = ();
+    const ENDPOINT: &'static str = "renote-mute/delete";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use crate::test::{ClientExt, TestClient};
+
+    #[tokio::test]
+    async fn request() {
+        let client = TestClient::new();
+        let (user, _) = client.admin.create_user().await;
+
+        client
+            .user
+            .test(crate::endpoint::renote_mute::create::Request { user_id: user.id })
+            .await;
+        client.user.test(Request { user_id: user.id }).await;
+    }
+}
@@ -2,6 +2,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 pub mod endpoint;
+pub mod mfm;
 pub mod model;
 pub mod streaming;
 
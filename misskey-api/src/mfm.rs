@@ -0,0 +1,783 @@
+//! MFM (Misskey Flavored Markdown), the markup language used in notes and profiles.
+//!
+//! Texts such as [`Note::text`][`crate::model::note::Note::text`],
+//! [`Note::cw`][`crate::model::note::Note::cw`] and
+//! [`User::description`][`crate::model::user::User::description`] are written in MFM.
+//! [`parse`] turns them into a tree of [`Node`]s in the same way as
+//! [mfm-js](https://github.com/misskey-dev/mfm.js), the parser used by Misskey.
+//! The tree can be rendered into HTML with [`HtmlRenderer`], or into plain text with
+//! [`to_plain_text`]. To compose MFM texts from user-supplied strings without accidentally
+//! creating mentions or breaking the formatting, use [`MfmBuilder`].
+//!
+//! ```
+//! use misskey_api::mfm::{self, Node};
+//!
+//! let nodes = mfm::parse("hello **@ai** #misskey");
+//! assert_eq!(
+//!     nodes,
+//!     vec![
+//!         Node::text("hello "),
+//!         Node::Bold {
+//!             children: vec![Node::Mention {
+//!                 username: "ai".to_string(),
+//!                 host: None,
+//!                 acct: "@ai".to_string(),
+//!             }],
+//!         },
+//!         Node::text(" "),
+//!         Node::Hashtag {
+//!             hashtag: "misskey".to_string(),
+//!         },
+//!     ]
+//! );
+//! ```
+
+use std::collections::BTreeMap;
+
+mod builder;
+mod emoji;
+mod parser;
+mod render;
+
+pub use builder::MfmBuilder;
+pub use render::{to_plain_text, HtmlRenderer, InstanceLinks, LinkBuilder};
+
+/// Maximum depth of the nested syntax that [`parse`] handles by default.
+///
+/// Syntax nested deeper than this is parsed as text, as Misskey does.
+pub const DEFAULT_NEST_LIMIT: usize = 20;
+
+/// A node of the MFM syntax tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Node {
+    /// Lines starting with `>`.
+    Quote {
+        children: Vec<Node>,
+    },
+    /// A line ending with `search` or `検索`, which is shown as a search box.
+    Search {
+        query: String,
+        /// The whole source text of the search box, including the button.
+        content: String,
+    },
+    /// Code fenced with `` ``` ``.
+    CodeBlock {
+        code: String,
+        lang: Option<String>,
+    },
+    /// `\[formula\]`
+    MathBlock {
+        formula: String,
+    },
+    /// `<center>...</center>`
+    Center {
+        children: Vec<Node>,
+    },
+    UnicodeEmoji {
+        emoji: String,
+    },
+    /// `:name:`, the custom emoji.
+    EmojiCode {
+        name: String,
+    },
+    /// `**...**`, `__...__` or `<b>...</b>`
+    Bold {
+        children: Vec<Node>,
+    },
+    /// `<small>...</small>`
+    Small {
+        children: Vec<Node>,
+    },
+    /// `*...*`, `_..._` or `<i>...</i>`
+    Italic {
+        children: Vec<Node>,
+    },
+    /// `~~...~~` or `<s>...</s>`
+    Strike {
+        children: Vec<Node>,
+    },
+    /// `` `code` ``
+    InlineCode {
+        code: String,
+    },
+    /// `\(formula\)`
+    MathInline {
+        formula: String,
+    },
+    /// `@username` or `@username@host`
+    Mention {
+        username: String,
+        host: Option<String>,
+        /// The mention as written in the text, such as `@username@host`.
+        acct: String,
+    },
+    /// `#hashtag`, where `hashtag` does not include `#`.
+    Hashtag {
+        hashtag: String,
+    },
+    /// A bare URL, or a URL enclosed in `<` and `>` when `brackets` is `true`.
+    Url {
+        url: String,
+        brackets: bool,
+    },
+    /// `[label](url)`, or `?[label](url)` when `silent` is `true`.
+    Link {
+        /// Whether to hide the preview of the URL.
+        silent: bool,
+        url: String,
+        children: Vec<Node>,
+    },
+    /// `$[name.arg1,arg2=value content]`, where the arguments without values are mapped to `None`.
+    ///
+    /// `***...***` is parsed as the `tada` function.
+    Fn {
+        name: String,
+        args: BTreeMap<String, Option<String>>,
+        children: Vec<Node>,
+    },
+    /// `<plain>...</plain>`, whose content is not parsed.
+    Plain {
+        text: String,
+    },
+    Text {
+        text: String,
+    },
+}
+
+impl Node {
+    /// Creates a text node.
+    pub fn text(text: impl Into<String>) -> Node {
+        Node::Text { text: text.into() }
+    }
+
+    /// Returns `true` if the node is a block, which is placed on its own lines.
+    pub fn is_block(&self) -> bool {
+        matches!(
+            self,
+            Node::Quote { .. }
+                | Node::Search { .. }
+                | Node::CodeBlock { .. }
+                | Node::MathBlock { .. }
+                | Node::Center { .. }
+        )
+    }
+
+    /// Returns the child nodes.
+    pub fn children(&self) -> &[Node] {
+        match self {
+            Node::Quote { children }
+            | Node::Center { children }
+            | Node::Bold { children }
+            | Node::Small { children }
+            | Node::Italic { children }
+            | Node::Strike { children }
+            | Node::Link { children, .. }
+            | Node::Fn { children, .. } => children,
+            _ => &[],
+        }
+    }
+}
+
+/// Parses the text in MFM.
+pub fn parse(text: &str) -> Vec<Node> {
+    parse_with_nest_limit(text, DEFAULT_NEST_LIMIT)
+}
+
+/// Parses the text in MFM, treating the syntax nested deeper than `nest_limit` as text.
+pub fn parse_with_nest_limit(text: &str, nest_limit: usize) -> Vec<Node> {
+    parser::Parser::new(text, nest_limit).parse_full()
+}
+
+/// Parses the text in MFM from the byte offset `pos`, looking back at the text before it only for
+/// the conditions such as the beginning of the line.
+fn parse_from(text: &str, pos: usize) -> Vec<Node> {
+    parser::Parser::new(text, DEFAULT_NEST_LIMIT).parse_full_from(pos)
+}
+
+/// Parses the text in the simple subset of MFM, which is used for user names.
+///
+/// Only [`Node::UnicodeEmoji`], [`Node::EmojiCode`], [`Node::Plain`] and [`Node::Text`] appear
+/// in the result.
+///
+/// ```
+/// use misskey_api::mfm::{self, Node};
+///
+/// assert_eq!(
+///     mfm::parse_simple("**Ai** :ai:"),
+///     vec![
+///         Node::text("**Ai** "),
+///         Node::EmojiCode {
+///             name: "ai".to_string()
+///         },
+///     ]
+/// );
+/// ```
+pub fn parse_simple(text: &str) -> Vec<Node> {
+    parser::Parser::new(text, DEFAULT_NEST_LIMIT).parse_simple()
+}
+
+/// Collects the nodes in the trees that satisfy the predicate, in the depth-first order.
+///
+/// ```
+/// use misskey_api::mfm::{self, Node};
+///
+/// let nodes = mfm::parse("@a\n<center>@b@example.com</center>");
+/// let mentions: Vec<_> = mfm::extract(&nodes, |node| matches!(node, Node::Mention { .. }))
+///     .into_iter()
+///     .filter_map(|node| match node {
+///         Node::Mention { acct, .. } => Some(acct.as_str()),
+///         _ => None,
+///     })
+///     .collect();
+/// assert_eq!(mentions, vec!["@a", "@b@example.com"]);
+/// ```
+pub fn extract<F>(nodes: &[Node], mut predicate: F) -> Vec<&Node>
+where
+    F: FnMut(&Node) -> bool,
+{
+    fn go<'a, F: FnMut(&Node) -> bool>(
+        nodes: &'a [Node],
+        predicate: &mut F,
+        acc: &mut Vec<&'a Node>,
+    ) {
+        for node in nodes {
+            if predicate(node) {
+                acc.push(node);
+            }
+            go(node.children(), predicate, acc);
+        }
+    }
+
+    let mut acc = Vec::new();
+    go(nodes, &mut predicate, &mut acc);
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, parse_simple, parse_with_nest_limit, Node};
+
+    fn text(text: &str) -> Node {
+        Node::text(text)
+    }
+
+    fn bold(children: Vec<Node>) -> Node {
+        Node::Bold { children }
+    }
+
+    fn mention(username: &str, host: Option<&str>, acct: &str) -> Node {
+        Node::Mention {
+            username: username.to_string(),
+            host: host.map(ToString::to_string),
+            acct: acct.to_string(),
+        }
+    }
+
+    fn hashtag(hashtag: &str) -> Node {
+        Node::Hashtag {
+            hashtag: hashtag.to_string(),
+        }
+    }
+
+    fn url(url: &str) -> Node {
+        Node::Url {
+            url: url.to_string(),
+            brackets: false,
+        }
+    }
+
+    fn emoji_code(name: &str) -> Node {
+        Node::EmojiCode {
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(parse_simple("abc"), vec![text("abc")]);
+        assert_eq!(
+            parse_simple("あ:bar:い"),
+            vec![text("あ"), emoji_code("bar"), text("い")]
+        );
+        assert_eq!(
+            parse_simple("@abc #abc https://example.com"),
+            vec![text("@abc #abc https://example.com")]
+        );
+        assert_eq!(
+            parse_simple("a<plain>:x:</plain>"),
+            vec![
+                text("a"),
+                Node::Plain {
+                    text: ":x:".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(parse(""), vec![]);
+        assert_eq!(parse("abc"), vec![text("abc")]);
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(
+            parse("> abc"),
+            vec![Node::Quote {
+                children: vec![text("abc")]
+            }]
+        );
+        assert_eq!(
+            parse("> abc\n>123"),
+            vec![Node::Quote {
+                children: vec![text("abc\n123")]
+            }]
+        );
+        assert_eq!(
+            parse(">> abc"),
+            vec![Node::Quote {
+                children: vec![Node::Quote {
+                    children: vec![text("abc")]
+                }]
+            }]
+        );
+        assert_eq!(
+            parse("before\n> abc\n\nafter"),
+            vec![
+                text("before"),
+                Node::Quote {
+                    children: vec![text("abc")]
+                },
+                text("after"),
+            ]
+        );
+        assert_eq!(parse(">"), vec![text(">")]);
+        assert_eq!(parse("a > b"), vec![text("a > b")]);
+    }
+
+    #[test]
+    fn test_search() {
+        for button in &["search", "Search", "検索", "[search]", "[検索]"] {
+            let input = format!("MFM 書き方 123 {}", button);
+            assert_eq!(
+                parse(&input),
+                vec![Node::Search {
+                    query: "MFM 書き方 123".to_string(),
+                    content: input.clone(),
+                }]
+            );
+        }
+        assert_eq!(
+            parse("abc\nhoge piyo bebeyo 検索\n123"),
+            vec![
+                text("abc"),
+                Node::Search {
+                    query: "hoge piyo bebeyo".to_string(),
+                    content: "hoge piyo bebeyo 検索".to_string(),
+                },
+                text("123"),
+            ]
+        );
+        assert_eq!(parse("search"), vec![text("search")]);
+    }
+
+    #[test]
+    fn test_code_block() {
+        assert_eq!(
+            parse("```\nabc\n```"),
+            vec![Node::CodeBlock {
+                code: "abc".to_string(),
+                lang: None,
+            }]
+        );
+        assert_eq!(
+            parse("```js\nconst a = 1;\n\nconst b = 2;\n```"),
+            vec![Node::CodeBlock {
+                code: "const a = 1;\n\nconst b = 2;".to_string(),
+                lang: Some("js".to_string()),
+            }]
+        );
+        assert_eq!(
+            parse("abc\n```\nconst abc = 1;\n```\n123"),
+            vec![
+                text("abc"),
+                Node::CodeBlock {
+                    code: "const abc = 1;".to_string(),
+                    lang: None,
+                },
+                text("123"),
+            ]
+        );
+        assert_eq!(
+            parse("```\naaa```bbb\n```"),
+            vec![Node::CodeBlock {
+                code: "aaa```bbb".to_string(),
+                lang: None,
+            }]
+        );
+        assert_eq!(parse("```\n```"), vec![text("```\n```")]);
+    }
+
+    #[test]
+    fn test_math_block() {
+        assert_eq!(
+            parse("\\[math1\\]"),
+            vec![Node::MathBlock {
+                formula: "math1".to_string()
+            }]
+        );
+        assert_eq!(
+            parse("\\[\nmath1\n\\]"),
+            vec![Node::MathBlock {
+                formula: "math1".to_string()
+            }]
+        );
+        assert_eq!(parse("abc\\[math1\\]"), vec![text("abc\\[math1\\]")]);
+        assert_eq!(parse("\\[math1\\]abc"), vec![text("\\[math1\\]abc")]);
+    }
+
+    #[test]
+    fn test_center() {
+        assert_eq!(
+            parse("<center>abc</center>"),
+            vec![Node::Center {
+                children: vec![text("abc")]
+            }]
+        );
+        assert_eq!(
+            parse("abc\n<center>\n**abc**\n</center>\n123"),
+            vec![
+                text("abc"),
+                Node::Center {
+                    children: vec![bold(vec![text("abc")])]
+                },
+                text("123"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_emoji() {
+        assert_eq!(parse(":cat:"), vec![emoji_code("cat")]);
+        assert_eq!(
+            parse("a :cat: b"),
+            vec![text("a "), emoji_code("cat"), text(" b")]
+        );
+        assert_eq!(parse("a:cat:b"), vec![text("a:cat:b")]);
+        assert_eq!(parse("10:30:00"), vec![text("10:30:00")]);
+        assert_eq!(
+            parse("今起きた😇"),
+            vec![
+                text("今起きた"),
+                Node::UnicodeEmoji {
+                    emoji: "😇".to_string()
+                }
+            ]
+        );
+        for emoji in &["👍🏽", "👨‍👩‍👧", "🇯🇵", "❤️", "#️⃣", "🏴󠁧󠁢󠁥󠁮󠁧󠁿"]
+        {
+            assert_eq!(
+                parse(emoji),
+                vec![Node::UnicodeEmoji {
+                    emoji: emoji.to_string()
+                }]
+            );
+        }
+        assert_eq!(parse("1©"), vec![text("1©")]);
+    }
+
+    #[test]
+    fn test_bold_and_italic() {
+        assert_eq!(parse("**bold**"), vec![bold(vec![text("bold")])]);
+        assert_eq!(parse("**123@abc**"), vec![bold(vec![text("123@abc")])]);
+        assert_eq!(
+            parse("**@abc**"),
+            vec![bold(vec![mention("abc", None, "@abc")])]
+        );
+        assert_eq!(parse("**abc"), vec![text("**abc")]);
+        assert_eq!(parse("<b>abc</b>"), vec![bold(vec![text("abc")])]);
+        assert_eq!(parse("__abc__"), vec![bold(vec![text("abc")])]);
+        assert_eq!(parse("__a**b__"), vec![text("__a**b__")]);
+        assert_eq!(
+            parse("*abc*"),
+            vec![Node::Italic {
+                children: vec![text("abc")]
+            }]
+        );
+        assert_eq!(parse("before*abc*"), vec![text("before*abc*")]);
+        assert_eq!(
+            parse("あいう*abc*"),
+            vec![
+                text("あいう"),
+                Node::Italic {
+                    children: vec![text("abc")]
+                }
+            ]
+        );
+        assert_eq!(parse("snake_case_word"), vec![text("snake_case_word")]);
+    }
+
+    #[test]
+    fn test_big_and_strike() {
+        assert_eq!(
+            parse("***abc***"),
+            vec![Node::Fn {
+                name: "tada".to_string(),
+                args: Default::default(),
+                children: vec![text("abc")],
+            }]
+        );
+        assert_eq!(
+            parse("~~foo~~"),
+            vec![Node::Strike {
+                children: vec![text("foo")]
+            }]
+        );
+        assert_eq!(parse("~~foo\nbar~~"), vec![text("~~foo\nbar~~")]);
+    }
+
+    #[test]
+    fn test_inline_code_and_math() {
+        assert_eq!(
+            parse("`var x = \"Strawberry Pasta\";`"),
+            vec![Node::InlineCode {
+                code: "var x = \"Strawberry Pasta\";".to_string()
+            }]
+        );
+        assert_eq!(parse("`foo\nbar`"), vec![text("`foo\nbar`")]);
+        assert_eq!(parse("`foo´bar`"), vec![text("`foo´bar`")]);
+        assert_eq!(
+            parse("\\(x = 2\\)"),
+            vec![Node::MathInline {
+                formula: "x = 2".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_mention() {
+        assert_eq!(parse("@abc"), vec![mention("abc", None, "@abc")]);
+        assert_eq!(
+            parse("before @abc after"),
+            vec![
+                text("before "),
+                mention("abc", None, "@abc"),
+                text(" after")
+            ]
+        );
+        assert_eq!(
+            parse("@abc@misskey.io"),
+            vec![mention("abc", Some("misskey.io"), "@abc@misskey.io")]
+        );
+        assert_eq!(parse("abc@example.com"), vec![text("abc@example.com")]);
+        assert_eq!(
+            parse("@abc-"),
+            vec![mention("abc", None, "@abc"), text("-")]
+        );
+        assert_eq!(
+            parse("@abc@example.com."),
+            vec![
+                mention("abc", Some("example.com"), "@abc@example.com"),
+                text(".")
+            ]
+        );
+        assert_eq!(parse("@-abc"), vec![text("@-abc")]);
+        assert_eq!(parse("@abc-@example.com"), vec![text("@abc-@example.com")]);
+        assert_eq!(parse("@abc@.example.com"), vec![text("@abc@.example.com")]);
+        assert_eq!(
+            parse("@abc@"),
+            vec![mention("abc", None, "@abc"), text("@")]
+        );
+    }
+
+    #[test]
+    fn test_hashtag() {
+        assert_eq!(parse("#abc"), vec![hashtag("abc")]);
+        assert_eq!(
+            parse("before #abc after"),
+            vec![text("before "), hashtag("abc"), text(" after")]
+        );
+        assert_eq!(
+            parse("#abc, #def."),
+            vec![hashtag("abc"), text(", "), hashtag("def"), text(".")]
+        );
+        assert_eq!(parse("abc#abc"), vec![text("abc#abc")]);
+        assert_eq!(parse("#123"), vec![text("#123")]);
+        assert_eq!(parse("(#foo)"), vec![text("("), hashtag("foo"), text(")")]);
+        assert_eq!(parse("#foo(bar)"), vec![hashtag("foo(bar)")]);
+        assert_eq!(parse("#foo(bar"), vec![hashtag("foo"), text("(bar")]);
+        assert_eq!(parse("#「foo」"), vec![hashtag("「foo」")]);
+        assert_eq!(parse("#foo\nbar"), vec![hashtag("foo"), text("\nbar")]);
+    }
+
+    #[test]
+    fn test_url() {
+        assert_eq!(
+            parse("https://misskey.io/@ai"),
+            vec![url("https://misskey.io/@ai")]
+        );
+        assert_eq!(
+            parse("official instance: https://misskey.io/@ai."),
+            vec![
+                text("official instance: "),
+                url("https://misskey.io/@ai"),
+                text(".")
+            ]
+        );
+        assert_eq!(
+            parse("https://example.com/foo(bar)"),
+            vec![url("https://example.com/foo(bar)")]
+        );
+        assert_eq!(
+            parse("(https://example.com/foo)"),
+            vec![text("("), url("https://example.com/foo"), text(")")]
+        );
+        assert_eq!(parse("https://."), vec![text("https://.")]);
+        assert_eq!(
+            parse("<https://example.com/日本語>"),
+            vec![Node::Url {
+                url: "https://example.com/日本語".to_string(),
+                brackets: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_link() {
+        assert_eq!(
+            parse("[official instance](https://misskey.io/@ai)."),
+            vec![
+                Node::Link {
+                    silent: false,
+                    url: "https://misskey.io/@ai".to_string(),
+                    children: vec![text("official instance")],
+                },
+                text(".")
+            ]
+        );
+        assert_eq!(
+            parse("?[official instance](https://misskey.io/@ai)"),
+            vec![Node::Link {
+                silent: true,
+                url: "https://misskey.io/@ai".to_string(),
+                children: vec![text("official instance")],
+            }]
+        );
+        assert_eq!(
+            parse("[**@ai** #a](<https://example.com>)"),
+            vec![Node::Link {
+                silent: false,
+                url: "https://example.com".to_string(),
+                children: vec![bold(vec![text("@ai")]), text(" #a")],
+            }]
+        );
+        assert_eq!(
+            parse("[https://a.example](https://b.example)"),
+            vec![Node::Link {
+                silent: false,
+                url: "https://b.example".to_string(),
+                children: vec![text("https://a.example")],
+            }]
+        );
+        assert_eq!(parse("[a](b)"), vec![text("[a](b)")]);
+    }
+
+    #[test]
+    fn test_link_unclosed() {
+        // labels used to be scanned again from each bracket, which took seconds here
+        let start = std::time::Instant::now();
+        for source in ["?[".repeat(20000), "[".repeat(20000) + "](a)"] {
+            assert_eq!(parse(&source), vec![text(&source)]);
+        }
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+
+        assert_eq!(
+            parse("[a [b](https://example.com)"),
+            vec![Node::Link {
+                silent: false,
+                url: "https://example.com".to_string(),
+                children: vec![text("a [b")],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fn() {
+        assert_eq!(
+            parse("$[tada abc]"),
+            vec![Node::Fn {
+                name: "tada".to_string(),
+                args: Default::default(),
+                children: vec![text("abc")],
+            }]
+        );
+        assert_eq!(
+            parse("$[spin.speed=1.5s,x abc]"),
+            vec![Node::Fn {
+                name: "spin".to_string(),
+                args: vec![
+                    ("speed".to_string(), Some("1.5s".to_string())),
+                    ("x".to_string(), None),
+                ]
+                .into_iter()
+                .collect(),
+                children: vec![text("abc")],
+            }]
+        );
+        assert_eq!(
+            parse("$[flip $[x2 :cat:]]"),
+            vec![Node::Fn {
+                name: "flip".to_string(),
+                args: Default::default(),
+                children: vec![Node::Fn {
+                    name: "x2".to_string(),
+                    args: Default::default(),
+                    children: vec![emoji_code("cat")],
+                }],
+            }]
+        );
+        assert_eq!(parse("$[tada abc"), vec![text("$[tada abc")]);
+        assert_eq!(parse("$[tada]"), vec![text("$[tada]")]);
+    }
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(
+            parse("a\n<plain>\n**Hello** world\n</plain>\nb"),
+            vec![
+                text("a\n"),
+                Node::Plain {
+                    text: "**Hello** world".to_string()
+                },
+                text("\nb")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nest_limit() {
+        assert_eq!(
+            parse_with_nest_limit("**a *b* c**", 2),
+            vec![bold(vec![
+                text("a "),
+                Node::Italic {
+                    children: vec![text("b")]
+                },
+                text(" c")
+            ])]
+        );
+        assert_eq!(
+            parse_with_nest_limit("<b><b>**@abc**</b></b>", 2),
+            vec![bold(vec![bold(vec![bold(vec![text("@abc")])])])]
+        );
+        assert_eq!(
+            parse_with_nest_limit("<b>#a(b(c(d)))</b>", 2),
+            vec![bold(vec![hashtag("a"), text("(b(c(d)))")])]
+        );
+        assert_eq!(
+            parse("<b>#a(b(c(d)))</b>"),
+            vec![bold(vec![hashtag("a(b(c(d)))")])]
+        );
+    }
+}
@@ -0,0 +1,735 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use super::{parse, parse_from, to_plain_text, Node};
+use crate::model::{note::Tag, user::User};
+
+use url::Url;
+
+const ZERO_WIDTH_SPACE: &str = "\u{200b}";
+
+/// Separators tried between the text and the following node, in order of preference.
+///
+/// The newlines are consumed by the blocks, and the zero-width space keeps the nodes such as
+/// `@a` and `@b` from being merged into `@a@b`.
+const SEPARATORS: [&str; 5] = ["", ZERO_WIDTH_SPACE, "\n", "\n\n", "\n\n\n"];
+
+/// Builder for MFM texts, which escapes the texts so that they are not parsed as MFM syntax.
+///
+/// The result is guaranteed to be parsed by [`parse`] into the nodes pushed to the builder.
+/// Texts are written as they are where possible, and are otherwise escaped with `<plain>`.
+/// A node which cannot be written at the position, such as a link whose label contains
+/// a mention, is written as its plain text (see [`to_plain_text`]).
+///
+/// Separators are inserted between the pieces where they are needed:
+///
+/// - Blocks such as [`center`][`MfmBuilder::center`] and [`quote`][`MfmBuilder::quote`] are put
+///   on their own lines with up to three newlines, which are consumed by the blocks and do not
+///   appear in the parsed nodes.
+/// - A zero-width space is inserted between the nodes which would be merged otherwise, such as
+///   the mentions `@a` and `@b`. This appears as a text node in the parsed nodes, and remains
+///   in the text when it is copied.
+///
+/// Each push checks the result by parsing the text written since the previous node, and
+/// [`build`][`MfmBuilder::build`] checks the whole text once more. In the rare case where
+/// the whole text is not parsed as intended, the text is built again checking the whole text on
+/// each push, which takes time quadratic in the length.
+///
+/// The result can be passed to the builders taking MFM texts, such as `NoteBuilder::text`
+/// in `misskey-util`.
+///
+/// ```
+/// use misskey_api::mfm::{self, MfmBuilder};
+///
+/// let name = "**@everyone** #spam";
+/// let text = MfmBuilder::new()
+///     .text("Hello, ")
+///     .bold(MfmBuilder::new().text(name))
+///     .text("!")
+///     .emoji("wave")
+///     .build();
+/// assert_eq!(text, "Hello, <b><plain>**@everyone** #spam</plain></b>!:wave:");
+/// assert_eq!(
+///     mfm::to_plain_text(&mfm::parse(&text)),
+///     "Hello, **@everyone** #spam!:wave:"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MfmBuilder {
+    /// The operations pushed to the builder, which are replayed in [`MfmBuilder::render`].
+    ops: Vec<Op>,
+    state: State,
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    Text(String),
+    /// A node with its forms in order of preference.
+    Node(Vec<(String, Node)>),
+    /// A node enclosing the content in one of `forms`, whose children are replaced with
+    /// the nodes of the content.
+    Nested {
+        forms: Vec<(String, String)>,
+        content: Box<MfmBuilder>,
+        /// Whether to prefix the lines of the content with `> `.
+        quote: bool,
+        node: Node,
+    },
+}
+
+/// The nodes written so far.
+#[derive(Debug, Clone, Default)]
+struct State {
+    /// The nodes written in `source`.
+    nodes: Vec<Node>,
+    source: String,
+    /// The text following `source`, which is written together with the next node.
+    text: String,
+    /// The positions in `source` and `nodes` where the last node starts, from which the next
+    /// node is checked.
+    last: (usize, usize),
+    /// Whether to check the whole source on each write.
+    thorough: bool,
+}
+
+impl MfmBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        MfmBuilder::default()
+    }
+
+    /// Returns `true` if nothing is pushed to the builder.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Pushes the text, which is escaped if it contains MFM syntax.
+    pub fn text(self, text: impl AsRef<str>) -> Self {
+        self.push(Op::Text(text.as_ref().to_string()))
+    }
+
+    /// Pushes the mention of the user.
+    pub fn mention(self, user: &User) -> Self {
+        self.mention_acct(&user.username, user.host.as_deref())
+    }
+
+    /// Pushes the mention of the user with the username and the host.
+    pub fn mention_acct(self, username: &str, host: Option<&str>) -> Self {
+        let acct = match host {
+            Some(host) => format!("@{}@{}", username, host),
+            None => format!("@{}", username),
+        };
+        let node = Node::Mention {
+            username: username.to_string(),
+            host: host.map(ToString::to_string),
+            acct: acct.clone(),
+        };
+        self.push(Op::Node(vec![(acct, node)]))
+    }
+
+    /// Pushes the hashtag.
+    pub fn hashtag(self, tag: impl Into<Tag>) -> Self {
+        let tag = tag.into();
+        let hashtag = tag.0.strip_prefix('#').unwrap_or(&tag.0).to_string();
+        let source = format!("#{}", hashtag);
+        self.push(Op::Node(vec![(source, Node::Hashtag { hashtag })]))
+    }
+
+    /// Pushes the custom emoji with the name.
+    pub fn emoji(self, name: &str) -> Self {
+        let name = name.trim_matches(':');
+        let node = Node::EmojiCode {
+            name: name.to_string(),
+        };
+        self.push(Op::Node(vec![(format!(":{}:", name), node)]))
+    }
+
+    /// Pushes the URL.
+    pub fn url(self, url: &Url) -> Self {
+        let url = url.as_str();
+        let bare = Node::Url {
+            url: url.to_string(),
+            brackets: false,
+        };
+        let brackets = Node::Url {
+            url: url.to_string(),
+            brackets: true,
+        };
+        self.push(Op::Node(vec![
+            (url.to_string(), bare),
+            (format!("<{}>", url), brackets),
+        ]))
+    }
+
+    /// Pushes the link to the URL with the label.
+    pub fn link(self, url: &Url, label: MfmBuilder) -> Self {
+        self.push_link(false, url, label)
+    }
+
+    /// Pushes the link to the URL with the label, without the preview of the URL.
+    pub fn silent_link(self, url: &Url, label: MfmBuilder) -> Self {
+        self.push_link(true, url, label)
+    }
+
+    fn push_link(self, silent: bool, url: &Url, label: MfmBuilder) -> Self {
+        let open = if silent { "?[" } else { "[" };
+        let node = Node::Link {
+            silent,
+            url: url.to_string(),
+            children: Vec::new(),
+        };
+        self.push(Op::Nested {
+            forms: vec![
+                (open.to_string(), format!("]({})", url)),
+                (open.to_string(), format!("](<{}>)", url)),
+            ],
+            content: Box::new(label),
+            quote: false,
+            node,
+        })
+    }
+
+    /// Pushes the content in bold.
+    pub fn bold(self, content: MfmBuilder) -> Self {
+        self.push_nested(
+            "<b>",
+            "</b>",
+            content,
+            Node::Bold {
+                children: Vec::new(),
+            },
+        )
+    }
+
+    /// Pushes the content in italic.
+    pub fn italic(self, content: MfmBuilder) -> Self {
+        self.push_nested(
+            "<i>",
+            "</i>",
+            content,
+            Node::Italic {
+                children: Vec::new(),
+            },
+        )
+    }
+
+    /// Pushes the content in small letters.
+    pub fn small(self, content: MfmBuilder) -> Self {
+        self.push_nested(
+            "<small>",
+            "</small>",
+            content,
+            Node::Small {
+                children: Vec::new(),
+            },
+        )
+    }
+
+    /// Pushes the content with a strikethrough.
+    pub fn strike(self, content: MfmBuilder) -> Self {
+        self.push_nested(
+            "<s>",
+            "</s>",
+            content,
+            Node::Strike {
+                children: Vec::new(),
+            },
+        )
+    }
+
+    /// Pushes the content centered on its own lines.
+    pub fn center(self, content: MfmBuilder) -> Self {
+        let node = Node::Center {
+            children: Vec::new(),
+        };
+        self.push_nested("<center>\n", "\n</center>", content, node)
+    }
+
+    /// Pushes the content quoted on its own lines.
+    pub fn quote(self, content: MfmBuilder) -> Self {
+        self.push(Op::Nested {
+            forms: vec![(String::new(), String::new())],
+            content: Box::new(content),
+            quote: true,
+            node: Node::Quote {
+                children: Vec::new(),
+            },
+        })
+    }
+
+    /// Pushes the inline code.
+    pub fn code(self, code: &str) -> Self {
+        let node = Node::InlineCode {
+            code: code.to_string(),
+        };
+        self.push(Op::Node(vec![(format!("`{}`", code), node)]))
+    }
+
+    /// Pushes the code block, with the name of the language for the syntax highlighting.
+    pub fn code_block(self, code: &str, lang: Option<&str>) -> Self {
+        let source = format!("```{}\n{}\n```", lang.unwrap_or_default(), code);
+        let node = Node::CodeBlock {
+            code: code.to_string(),
+            lang: lang.map(ToString::to_string),
+        };
+        self.push(Op::Node(vec![(source, node)]))
+    }
+
+    /// Pushes the content with the function, such as `spin` and `x2`.
+    pub fn func(self, name: &str, content: MfmBuilder) -> Self {
+        self.func_with_args(name, Vec::<(String, Option<String>)>::new(), content)
+    }
+
+    /// Pushes the content with the function and the arguments,
+    /// where the arguments without values are given as `None`.
+    pub fn func_with_args<I, K, V>(self, name: &str, args: I, content: MfmBuilder) -> Self
+    where
+        I: IntoIterator<Item = (K, Option<V>)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let args: BTreeMap<String, Option<String>> = args
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.map(Into::into)))
+            .collect();
+        let mut open = format!("$[{}", name);
+        for (i, (key, value)) in args.iter().enumerate() {
+            open.push(if i == 0 { '.' } else { ',' });
+            open.push_str(key);
+            if let Some(value) = value {
+                open.push('=');
+                open.push_str(value);
+            }
+        }
+        open.push(' ');
+        let node = Node::Fn {
+            name: name.to_string(),
+            args,
+            children: Vec::new(),
+        };
+        self.push_nested(&open, "]", content, node)
+    }
+
+    /// Returns the MFM text.
+    pub fn build(&self) -> String {
+        self.render(false).0
+    }
+
+    fn push_nested(self, open: &str, close: &str, content: MfmBuilder, node: Node) -> Self {
+        self.push(Op::Nested {
+            forms: vec![(open.to_string(), close.to_string())],
+            content: Box::new(content),
+            quote: false,
+            node,
+        })
+    }
+
+    fn push(mut self, op: Op) -> Self {
+        self.state.apply(&op, false);
+        self.ops.push(op);
+        self
+    }
+
+    /// Returns the source text and the nodes.
+    ///
+    /// If `strict` is `true`, all the texts are escaped, so that the result can be placed
+    /// in contexts such as the content of functions, where `]` in raw texts closes the function.
+    fn render(&self, strict: bool) -> (String, Vec<Node>) {
+        let (source, nodes) = if strict {
+            self.replay(true, false)
+        } else {
+            self.state.finish(false)
+        };
+        // each write only checks the source from the previous node, and the nodes before it may
+        // extend beyond that
+        if round_trips(&source, &nodes) {
+            return (source, nodes);
+        }
+        let (source, nodes) = self.replay(strict, true);
+        if strict || round_trips(&source, &nodes) {
+            return (source, nodes);
+        }
+        // raw texts can still be combined with the trailing text, such as a pair of "`"
+        self.replay(true, true)
+    }
+
+    fn replay(&self, strict: bool, thorough: bool) -> (String, Vec<Node>) {
+        let mut state = State {
+            thorough,
+            ..State::default()
+        };
+        for op in &self.ops {
+            state.apply(op, strict);
+        }
+        state.finish(strict)
+    }
+}
+
+impl State {
+    fn apply(&mut self, op: &Op, strict: bool) {
+        match op {
+            Op::Text(text) => self.text.push_str(text),
+            Op::Node(forms) => self.write(forms, strict),
+            Op::Nested {
+                forms,
+                content,
+                quote,
+                node,
+            } => {
+                let mut contents = vec![content.render(true)];
+                if !strict {
+                    contents.insert(0, content.render(false));
+                }
+                let mut candidates = Vec::new();
+                for (source, children) in contents {
+                    let mut node = node.clone();
+                    if let Some(node_children) = children_mut(&mut node) {
+                        *node_children = children;
+                    }
+                    let source = if *quote {
+                        let lines: Vec<_> =
+                            source.split('\n').map(|l| format!("> {}", l)).collect();
+                        lines.join("\n")
+                    } else {
+                        source
+                    };
+                    for (open, close) in forms {
+                        candidates.push((format!("{}{}{}", open, source, close), node.clone()));
+                    }
+                }
+                self.write(&candidates, strict);
+            }
+        }
+    }
+
+    /// Writes the node in the first of `forms` that is parsed as intended,
+    /// or pushes its plain text if none of them is.
+    ///
+    /// Only the source from the start of the last node is parsed, unless `thorough` is set.
+    fn write(&mut self, forms: &[(String, Node)], strict: bool) {
+        let (start, start_node) = if self.thorough { (0, 0) } else { self.last };
+        // the parser looks back at the preceding character
+        let context = self.source[..start]
+            .chars()
+            .next_back()
+            .map_or(0, char::len_utf8);
+        let prefix = &self.source[start - context..];
+        let mut nodes = self.nodes[start_node..].to_vec();
+        if !self.text.is_empty() {
+            nodes.push(Node::text(self.text.as_str()));
+        }
+        for text in self.text_forms(strict) {
+            for &separator in &SEPARATORS {
+                for (form, node) in forms {
+                    let written = format!("{}{}{}", text, separator, form);
+                    let source = format!("{}{}", prefix, written);
+                    let mut expected = nodes.clone();
+                    if separator == ZERO_WIDTH_SPACE {
+                        expected.push(Node::text(ZERO_WIDTH_SPACE));
+                    }
+                    expected.push(node.clone());
+                    if normalize(&parse_from(&source, context)) == normalize(&expected) {
+                        let written_nodes = expected.split_off(self.nodes.len() - start_node);
+                        self.source.push_str(&written);
+                        self.nodes.extend(written_nodes);
+                        self.last = (self.source.len() - form.len(), self.nodes.len() - 1);
+                        self.text.clear();
+                        return;
+                    }
+                }
+            }
+        }
+        let fallback = to_plain_text(std::slice::from_ref(&forms[0].1));
+        self.text.push_str(&fallback);
+    }
+
+    /// Returns the source text and the nodes, including the trailing text.
+    fn finish(&self, strict: bool) -> (String, Vec<Node>) {
+        if self.text.is_empty() {
+            return (self.source.clone(), self.nodes.clone());
+        }
+        let mut nodes = self.nodes.clone();
+        nodes.push(Node::text(self.text.as_str()));
+        let source = self
+            .text_forms(strict)
+            .into_iter()
+            .map(|text| format!("{}{}", self.source, text))
+            .find(|source| round_trips(source, &nodes))
+            // escaped texts are parsed as intended after the newlines consumed by blocks, unless
+            // the raw texts before them are combined with them, which is left to `render`
+            .unwrap_or_else(|| format!("{}\n{}", self.source, escape(&self.text)));
+        (source, nodes)
+    }
+
+    /// Returns the forms of the trailing text, preceded by the newlines consumed by blocks.
+    fn text_forms(&self, strict: bool) -> Vec<String> {
+        if self.text.is_empty() {
+            return vec![String::new()];
+        }
+        let escaped = escape(&self.text);
+        let mut forms = Vec::new();
+        for newlines in ["", "\n", "\n\n"] {
+            if !strict {
+                forms.push(format!("{}{}", newlines, self.text));
+            }
+            forms.push(format!("{}{}", newlines, escaped));
+        }
+        forms
+    }
+}
+
+impl Display for MfmBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.build())
+    }
+}
+
+impl From<MfmBuilder> for String {
+    fn from(builder: MfmBuilder) -> String {
+        builder.build()
+    }
+}
+
+/// Wraps the lines of the text in `<plain>`, leaving the newlines as they are.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            escaped.push('\n');
+        }
+        for (j, line) in line.split('\r').enumerate() {
+            if j > 0 {
+                escaped.push('\r');
+            }
+            if !line.is_empty() {
+                // `</plain>` is split into two plain tags
+                let line = line.replace("</plain>", "</pl</plain><plain>ain>");
+                escaped.push_str("<plain>");
+                escaped.push_str(&line);
+                escaped.push_str("</plain>");
+            }
+        }
+    }
+    escaped
+}
+
+fn round_trips(source: &str, expected: &[Node]) -> bool {
+    normalize(&parse(source)) == normalize(expected)
+}
+
+/// Converts the nodes into the form where the texts, the plain tags and the Unicode emojis
+/// are merged into single text nodes.
+fn normalize(nodes: &[Node]) -> Vec<Node> {
+    let mut normalized: Vec<Node> = Vec::new();
+    for node in nodes {
+        let text = match node {
+            Node::Text { text } | Node::Plain { text } => text,
+            Node::UnicodeEmoji { emoji } => emoji,
+            _ => {
+                let mut node = node.clone();
+                if let Some(children) = children_mut(&mut node) {
+                    *children = normalize(children);
+                }
+                normalized.push(node);
+                continue;
+            }
+        };
+        if text.is_empty() {
+            continue;
+        }
+        match normalized.last_mut() {
+            Some(Node::Text { text: last }) => last.push_str(text),
+            _ => normalized.push(Node::text(text.as_str())),
+        }
+    }
+    normalized
+}
+
+fn children_mut(node: &mut Node) -> Option<&mut Vec<Node>> {
+    match node {
+        Node::Quote { children }
+        | Node::Center { children }
+        | Node::Bold { children }
+        | Node::Small { children }
+        | Node::Italic { children }
+        | Node::Strike { children }
+        | Node::Link { children, .. }
+        | Node::Fn { children, .. } => Some(children),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize, MfmBuilder};
+    use crate::mfm::{parse, Node};
+
+    use url::Url;
+
+    fn assert_round_trip(builder: &MfmBuilder) {
+        let (source, nodes) = builder.render(false);
+        assert_eq!(
+            normalize(&parse(&source)),
+            normalize(&nodes),
+            "{:?}",
+            source
+        );
+    }
+
+    #[test]
+    fn test_text() {
+        let builder = MfmBuilder::new().text("hello, world");
+        assert_eq!(builder.build(), "hello, world");
+        assert_round_trip(&builder);
+    }
+
+    #[test]
+    fn test_escape() {
+        for text in [
+            "@ai",
+            "#misskey",
+            ":ai:",
+            "**bold** and *italic*",
+            "> quote",
+            "misskey search",
+            "https://example.com",
+            "$[x2 big]",
+            "<plain>a</plain>",
+            "\n```\ncode\n```\n",
+            "a\r\nb\rc\n\n",
+        ] {
+            let builder = MfmBuilder::new().text(text);
+            assert_eq!(normalize(&parse(&builder.build())), vec![Node::text(text)]);
+            assert_round_trip(&builder);
+        }
+    }
+
+    #[test]
+    fn test_adjacent_nodes() {
+        let builder = MfmBuilder::new()
+            .text("a")
+            .mention_acct("b", None)
+            .mention_acct("c", Some("example.com"))
+            .text("d")
+            .hashtag("e")
+            .emoji("f")
+            .text("g");
+        assert_eq!(
+            builder.build(),
+            "a\u{200b}@b\u{200b}@c@example.com<plain>d</plain>#e:f:g"
+        );
+        assert_round_trip(&builder);
+    }
+
+    #[test]
+    fn test_blocks() {
+        let builder = MfmBuilder::new()
+            .text("a\n")
+            .quote(
+                MfmBuilder::new()
+                    .text("b\nc")
+                    .bold(MfmBuilder::new().text("d")),
+            )
+            .text("\ne")
+            .code_block("fn main() {}", Some("rust"))
+            .center(MfmBuilder::new().text("f"));
+        assert_eq!(
+            builder.build(),
+            "a\n\n\n> b\n> c<b>d</b>\n\n\ne\n```rust\nfn main() {}\n```\n<center>\nf\n</center>"
+        );
+        assert_round_trip(&builder);
+    }
+
+    #[test]
+    fn test_link() {
+        let url = Url::parse("https://example.com/a(b)").unwrap();
+        let builder = MfmBuilder::new()
+            .url(&url)
+            .text(".")
+            .link(&url, MfmBuilder::new().text("[label]"))
+            .silent_link(&url, MfmBuilder::new().mention_acct("a", None));
+        assert_eq!(
+            parse(&builder.build()),
+            vec![
+                Node::Url {
+                    url: "https://example.com/a(b)".to_string(),
+                    brackets: false,
+                },
+                Node::text("."),
+                Node::Link {
+                    silent: false,
+                    url: "https://example.com/a(b)".to_string(),
+                    children: vec![Node::Plain {
+                        text: "[label]".to_string()
+                    }],
+                },
+                Node::Plain {
+                    text: "@a (https://example.com/a(b))".to_string()
+                },
+            ]
+        );
+        assert_round_trip(&builder);
+    }
+
+    #[test]
+    fn test_fallback() {
+        let builder = MfmBuilder::new()
+            .code("a`b")
+            .bold(MfmBuilder::new().quote(MfmBuilder::new().text("c")))
+            .hashtag("123");
+        assert_eq!(
+            normalize(&parse(&builder.build())),
+            vec![Node::text("a`b> c#123")]
+        );
+        assert_round_trip(&builder);
+    }
+
+    #[test]
+    fn test_func() {
+        let builder = MfmBuilder::new()
+            .func("spin", MfmBuilder::new().text("a]"))
+            .func_with_args(
+                "flip",
+                vec![("h", None), ("speed", Some("1s"))],
+                MfmBuilder::new().emoji("ai"),
+            );
+        assert_eq!(
+            builder.build(),
+            "$[spin <plain>a]</plain>]$[flip.h,speed=1s :ai:]"
+        );
+        assert_round_trip(&builder);
+    }
+
+    #[test]
+    fn test_merged_across_nodes() {
+        // `<https://...>` is a URL only in the whole text, since the nodes are checked one by one
+        let url = Url::parse("https://example.com").unwrap();
+        let builder = MfmBuilder::new().text("<").url(&url).url(&url);
+        assert_eq!(
+            builder.build(),
+            "<https://example.com/\u{200b}https://example.com/"
+        );
+        assert_round_trip(&builder);
+
+        // the trailing "`" encloses the nodes with the preceding one in any form
+        let builder = MfmBuilder::new().emoji("a").text("`").emoji("b").text("`");
+        assert_eq!(builder.build(), ":a:<plain>`</plain>:b:<plain>`</plain>");
+        assert_round_trip(&builder);
+    }
+
+    #[test]
+    fn test_many_pushes() {
+        // the whole text used to be parsed on each push, which took seconds here
+        let start = std::time::Instant::now();
+        let mut builder = MfmBuilder::new();
+        for i in 0..2000 {
+            builder = builder
+                .text(format!("{} ", i))
+                .mention_acct("a", None)
+                .bold(MfmBuilder::new().hashtag("b"));
+        }
+        let (source, nodes) = builder.render(false);
+        assert!(start.elapsed() < std::time::Duration::from_secs(2));
+        assert_eq!(normalize(&parse(&source)), normalize(&nodes));
+    }
+}
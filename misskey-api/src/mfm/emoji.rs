@@ -0,0 +1,163 @@
+//! Matching of Unicode emoji, approximating the pattern of Twemoji used in mfm-js.
+
+const VARIATION_SELECTOR_16: char = '\u{fe0f}';
+const ZERO_WIDTH_JOINER: char = '\u{200d}';
+const COMBINING_ENCLOSING_KEYCAP: char = '\u{20e3}';
+const CANCEL_TAG: char = '\u{e007f}';
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&c)
+}
+
+fn is_skin_tone(c: char) -> bool {
+    ('\u{1f3fb}'..='\u{1f3ff}').contains(&c)
+}
+
+fn is_tag(c: char) -> bool {
+    ('\u{e0020}'..='\u{e007e}').contains(&c)
+}
+
+/// Characters shown as emoji by default.
+fn is_presentation(c: char) -> bool {
+    matches!(
+        c,
+        '\u{231a}'..='\u{231b}'
+            | '\u{23e9}'..='\u{23ec}'
+            | '\u{23f0}'
+            | '\u{23f3}'
+            | '\u{25fd}'..='\u{25fe}'
+            | '\u{2614}'..='\u{2615}'
+            | '\u{2648}'..='\u{2653}'
+            | '\u{267f}'
+            | '\u{2693}'
+            | '\u{26a1}'
+            | '\u{26aa}'..='\u{26ab}'
+            | '\u{26bd}'..='\u{26be}'
+            | '\u{26c4}'..='\u{26c5}'
+            | '\u{26ce}'
+            | '\u{26d4}'
+            | '\u{26ea}'
+            | '\u{26f2}'..='\u{26f3}'
+            | '\u{26f5}'
+            | '\u{26fa}'
+            | '\u{26fd}'
+            | '\u{2705}'
+            | '\u{270a}'..='\u{270b}'
+            | '\u{2728}'
+            | '\u{274c}'
+            | '\u{274e}'
+            | '\u{2753}'..='\u{2755}'
+            | '\u{2757}'
+            | '\u{2795}'..='\u{2797}'
+            | '\u{27b0}'
+            | '\u{27bf}'
+            | '\u{2b1b}'..='\u{2b1c}'
+            | '\u{2b50}'
+            | '\u{2b55}'
+            | '\u{1f004}'
+            | '\u{1f0cf}'
+            | '\u{1f18e}'
+            | '\u{1f191}'..='\u{1f19a}'
+            | '\u{1f201}'
+            | '\u{1f21a}'
+            | '\u{1f22f}'
+            | '\u{1f232}'..='\u{1f236}'
+            | '\u{1f238}'..='\u{1f23a}'
+            | '\u{1f250}'..='\u{1f251}'
+            | '\u{1f300}'..='\u{1faff}'
+    )
+}
+
+/// Characters shown as text by default, which are emoji when followed by U+FE0F.
+fn is_text_default(c: char) -> bool {
+    matches!(
+        c,
+        '\u{a9}'
+            | '\u{ae}'
+            | '\u{203c}'
+            | '\u{2049}'
+            | '\u{2122}'
+            | '\u{2139}'
+            | '\u{2194}'..='\u{21aa}'
+            | '\u{2300}'..='\u{23ff}'
+            | '\u{24c2}'
+            | '\u{25aa}'..='\u{25fe}'
+            | '\u{2600}'..='\u{27bf}'
+            | '\u{2934}'..='\u{2935}'
+            | '\u{2b05}'..='\u{2b55}'
+            | '\u{3030}'
+            | '\u{303d}'
+            | '\u{3297}'
+            | '\u{3299}'
+            | '\u{1f170}'..='\u{1f251}'
+    )
+}
+
+/// Returns the length in bytes of the emoji at the start of `s`.
+pub(super) fn match_emoji(s: &str) -> Option<usize> {
+    let mut chars = s.char_indices().peekable();
+    let (_, first) = chars.next()?;
+    let end_of = |chars: &mut std::iter::Peekable<std::str::CharIndices>| {
+        chars.peek().map_or(s.len(), |&(i, _)| i)
+    };
+
+    if is_regional_indicator(first) {
+        return match chars.next() {
+            Some((_, c)) if is_regional_indicator(c) => Some(end_of(&mut chars)),
+            _ => None,
+        };
+    }
+
+    if first.is_ascii_digit() || first == '#' || first == '*' {
+        chars.next_if(|&(_, c)| c == VARIATION_SELECTOR_16);
+        chars.next_if(|&(_, c)| c == COMBINING_ENCLOSING_KEYCAP)?;
+        return Some(end_of(&mut chars));
+    }
+
+    let mut is_emoji = is_presentation(first);
+    if !is_emoji && !is_text_default(first) {
+        return None;
+    }
+    is_emoji |= consume_modifiers(&mut chars);
+    if !is_emoji {
+        return None;
+    }
+
+    loop {
+        let mut next = chars.clone();
+        if next.next_if(|&(_, c)| c == ZERO_WIDTH_JOINER).is_none() {
+            break;
+        }
+        match next.next() {
+            Some((_, c)) if is_presentation(c) || is_text_default(c) => {
+                consume_modifiers(&mut next);
+                chars = next;
+            }
+            _ => break,
+        }
+    }
+    Some(end_of(&mut chars))
+}
+
+/// Consumes the variation selector, the skin tone and the tag sequence following the emoji,
+/// and returns `true` if any of them forces the emoji presentation.
+fn consume_modifiers(chars: &mut std::iter::Peekable<std::str::CharIndices>) -> bool {
+    let mut is_emoji = false;
+    if chars
+        .next_if(|&(_, c)| c == VARIATION_SELECTOR_16)
+        .is_some()
+    {
+        is_emoji = true;
+    }
+    if chars.next_if(|&(_, c)| is_skin_tone(c)).is_some() {
+        is_emoji = true;
+    }
+    let mut tags = chars.clone();
+    if tags.next_if(|&(_, c)| is_tag(c)).is_some() {
+        while tags.next_if(|&(_, c)| is_tag(c)).is_some() {}
+        if tags.next_if(|&(_, c)| c == CANCEL_TAG).is_some() {
+            *chars = tags;
+        }
+    }
+    is_emoji
+}
@@ -0,0 +1,1048 @@
+//! Port of the parser in mfm-js.
+//!
+//! Each rule takes the position in bytes and returns the position after the parsed node,
+//! or `None` if the rule does not match. Like mfm-js, some rules return the consumed source as
+//! a text node when the syntax is not closed.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::{emoji, Node};
+
+type ParseResult = Option<(usize, Node)>;
+type Rule<'a> = fn(&mut Parser<'a>, usize) -> ParseResult;
+
+pub(super) struct Parser<'a> {
+    input: &'a str,
+    nest_limit: usize,
+    depth: usize,
+    link_label: bool,
+    /// the end of link labels, keyed by the positions of the nodes in the labels and the depth
+    label_ends: HashMap<(usize, usize), usize>,
+}
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\u{3000}' | '\t')
+}
+
+fn is_newline(c: char) -> bool {
+    matches!(c, '\r' | '\n')
+}
+
+fn is_fn_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_hashtag_char(c: char) -> bool {
+    !is_space(c)
+        && !is_newline(c)
+        && !matches!(
+            c,
+            '.' | ','
+                | '!'
+                | '?'
+                | '\''
+                | '"'
+                | '#'
+                | ':'
+                | '/'
+                | '['
+                | ']'
+                | '【'
+                | '】'
+                | '('
+                | ')'
+                | '「'
+                | '」'
+                | '（'
+                | '）'
+                | '<'
+                | '>'
+        )
+}
+
+fn is_url_char(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || matches!(
+            c,
+            '.' | ','
+                | '_'
+                | '/'
+                | ':'
+                | '%'
+                | '#'
+                | '@'
+                | '$'
+                | '&'
+                | '?'
+                | '!'
+                | '~'
+                | '='
+                | '+'
+                | '-'
+        )
+}
+
+fn merge_text(nodes: Vec<Node>) -> Vec<Node> {
+    let mut merged = Vec::with_capacity(nodes.len());
+    let mut text = String::new();
+    for node in nodes {
+        match node {
+            Node::Text { text: t } => text.push_str(&t),
+            node => {
+                if !text.is_empty() {
+                    merged.push(Node::text(std::mem::take(&mut text)));
+                }
+                merged.push(node);
+            }
+        }
+    }
+    if !text.is_empty() {
+        merged.push(Node::text(text));
+    }
+    merged
+}
+
+impl<'a> Parser<'a> {
+    pub(super) fn new(input: &'a str, nest_limit: usize) -> Self {
+        Parser {
+            input,
+            nest_limit,
+            depth: 0,
+            link_label: false,
+            label_ends: HashMap::new(),
+        }
+    }
+
+    pub(super) fn parse_full(&mut self) -> Vec<Node> {
+        self.parse_full_from(0)
+    }
+
+    pub(super) fn parse_full_from(&mut self, pos: usize) -> Vec<Node> {
+        merge_text(self.many(pos, Parser::full).1)
+    }
+
+    pub(super) fn parse_simple(&mut self) -> Vec<Node> {
+        merge_text(self.many(0, Parser::simple).1)
+    }
+
+    // {{{ Combinators
+
+    fn many(&mut self, mut pos: usize, rule: Rule<'a>) -> (usize, Vec<Node>) {
+        let mut nodes = Vec::new();
+        while let Some((next, node)) = rule(self, pos) {
+            nodes.push(node);
+            pos = next;
+        }
+        (pos, nodes)
+    }
+
+    fn alt(&mut self, pos: usize, rules: &[Rule<'a>]) -> ParseResult {
+        rules.iter().find_map(|rule| rule(self, pos))
+    }
+
+    /// Applies the rule one level deeper, or falls back to a character beyond the limit.
+    fn nest(&mut self, pos: usize, rule: Rule<'a>) -> ParseResult {
+        if self.depth >= self.nest_limit {
+            return self.text(pos);
+        }
+        self.depth += 1;
+        let result = rule(self, pos);
+        self.depth -= 1;
+        result
+    }
+
+    /// Parses inline nodes until `stop` matches.
+    fn inline_until<F>(&mut self, mut pos: usize, stop: F) -> (usize, Vec<Node>)
+    where
+        F: Fn(&Self, usize) -> bool,
+    {
+        let mut nodes = Vec::new();
+        while !stop(self, pos) {
+            match self.nest(pos, Parser::inline) {
+                Some((next, node)) => {
+                    nodes.push(node);
+                    pos = next;
+                }
+                None => break,
+            }
+        }
+        (pos, nodes)
+    }
+
+    /// Parses `open`, inline nodes and `close`, which results in the source text if not closed.
+    fn enclosed(
+        &mut self,
+        pos: usize,
+        open: &str,
+        close: &str,
+        single_line: bool,
+        make: fn(Vec<Node>) -> Node,
+    ) -> ParseResult {
+        if !self.starts_with(pos, open) {
+            return None;
+        }
+        let start = pos + open.len();
+        let (end, children) = self.inline_until(start, |p, i| {
+            p.starts_with(i, close) || (single_line && p.newline(i).is_some())
+        });
+        if children.is_empty() {
+            return Some((start, Node::text(&self.input[pos..start])));
+        }
+        if !self.starts_with(end, close) {
+            return Some((end, Node::text(&self.input[pos..end])));
+        }
+        Some((end + close.len(), make(merge_text(children))))
+    }
+
+    /// Parses alphanumerics and spaces enclosed with `mark`.
+    fn enclosed_alnum(&self, pos: usize, mark: &str) -> Option<(usize, Node)> {
+        if !self.starts_with(pos, mark) {
+            return None;
+        }
+        let start = pos + mark.len();
+        let end = self.take_while(start, |c| c.is_ascii_alphanumeric() || is_space(c));
+        if end == start || !self.starts_with(end, mark) {
+            return None;
+        }
+        Some((end + mark.len(), Node::text(&self.input[start..end])))
+    }
+
+    // }}}
+
+    // {{{ Primitives
+
+    fn starts_with(&self, pos: usize, s: &str) -> bool {
+        self.input[pos..].starts_with(s)
+    }
+
+    fn next_char(&self, pos: usize) -> Option<(usize, char)> {
+        let c = self.input[pos..].chars().next()?;
+        Some((pos + c.len_utf8(), c))
+    }
+
+    fn take_while(&self, pos: usize, f: impl Fn(char) -> bool) -> usize {
+        self.input[pos..]
+            .find(|c| !f(c))
+            .map_or(self.input.len(), |i| pos + i)
+    }
+
+    fn newline(&self, pos: usize) -> Option<usize> {
+        if self.starts_with(pos, "\r\n") {
+            Some(pos + 2)
+        } else if self.starts_with(pos, "\r") || self.starts_with(pos, "\n") {
+            Some(pos + 1)
+        } else {
+            None
+        }
+    }
+
+    fn newline_opt(&self, pos: usize) -> usize {
+        self.newline(pos).unwrap_or(pos)
+    }
+
+    fn line_begin(&self, pos: usize) -> bool {
+        pos == 0 || matches!(self.input.as_bytes()[pos - 1], b'\r' | b'\n')
+    }
+
+    fn line_end(&self, pos: usize) -> bool {
+        pos == self.input.len() || matches!(self.input.as_bytes()[pos], b'\r' | b'\n')
+    }
+
+    fn after_alnum(&self, pos: usize) -> bool {
+        matches!(self.input[..pos].chars().next_back(), Some(c) if c.is_ascii_alphanumeric())
+    }
+
+    fn schema(&self, pos: usize) -> Option<usize> {
+        if self.starts_with(pos, "https://") {
+            Some(pos + 8)
+        } else if self.starts_with(pos, "http://") {
+            Some(pos + 7)
+        } else {
+            None
+        }
+    }
+
+    // }}}
+
+    // {{{ Rule sets
+
+    fn full(&mut self, pos: usize) -> ParseResult {
+        self.alt(
+            pos,
+            &[
+                Parser::unicode_emoji,
+                Parser::center_tag,
+                Parser::small_tag,
+                Parser::plain_tag,
+                Parser::bold_tag,
+                Parser::italic_tag,
+                Parser::strike_tag,
+                Parser::url_alt,
+                Parser::big,
+                Parser::bold_asta,
+                Parser::italic_asta,
+                Parser::bold_under,
+                Parser::italic_under,
+                Parser::code_block,
+                Parser::inline_code,
+                Parser::quote,
+                Parser::math_block,
+                Parser::math_inline,
+                Parser::strike_wave,
+                Parser::fn_,
+                Parser::mention,
+                Parser::hashtag,
+                Parser::emoji_code,
+                Parser::link,
+                Parser::url,
+                Parser::search,
+                Parser::text,
+            ],
+        )
+    }
+
+    fn inline(&mut self, pos: usize) -> ParseResult {
+        self.alt(
+            pos,
+            &[
+                Parser::unicode_emoji,
+                Parser::small_tag,
+                Parser::plain_tag,
+                Parser::bold_tag,
+                Parser::italic_tag,
+                Parser::strike_tag,
+                Parser::url_alt,
+                Parser::big,
+                Parser::bold_asta,
+                Parser::italic_asta,
+                Parser::bold_under,
+                Parser::italic_under,
+                Parser::inline_code,
+                Parser::math_inline,
+                Parser::strike_wave,
+                Parser::fn_,
+                Parser::mention,
+                Parser::hashtag,
+                Parser::emoji_code,
+                Parser::link,
+                Parser::url,
+                Parser::text,
+            ],
+        )
+    }
+
+    fn simple(&mut self, pos: usize) -> ParseResult {
+        self.alt(
+            pos,
+            &[
+                Parser::unicode_emoji,
+                Parser::emoji_code,
+                Parser::plain_tag,
+                Parser::text,
+            ],
+        )
+    }
+
+    fn nested_full(&mut self, pos: usize) -> ParseResult {
+        self.nest(pos, Parser::full)
+    }
+
+    // }}}
+
+    // {{{ Blocks
+
+    fn quote(&mut self, pos: usize) -> ParseResult {
+        let pos = self.newline_opt(self.newline_opt(pos));
+        if !self.line_begin(pos) {
+            return None;
+        }
+        let (mut end, line) = self.quote_line(pos)?;
+        let mut lines = vec![line];
+        while let Some((next, line)) = self.newline(end).and_then(|p| self.quote_line(p)) {
+            lines.push(line);
+            end = next;
+        }
+        let end = self.newline_opt(self.newline_opt(end));
+        if lines.len() == 1 && lines[0].is_empty() {
+            return None;
+        }
+
+        let content = lines.join("\n");
+        let mut parser = Parser {
+            input: &content,
+            nest_limit: self.nest_limit,
+            depth: self.depth,
+            link_label: self.link_label,
+            label_ends: HashMap::new(),
+        };
+        let (_, children) = parser.many(0, Parser::nested_full);
+        Some((
+            end,
+            Node::Quote {
+                children: merge_text(children),
+            },
+        ))
+    }
+
+    fn quote_line(&self, pos: usize) -> Option<(usize, &'a str)> {
+        if !self.starts_with(pos, ">") {
+            return None;
+        }
+        let start = match self.next_char(pos + 1) {
+            Some((next, c)) if is_space(c) => next,
+            _ => pos + 1,
+        };
+        let end = self.take_while(start, |c| !is_newline(c));
+        Some((end, &self.input[start..end]))
+    }
+
+    fn search(&mut self, pos: usize) -> ParseResult {
+        let start = self.newline_opt(pos);
+        if !self.line_begin(start) {
+            return None;
+        }
+        let mut end = start;
+        while self.newline(end).is_none() && self.search_button_line(end).is_none() {
+            end = self.next_char(end)?.0;
+        }
+        if end == start {
+            return None;
+        }
+        let button_end = self.search_button_line(end)?;
+        Some((
+            self.newline_opt(button_end),
+            Node::Search {
+                query: self.input[start..end].to_string(),
+                content: self.input[start..button_end].to_string(),
+            },
+        ))
+    }
+
+    /// Parses a space and the search button at the end of the line.
+    fn search_button_line(&self, pos: usize) -> Option<usize> {
+        let (pos, c) = self.next_char(pos)?;
+        if !is_space(c) {
+            return None;
+        }
+        let end = ["[検索]", "[search]", "検索", "search"]
+            .iter()
+            .find(|button| {
+                matches!(self.input.get(pos..pos + button.len()), Some(s) if s.eq_ignore_ascii_case(button))
+            })
+            .map(|button| pos + button.len())?;
+        if self.line_end(end) {
+            Some(end)
+        } else {
+            None
+        }
+    }
+
+    fn code_block(&mut self, pos: usize) -> ParseResult {
+        let pos = self.newline_opt(pos);
+        if !self.line_begin(pos) || !self.starts_with(pos, "```") {
+            return None;
+        }
+        let lang_start = pos + 3;
+        let lang_end = self.take_while(lang_start, |c| !is_newline(c));
+        let start = self.newline(lang_end)?;
+        let mut end = start;
+        while !self.code_block_close(end) {
+            end = self.next_char(end)?.0;
+        }
+        if end == start {
+            return None;
+        }
+        let close_end = self.newline(end)? + 3;
+
+        let lang = self.input[lang_start..lang_end].trim();
+        Some((
+            self.newline_opt(close_end),
+            Node::CodeBlock {
+                code: self.input[start..end].to_string(),
+                lang: if lang.is_empty() {
+                    None
+                } else {
+                    Some(lang.to_string())
+                },
+            },
+        ))
+    }
+
+    fn code_block_close(&self, pos: usize) -> bool {
+        match self.newline(pos) {
+            Some(pos) => self.starts_with(pos, "```") && self.line_end(pos + 3),
+            None => false,
+        }
+    }
+
+    fn math_block(&mut self, pos: usize) -> ParseResult {
+        let pos = self.newline_opt(pos);
+        if !self.line_begin(pos) || !self.starts_with(pos, "\\[") {
+            return None;
+        }
+        let start = self.newline_opt(pos + 2);
+        let mut end = start;
+        while !self.starts_with(self.newline_opt(end), "\\]") {
+            end = self.next_char(end)?.0;
+        }
+        if end == start {
+            return None;
+        }
+        let close_end = self.newline_opt(end) + 2;
+        if !self.line_end(close_end) {
+            return None;
+        }
+        Some((
+            self.newline_opt(close_end),
+            Node::MathBlock {
+                formula: self.input[start..end].to_string(),
+            },
+        ))
+    }
+
+    fn center_tag(&mut self, pos: usize) -> ParseResult {
+        let pos = self.newline_opt(pos);
+        if !self.line_begin(pos) || !self.starts_with(pos, "<center>") {
+            return None;
+        }
+        let start = self.newline_opt(pos + 8);
+        let (end, children) =
+            self.inline_until(start, |p, i| p.starts_with(p.newline_opt(i), "</center>"));
+        let close = self.newline_opt(end);
+        if children.is_empty() || !self.starts_with(close, "</center>") {
+            return None;
+        }
+        let close_end = close + 9;
+        if !self.line_end(close_end) {
+            return None;
+        }
+        Some((
+            self.newline_opt(close_end),
+            Node::Center {
+                children: merge_text(children),
+            },
+        ))
+    }
+
+    // }}}
+
+    // {{{ Inlines
+
+    fn unicode_emoji(&mut self, pos: usize) -> ParseResult {
+        let len = emoji::match_emoji(&self.input[pos..])?;
+        Some((
+            pos + len,
+            Node::UnicodeEmoji {
+                emoji: self.input[pos..pos + len].to_string(),
+            },
+        ))
+    }
+
+    fn plain_tag(&mut self, pos: usize) -> ParseResult {
+        if !self.starts_with(pos, "<plain>") {
+            return None;
+        }
+        let start = self.newline_opt(pos + 7);
+        let mut end = start;
+        while !self.starts_with(self.newline_opt(end), "</plain>") {
+            end = self.next_char(end)?.0;
+        }
+        if end == start {
+            return None;
+        }
+        Some((
+            self.newline_opt(end) + 8,
+            Node::Plain {
+                text: self.input[start..end].to_string(),
+            },
+        ))
+    }
+
+    fn small_tag(&mut self, pos: usize) -> ParseResult {
+        self.enclosed(pos, "<small>", "</small>", false, |children| Node::Small {
+            children,
+        })
+    }
+
+    fn bold_tag(&mut self, pos: usize) -> ParseResult {
+        self.enclosed(pos, "<b>", "</b>", false, |children| Node::Bold {
+            children,
+        })
+    }
+
+    fn italic_tag(&mut self, pos: usize) -> ParseResult {
+        self.enclosed(pos, "<i>", "</i>", false, |children| Node::Italic {
+            children,
+        })
+    }
+
+    fn strike_tag(&mut self, pos: usize) -> ParseResult {
+        self.enclosed(pos, "<s>", "</s>", false, |children| Node::Strike {
+            children,
+        })
+    }
+
+    fn big(&mut self, pos: usize) -> ParseResult {
+        self.enclosed(pos, "***", "***", false, |children| Node::Fn {
+            name: "tada".to_string(),
+            args: BTreeMap::new(),
+            children,
+        })
+    }
+
+    fn bold_asta(&mut self, pos: usize) -> ParseResult {
+        self.enclosed(pos, "**", "**", false, |children| Node::Bold { children })
+    }
+
+    fn strike_wave(&mut self, pos: usize) -> ParseResult {
+        self.enclosed(pos, "~~", "~~", true, |children| Node::Strike { children })
+    }
+
+    fn bold_under(&mut self, pos: usize) -> ParseResult {
+        let (end, text) = self.enclosed_alnum(pos, "__")?;
+        Some((
+            end,
+            Node::Bold {
+                children: vec![text],
+            },
+        ))
+    }
+
+    fn italic_asta(&mut self, pos: usize) -> ParseResult {
+        let (end, text) = self.enclosed_alnum(pos, "*")?;
+        if self.after_alnum(pos) {
+            return None;
+        }
+        Some((
+            end,
+            Node::Italic {
+                children: vec![text],
+            },
+        ))
+    }
+
+    fn italic_under(&mut self, pos: usize) -> ParseResult {
+        let (end, text) = self.enclosed_alnum(pos, "_")?;
+        if self.after_alnum(pos) {
+            return None;
+        }
+        Some((
+            end,
+            Node::Italic {
+                children: vec![text],
+            },
+        ))
+    }
+
+    fn inline_code(&mut self, pos: usize) -> ParseResult {
+        if !self.starts_with(pos, "`") {
+            return None;
+        }
+        let start = pos + 1;
+        let end = self.take_while(start, |c| c != '`' && c != '´' && !is_newline(c));
+        if end == start || !self.starts_with(end, "`") {
+            return None;
+        }
+        Some((
+            end + 1,
+            Node::InlineCode {
+                code: self.input[start..end].to_string(),
+            },
+        ))
+    }
+
+    fn math_inline(&mut self, pos: usize) -> ParseResult {
+        if !self.starts_with(pos, "\\(") {
+            return None;
+        }
+        let start = pos + 2;
+        let mut end = start;
+        while !self.starts_with(end, "\\)") && self.newline(end).is_none() {
+            end = self.next_char(end)?.0;
+        }
+        if end == start || !self.starts_with(end, "\\)") {
+            return None;
+        }
+        Some((
+            end + 2,
+            Node::MathInline {
+                formula: self.input[start..end].to_string(),
+            },
+        ))
+    }
+
+    fn fn_(&mut self, pos: usize) -> ParseResult {
+        if !self.starts_with(pos, "$[") {
+            return None;
+        }
+        let as_text = |parser: &Self, end: usize| Some((end, Node::text(&parser.input[pos..end])));
+
+        let name_start = pos + 2;
+        let name_end = self.take_while(name_start, is_fn_name_char);
+        if name_end == name_start {
+            return as_text(self, name_start);
+        }
+        let (args_end, args) = self
+            .fn_args(name_end)
+            .unwrap_or_else(|| (name_end, BTreeMap::new()));
+        if !self.starts_with(args_end, " ") {
+            return as_text(self, args_end);
+        }
+        let start = args_end + 1;
+        let (end, children) = self.inline_until(start, |p, i| p.starts_with(i, "]"));
+        if children.is_empty() {
+            return as_text(self, start);
+        }
+        if !self.starts_with(end, "]") {
+            return as_text(self, end);
+        }
+        Some((
+            end + 1,
+            Node::Fn {
+                name: self.input[name_start..name_end].to_string(),
+                args,
+                children: merge_text(children),
+            },
+        ))
+    }
+
+    fn fn_args(&self, pos: usize) -> Option<(usize, BTreeMap<String, Option<String>>)> {
+        if !self.starts_with(pos, ".") {
+            return None;
+        }
+        let mut args = BTreeMap::new();
+        let (mut end, (key, value)) = self.fn_arg(pos + 1)?;
+        args.insert(key, value);
+        while self.starts_with(end, ",") {
+            match self.fn_arg(end + 1) {
+                Some((next, (key, value))) => {
+                    args.insert(key, value);
+                    end = next;
+                }
+                None => break,
+            }
+        }
+        Some((end, args))
+    }
+
+    fn fn_arg(&self, pos: usize) -> Option<(usize, (String, Option<String>))> {
+        let key_end = self.take_while(pos, is_fn_name_char);
+        if key_end == pos {
+            return None;
+        }
+        let key = self.input[pos..key_end].to_string();
+        if self.starts_with(key_end, "=") {
+            let value_start = key_end + 1;
+            let value_end = self.take_while(value_start, |c| {
+                c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')
+            });
+            if value_end > value_start {
+                let value = self.input[value_start..value_end].to_string();
+                return Some((value_end, (key, Some(value))));
+            }
+        }
+        Some((key_end, (key, None)))
+    }
+
+    fn mention(&mut self, pos: usize) -> ParseResult {
+        if self.link_label || !self.starts_with(pos, "@") {
+            return None;
+        }
+        let name_start = pos + 1;
+        let name_end = self.take_while(name_start, |c| {
+            c.is_ascii_alphanumeric() || matches!(c, '_' | '-')
+        });
+        if name_end == name_start {
+            return None;
+        }
+        let (end, host) = if self.starts_with(name_end, "@") {
+            let host_start = name_end + 1;
+            let host_end = self.take_while(host_start, |c| {
+                c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')
+            });
+            if host_end > host_start {
+                (host_end, Some(&self.input[host_start..host_end]))
+            } else {
+                (name_end, None)
+            }
+        } else {
+            (name_end, None)
+        };
+        if self.after_alnum(pos) {
+            return None;
+        }
+
+        let mut invalid = false;
+        // remove `.` and `-` at the end of the host
+        let host = match host {
+            Some(host) => {
+                let trimmed = host.trim_end_matches(['.', '-']);
+                if trimmed.is_empty() {
+                    invalid = true;
+                    None
+                } else {
+                    Some(trimmed)
+                }
+            }
+            None => None,
+        };
+        // remove `-` at the end of the username, which is not allowed if the host follows
+        let mut username = &self.input[name_start..name_end];
+        let trimmed = username.trim_end_matches('-');
+        if trimmed.len() != username.len() {
+            if host.is_none() {
+                username = trimmed;
+            } else {
+                invalid = true;
+            }
+        }
+        if username.is_empty() || username.starts_with('-') {
+            invalid = true;
+        }
+        if matches!(host, Some(host) if host.starts_with(['.', '-'])) {
+            invalid = true;
+        }
+        if invalid {
+            return Some((end, Node::text(&self.input[pos..end])));
+        }
+
+        let acct = match host {
+            Some(host) => format!("@{}@{}", username, host),
+            None => format!("@{}", username),
+        };
+        Some((
+            pos + acct.len(),
+            Node::Mention {
+                username: username.to_string(),
+                host: host.map(ToString::to_string),
+                acct,
+            },
+        ))
+    }
+
+    fn hashtag(&mut self, pos: usize) -> ParseResult {
+        if self.link_label || !self.starts_with(pos, "#") {
+            return None;
+        }
+        let start = pos + 1;
+        let mut end = start;
+        while let Some(next) = self.hashtag_item(end) {
+            end = next;
+        }
+        if end == start || self.after_alnum(pos) {
+            return None;
+        }
+        let hashtag = &self.input[start..end];
+        // tags of numbers only are not allowed
+        if hashtag.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Some((
+            end,
+            Node::Hashtag {
+                hashtag: hashtag.to_string(),
+            },
+        ))
+    }
+
+    fn hashtag_item(&mut self, pos: usize) -> Option<usize> {
+        for (open, close) in [("(", ")"), ("[", "]"), ("「", "」"), ("（", "）")] {
+            if !self.starts_with(pos, open) {
+                continue;
+            }
+            let mut end = pos + open.len();
+            while let Some(next) = self.nested_item(end, Parser::hashtag_item, is_hashtag_char) {
+                end = next;
+            }
+            if self.starts_with(end, close) {
+                return Some(end + close.len());
+            }
+        }
+        self.char_if(pos, is_hashtag_char)
+    }
+
+    fn emoji_code(&mut self, pos: usize) -> ParseResult {
+        if !self.starts_with(pos, ":") || self.after_alnum(pos) {
+            return None;
+        }
+        let start = pos + 1;
+        let end = self.take_while(start, |c| {
+            c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-')
+        });
+        if end == start || !self.starts_with(end, ":") {
+            return None;
+        }
+        if self.input[end + 1..].starts_with(|c: char| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        Some((
+            end + 1,
+            Node::EmojiCode {
+                name: self.input[start..end].to_string(),
+            },
+        ))
+    }
+
+    fn link(&mut self, pos: usize) -> ParseResult {
+        if self.link_label {
+            return None;
+        }
+        let (silent, start) = if self.starts_with(pos, "?[") {
+            (true, pos + 2)
+        } else if self.starts_with(pos, "[") {
+            (false, pos + 1)
+        } else {
+            return None;
+        };
+        // find out whether the link is closed before parsing the label, since the label is
+        // scanned again from each `[` in it otherwise
+        let end = self.link_label_end(start);
+        if end == start || !self.starts_with(end, "](") {
+            return None;
+        }
+        let url_start = end + 2;
+        let (url_end, url) = match self.url_alt(url_start).or_else(|| self.url(url_start))? {
+            (url_end, Node::Url { url, .. }) => (url_end, url),
+            _ => return None,
+        };
+        if !self.starts_with(url_end, ")") {
+            return None;
+        }
+        self.link_label = true;
+        let (_, children) = self.inline_until(start, Parser::link_label_stop);
+        self.link_label = false;
+        Some((
+            url_end + 1,
+            Node::Link {
+                silent,
+                url,
+                children: merge_text(children),
+            },
+        ))
+    }
+
+    fn link_label_stop(&self, pos: usize) -> bool {
+        self.starts_with(pos, "]") || self.newline(pos).is_some()
+    }
+
+    /// Returns the end of the link label from `start`, as [`inline_until`][`Parser::inline_until`]
+    /// would stop there.
+    ///
+    /// The ends are remembered for every node in the label, so that labels starting in the middle
+    /// of a scanned one are not scanned again.
+    fn link_label_end(&mut self, start: usize) -> usize {
+        self.link_label = true;
+        let mut visited = Vec::new();
+        let mut pos = start;
+        let end = loop {
+            if let Some(&end) = self.label_ends.get(&(pos, self.depth)) {
+                break end;
+            }
+            if self.link_label_stop(pos) {
+                break pos;
+            }
+            visited.push(pos);
+            match self.nest(pos, Parser::inline) {
+                Some((next, _)) => pos = next,
+                None => break pos,
+            }
+        };
+        self.link_label = false;
+        for pos in visited {
+            self.label_ends.insert((pos, self.depth), end);
+        }
+        end
+    }
+
+    fn url(&mut self, pos: usize) -> ParseResult {
+        if self.link_label {
+            return None;
+        }
+        let start = self.schema(pos)?;
+        let mut end = start;
+        while let Some(next) = self.url_item(end) {
+            end = next;
+        }
+        if end == start {
+            return None;
+        }
+        // remove `.` and `,` at the end
+        let content = self.input[start..end].trim_end_matches(['.', ',']);
+        if content.is_empty() {
+            return Some((end, Node::text(&self.input[pos..end])));
+        }
+        let end = start + content.len();
+        Some((
+            end,
+            Node::Url {
+                url: self.input[pos..end].to_string(),
+                brackets: false,
+            },
+        ))
+    }
+
+    fn url_item(&mut self, pos: usize) -> Option<usize> {
+        for (open, close) in [("(", ")"), ("[", "]")] {
+            if !self.starts_with(pos, open) {
+                continue;
+            }
+            let mut end = pos + open.len();
+            while let Some(next) = self.nested_item(end, Parser::url_item, is_url_char) {
+                end = next;
+            }
+            if self.starts_with(end, close) {
+                return Some(end + close.len());
+            }
+        }
+        self.char_if(pos, is_url_char)
+    }
+
+    fn url_alt(&mut self, pos: usize) -> ParseResult {
+        if self.link_label || !self.starts_with(pos, "<") {
+            return None;
+        }
+        let start = self.schema(pos + 1)?;
+        let end = self.take_while(start, |c| c != '>' && !is_space(c));
+        if end == start || !self.starts_with(end, ">") {
+            return None;
+        }
+        Some((
+            end + 1,
+            Node::Url {
+                url: self.input[pos + 1..end].to_string(),
+                brackets: true,
+            },
+        ))
+    }
+
+    fn text(&mut self, pos: usize) -> ParseResult {
+        let (next, c) = self.next_char(pos)?;
+        Some((next, Node::text(c)))
+    }
+
+    /// Applies `rule` one level deeper, or only accepts a character matching `fallback`
+    /// beyond the limit.
+    fn nested_item(
+        &mut self,
+        pos: usize,
+        rule: fn(&mut Self, usize) -> Option<usize>,
+        fallback: fn(char) -> bool,
+    ) -> Option<usize> {
+        if self.depth >= self.nest_limit {
+            return self.char_if(pos, fallback);
+        }
+        self.depth += 1;
+        let result = rule(self, pos);
+        self.depth -= 1;
+        result
+    }
+
+    fn char_if(&self, pos: usize, f: fn(char) -> bool) -> Option<usize> {
+        match self.next_char(pos) {
+            Some((next, c)) if f(c) => Some(next),
+            _ => None,
+        }
+    }
+
+    // }}}
+}
@@ -0,0 +1,491 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::{parse, Node};
+use crate::model::note::{Note, NoteEmoji};
+
+use url::Url;
+
+/// Builder of the URLs linked from mentions, hashtags and search boxes in [`HtmlRenderer`].
+///
+/// Returning `None` renders the node as text without a link.
+pub trait LinkBuilder {
+    /// Returns the URL of the user, where `host` is `None` for local users.
+    fn mention(&self, username: &str, host: Option<&str>) -> Option<Url>;
+
+    /// Returns the URL of the page of the hashtag, where `hashtag` does not include `#`.
+    fn hashtag(&self, hashtag: &str) -> Option<Url>;
+
+    /// Returns the URL of the search results of the query.
+    ///
+    /// This links to Google by default as Misskey does.
+    fn search(&self, query: &str) -> Option<Url> {
+        Url::parse_with_params("https://www.google.com/search", &[("q", query)]).ok()
+    }
+}
+
+impl<L: LinkBuilder + ?Sized> LinkBuilder for &L {
+    fn mention(&self, username: &str, host: Option<&str>) -> Option<Url> {
+        (**self).mention(username, host)
+    }
+
+    fn hashtag(&self, hashtag: &str) -> Option<Url> {
+        (**self).hashtag(hashtag)
+    }
+
+    fn search(&self, query: &str) -> Option<Url> {
+        (**self).search(query)
+    }
+}
+
+/// [`LinkBuilder`] that links to the pages in the web client of the Misskey instance.
+#[derive(Debug, Clone)]
+pub struct InstanceLinks {
+    url: Url,
+}
+
+impl InstanceLinks {
+    /// Creates a link builder for the instance at `url`, such as `https://misskey.io/`.
+    pub fn new(url: Url) -> Self {
+        InstanceLinks { url }
+    }
+
+    fn page(&self, segments: &[&str]) -> Option<Url> {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .ok()?
+            .pop_if_empty()
+            .extend(segments);
+        Some(url)
+    }
+}
+
+impl LinkBuilder for InstanceLinks {
+    fn mention(&self, username: &str, host: Option<&str>) -> Option<Url> {
+        match host {
+            Some(host) if Some(host) != self.url.host_str() => {
+                self.page(&[&format!("@{}@{}", username, host)])
+            }
+            _ => self.page(&[&format!("@{}", username)]),
+        }
+    }
+
+    fn hashtag(&self, hashtag: &str) -> Option<Url> {
+        self.page(&["tags", hashtag])
+    }
+}
+
+/// Renderer of MFM into HTML.
+///
+/// All text is escaped and only the following elements are generated, so the output can be
+/// embedded in other pages as is:
+/// `a`, `b`, `blockquote`, `br`, `code`, `del`, `div`, `i`, `img`, `pre`, `small` and `span`.
+///
+/// Functions (`$[...]`) are rendered as `<i>` with their content, as the Misskey server does
+/// for other software. Custom emoji are rendered as images if their URLs are known,
+/// and as `:name:` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use misskey_api::mfm::{self, HtmlRenderer, InstanceLinks};
+///
+/// let links = InstanceLinks::new("https://misskey.example/".parse().unwrap());
+/// let mut renderer = HtmlRenderer::new(links);
+/// renderer.emoji("ai", "https://misskey.example/emoji/ai.png".parse().unwrap());
+///
+/// assert_eq!(
+///     renderer.render(&mfm::parse("**Hi** @ai :ai: <script>")),
+///     "<b>Hi</b> \
+///      <a href=\"https://misskey.example/@ai\" class=\"u-url mention\">@ai</a> \
+///      <img class=\"emoji\" src=\"https://misskey.example/emoji/ai.png\" alt=\":ai:\" title=\":ai:\"> \
+///      &lt;script&gt;"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct HtmlRenderer<L = InstanceLinks> {
+    links: L,
+    emojis: HashMap<String, Url>,
+    mention_host: Option<String>,
+}
+
+impl<L> HtmlRenderer<L> {
+    /// Creates a renderer with the link builder.
+    pub fn new(links: L) -> Self {
+        HtmlRenderer {
+            links,
+            emojis: HashMap::new(),
+            mention_host: None,
+        }
+    }
+
+    /// Sets the URL of the custom emoji.
+    pub fn emoji(&mut self, name: impl Into<String>, url: Url) -> &mut Self {
+        self.emojis.insert(name.into(), url);
+        self
+    }
+
+    /// Sets the URLs of the custom emoji, such as [`Note::emojis`].
+    pub fn emojis<'a>(&mut self, emojis: impl IntoIterator<Item = &'a NoteEmoji>) -> &mut Self {
+        for emoji in emojis {
+            self.emoji(emoji.name.clone(), emoji.url.clone());
+        }
+        self
+    }
+
+    /// Sets the host of the users mentioned without hosts, which is `None` for local users.
+    pub fn mention_host(&mut self, host: Option<impl Into<String>>) -> &mut Self {
+        self.mention_host = host.map(Into::into);
+        self
+    }
+
+    /// Prepares for rendering the text of the note.
+    ///
+    /// This sets the custom emoji in the note, and resolves mentions without hosts
+    /// to the users on the same host as the author of the note.
+    pub fn note(&mut self, note: &Note) -> &mut Self {
+        self.emojis(&note.emojis)
+            .mention_host(note.user.host.as_deref())
+    }
+
+    fn emoji_url(&self, name: &str) -> Option<&Url> {
+        // remote emoji may be named in the form of `name@host`
+        self.emojis.get(name).or_else(|| {
+            self.emojis
+                .iter()
+                .find(|(key, _)| key.split('@').next() == Some(name))
+                .map(|(_, url)| url)
+        })
+    }
+}
+
+impl<L: LinkBuilder> HtmlRenderer<L> {
+    /// Renders the nodes into HTML.
+    pub fn render(&self, nodes: &[Node]) -> String {
+        let mut html = String::new();
+        self.render_nodes(nodes, &mut html);
+        html
+    }
+
+    /// Parses the text in MFM and renders it into HTML.
+    pub fn render_text(&self, text: &str) -> String {
+        self.render(&parse(text))
+    }
+
+    fn render_nodes(&self, nodes: &[Node], html: &mut String) {
+        for node in nodes {
+            self.render_node(node, html);
+        }
+    }
+
+    fn wrap(&self, tag: &str, children: &[Node], html: &mut String) {
+        let _ = write!(html, "<{}>", tag);
+        self.render_nodes(children, html);
+        let _ = write!(html, "</{}>", tag);
+    }
+
+    fn link(&self, url: Option<Url>, attrs: &str, text: &str, html: &mut String) {
+        match url {
+            Some(url) => {
+                let _ = write!(html, "<a href=\"{}\"{}>", escape(url.as_str()), attrs);
+                push_text(text, html);
+                html.push_str("</a>");
+            }
+            None => push_text(text, html),
+        }
+    }
+
+    fn render_node(&self, node: &Node, html: &mut String) {
+        match node {
+            Node::Quote { children } => self.wrap("blockquote", children, html),
+            Node::Search { query, content } => {
+                self.link(self.links.search(query), "", content, html)
+            }
+            Node::CodeBlock { code, .. } => {
+                html.push_str("<pre><code>");
+                html.push_str(&escape(code));
+                html.push_str("</code></pre>");
+            }
+            Node::MathBlock { formula } | Node::MathInline { formula } => {
+                html.push_str("<code>");
+                html.push_str(&escape(formula));
+                html.push_str("</code>");
+            }
+            Node::Center { children } => {
+                html.push_str("<div style=\"text-align: center\">");
+                self.render_nodes(children, html);
+                html.push_str("</div>");
+            }
+            Node::UnicodeEmoji { emoji } => html.push_str(&escape(emoji)),
+            Node::EmojiCode { name } => {
+                let code = format!(":{}:", name);
+                match self.emoji_url(name) {
+                    Some(url) if matches!(url.scheme(), "http" | "https") => {
+                        let _ = write!(
+                            html,
+                            "<img class=\"emoji\" src=\"{}\" alt=\"{code}\" title=\"{code}\">",
+                            escape(url.as_str()),
+                            code = escape(&code)
+                        );
+                    }
+                    _ => html.push_str(&escape(&code)),
+                }
+            }
+            Node::Bold { children } => self.wrap("b", children, html),
+            Node::Small { children } => self.wrap("small", children, html),
+            Node::Italic { children } | Node::Fn { children, .. } => self.wrap("i", children, html),
+            Node::Strike { children } => self.wrap("del", children, html),
+            Node::InlineCode { code } => {
+                html.push_str("<code>");
+                html.push_str(&escape(code));
+                html.push_str("</code>");
+            }
+            Node::Mention {
+                username,
+                host,
+                acct,
+            } => {
+                let host = host.as_deref().or(self.mention_host.as_deref());
+                let url = self.links.mention(username, host);
+                self.link(url, " class=\"u-url mention\"", acct, html);
+            }
+            Node::Hashtag { hashtag } => {
+                let url = self.links.hashtag(hashtag);
+                self.link(url, " rel=\"tag\"", &format!("#{}", hashtag), html);
+            }
+            Node::Url { url, .. } => self.link(url.parse().ok(), "", url, html),
+            Node::Link { url, children, .. } => match url.parse::<Url>() {
+                Ok(url) => {
+                    let _ = write!(html, "<a href=\"{}\">", escape(url.as_str()));
+                    self.render_nodes(children, html);
+                    html.push_str("</a>");
+                }
+                Err(_) => self.render_nodes(children, html),
+            },
+            Node::Plain { text } => {
+                html.push_str("<span>");
+                push_text(text, html);
+                html.push_str("</span>");
+            }
+            Node::Text { text } => push_text(text, html),
+        }
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn push_text(text: &str, html: &mut String) {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            html.push_str("<br>");
+        }
+        html.push_str(&escape(line));
+    }
+}
+
+/// Renders the nodes into plain text, removing the formatting.
+///
+/// Blocks are placed on their own lines, quotes are prefixed with `> `,
+/// and links are rendered as `label (url)`.
+///
+/// ```
+/// use misskey_api::mfm;
+///
+/// let nodes = mfm::parse("$[shake **Hello**] [Misskey](https://misskey-hub.net/)\n> quote\nbye");
+/// assert_eq!(
+///     mfm::to_plain_text(&nodes),
+///     "Hello Misskey (https://misskey-hub.net/)\n> quote\nbye"
+/// );
+/// ```
+pub fn to_plain_text(nodes: &[Node]) -> String {
+    let mut text = String::new();
+    push_plain_text(nodes, &mut text);
+    text
+}
+
+fn push_plain_text(nodes: &[Node], text: &mut String) {
+    for (i, node) in nodes.iter().enumerate() {
+        if node.is_block() {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            push_plain_block(node, text);
+            if i + 1 < nodes.len() {
+                text.push('\n');
+            }
+        } else {
+            push_plain_inline(node, text);
+        }
+    }
+}
+
+fn push_plain_block(node: &Node, text: &mut String) {
+    match node {
+        Node::Quote { children } => {
+            let quoted = to_plain_text(children);
+            let lines: Vec<_> = quoted.lines().map(|line| format!("> {}", line)).collect();
+            text.push_str(&lines.join("\n"));
+        }
+        Node::Search { query, .. } => text.push_str(query),
+        Node::CodeBlock { code, .. } => text.push_str(code),
+        Node::MathBlock { formula } => text.push_str(formula),
+        node => push_plain_text(node.children(), text),
+    }
+}
+
+fn push_plain_inline(node: &Node, text: &mut String) {
+    match node {
+        Node::UnicodeEmoji { emoji } => text.push_str(emoji),
+        Node::EmojiCode { name } => {
+            let _ = write!(text, ":{}:", name);
+        }
+        Node::InlineCode { code } => text.push_str(code),
+        Node::MathInline { formula } => text.push_str(formula),
+        Node::Mention { acct, .. } => text.push_str(acct),
+        Node::Hashtag { hashtag } => {
+            let _ = write!(text, "#{}", hashtag);
+        }
+        Node::Url { url, .. } => text.push_str(url),
+        Node::Link { url, children, .. } => {
+            let label = to_plain_text(children);
+            if &label == url {
+                text.push_str(url);
+            } else {
+                let _ = write!(text, "{} ({})", label, url);
+            }
+        }
+        Node::Plain { text: plain } | Node::Text { text: plain } => text.push_str(plain),
+        node => push_plain_text(node.children(), text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_plain_text, HtmlRenderer, InstanceLinks, LinkBuilder};
+    use crate::mfm::parse;
+
+    use url::Url;
+
+    fn renderer() -> HtmlRenderer {
+        HtmlRenderer::new(InstanceLinks::new(
+            "https://misskey.example/".parse().unwrap(),
+        ))
+    }
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(
+            renderer().render_text("<b>\"a\" & 'b'</b>\n<img src=x>"),
+            "<b>&quot;a&quot; &amp; &#39;b&#39;</b><br>&lt;img src=x&gt;"
+        );
+        assert_eq!(renderer().render_text("`<br>`"), "<code>&lt;br&gt;</code>");
+    }
+
+    #[test]
+    fn test_html_blocks() {
+        assert_eq!(
+            renderer().render_text("a\n> b\n> c\nd"),
+            "a<blockquote>b<br>c</blockquote>d"
+        );
+        assert_eq!(
+            renderer().render_text("```rust\nfn main() {}\n```"),
+            "<pre><code>fn main() {}</code></pre>"
+        );
+        assert_eq!(
+            renderer().render_text("<center>$[x2 big]</center>"),
+            "<div style=\"text-align: center\"><i>big</i></div>"
+        );
+        assert_eq!(
+            renderer().render_text("misskey search"),
+            "<a href=\"https://www.google.com/search?q=misskey\">misskey search</a>"
+        );
+    }
+
+    #[test]
+    fn test_html_links() {
+        let mut renderer = renderer();
+        assert_eq!(
+            renderer.render_text("@a @b@misskey.example @c@remote.example"),
+            "<a href=\"https://misskey.example/@a\" class=\"u-url mention\">@a</a> \
+             <a href=\"https://misskey.example/@b\" class=\"u-url mention\">@b@misskey.example</a> \
+             <a href=\"https://misskey.example/@c@remote.example\" class=\"u-url mention\">@c@remote.example</a>"
+        );
+        renderer.mention_host(Some("remote.example"));
+        assert_eq!(
+            renderer.render_text("@a"),
+            "<a href=\"https://misskey.example/@a@remote.example\" class=\"u-url mention\">@a</a>"
+        );
+        assert_eq!(
+            renderer.render_text("#ミスキー #a%b"),
+            "<a href=\"https://misskey.example/tags/%E3%83%9F%E3%82%B9%E3%82%AD%E3%83%BC\" rel=\"tag\">#ミスキー</a> \
+             <a href=\"https://misskey.example/tags/a%25b\" rel=\"tag\">#a%b</a>"
+        );
+        assert_eq!(
+            renderer.render_text("[**a**](<https://example.com/?q=\"x\">)"),
+            "<a href=\"https://example.com/?q=%22x%22\"><b>a</b></a>"
+        );
+    }
+
+    #[test]
+    fn test_html_custom_links() {
+        struct Frontend;
+
+        impl LinkBuilder for Frontend {
+            fn mention(&self, username: &str, _host: Option<&str>) -> Option<Url> {
+                format!("https://frontend.example/users/{}", username)
+                    .parse()
+                    .ok()
+            }
+
+            fn hashtag(&self, _hashtag: &str) -> Option<Url> {
+                None
+            }
+        }
+
+        assert_eq!(
+            HtmlRenderer::new(Frontend).render_text("@a #b"),
+            "<a href=\"https://frontend.example/users/a\" class=\"u-url mention\">@a</a> #b"
+        );
+    }
+
+    #[test]
+    fn test_html_emoji() {
+        let mut renderer = renderer();
+        renderer.emoji(
+            "blob@remote.example",
+            "https://remote.example/blob.png".parse().unwrap(),
+        );
+        assert_eq!(
+            renderer.render_text(":blob: :unknown:"),
+            "<img class=\"emoji\" src=\"https://remote.example/blob.png\" alt=\":blob:\" title=\":blob:\"> :unknown:"
+        );
+    }
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(
+            to_plain_text(&parse("**a** ~~b~~ `c` :d: @e #f https://g.example")),
+            "a b c :d: @e #f https://g.example"
+        );
+        assert_eq!(to_plain_text(&parse("a\n```\ncode\n```\nb")), "a\ncode\nb");
+        assert_eq!(to_plain_text(&parse("> a\n>> b")), "> a\n> > b");
+        assert_eq!(
+            to_plain_text(&parse("[https://a.example](https://a.example)")),
+            "https://a.example"
+        );
+    }
+}
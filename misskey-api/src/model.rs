@@ -26,7 +26,10 @@ pub mod clip;
 pub mod drive;
 pub mod emoji;
 pub mod following;
+pub mod gallery;
+pub mod hashtag;
 pub mod id;
+pub mod instance;
 pub mod log;
 pub mod messaging;
 pub mod meta;
@@ -37,9 +40,15 @@ pub mod note_reaction;
 pub mod notification;
 pub mod page;
 pub mod query;
+pub mod queue;
 pub mod registry;
+pub mod relay;
+pub mod renote_muting;
+pub mod reversi;
+pub mod role;
 pub mod signin;
 pub mod sort;
 pub mod user;
 pub mod user_group;
 pub mod user_list;
+pub mod webhook;
@@ -0,0 +1,35 @@
+use crate::model::{drive::DriveFile, id::Id, note::Tag, user::User};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryPost {
+    pub id: Id<GalleryPost>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub title: String,
+    pub description: Option<String>,
+    pub user_id: Id<User>,
+    pub user: Box<User>,
+    pub file_ids: Vec<Id<DriveFile>>,
+    pub files: Vec<DriveFile>,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    pub is_sensitive: bool,
+    pub liked_count: u64,
+    #[serde(default)]
+    pub is_liked: Option<bool>,
+}
+
+impl_entity!(GalleryPost);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryLike {
+    pub id: Id<GalleryLike>,
+    pub post: GalleryPost,
+}
+
+impl_entity!(GalleryLike);
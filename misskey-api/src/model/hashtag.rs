@@ -0,0 +1,69 @@
+use std::fmt::{self, Display};
+
+use crate::model::note::Tag;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Hashtag {
+    pub tag: Tag,
+    pub mentioned_users_count: u64,
+    pub mentioned_local_users_count: u64,
+    pub mentioned_remote_users_count: u64,
+    pub attached_users_count: u64,
+    pub attached_local_users_count: u64,
+    pub attached_remote_users_count: u64,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Copy)]
+pub enum HashtagSortKey {
+    MentionedUsers,
+    MentionedLocalUsers,
+    MentionedRemoteUsers,
+    AttachedUsers,
+    AttachedLocalUsers,
+    AttachedRemoteUsers,
+}
+
+impl Display for HashtagSortKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HashtagSortKey::MentionedUsers => f.write_str("mentionedUsers"),
+            HashtagSortKey::MentionedLocalUsers => f.write_str("mentionedLocalUsers"),
+            HashtagSortKey::MentionedRemoteUsers => f.write_str("mentionedRemoteUsers"),
+            HashtagSortKey::AttachedUsers => f.write_str("attachedUsers"),
+            HashtagSortKey::AttachedLocalUsers => f.write_str("attachedLocalUsers"),
+            HashtagSortKey::AttachedRemoteUsers => f.write_str("attachedRemoteUsers"),
+        }
+    }
+}
+
+#[derive(Debug, Error, Clone)]
+#[error("invalid sort key")]
+pub struct ParseHashtagSortKeyError {
+    _priv: (),
+}
+
+impl std::str::FromStr for HashtagSortKey {
+    type Err = ParseHashtagSortKeyError;
+
+    fn from_str(s: &str) -> Result<HashtagSortKey, Self::Err> {
+        match s {
+            "mentionedUsers" | "MentionedUsers" => Ok(HashtagSortKey::MentionedUsers),
+            "mentionedLocalUsers" | "MentionedLocalUsers" => {
+                Ok(HashtagSortKey::MentionedLocalUsers)
+            }
+            "mentionedRemoteUsers" | "MentionedRemoteUsers" => {
+                Ok(HashtagSortKey::MentionedRemoteUsers)
+            }
+            "attachedUsers" | "AttachedUsers" => Ok(HashtagSortKey::AttachedUsers),
+            "attachedLocalUsers" | "AttachedLocalUsers" => Ok(HashtagSortKey::AttachedLocalUsers),
+            "attachedRemoteUsers" | "AttachedRemoteUsers" => {
+                Ok(HashtagSortKey::AttachedRemoteUsers)
+            }
+            _ => Err(ParseHashtagSortKeyError { _priv: () }),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt::{self, Display};
+
+use crate::model::id::Id;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use url::Url;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Instance {
+    pub id: Id<Instance>,
+    pub caught_at: DateTime<Utc>,
+    pub host: String,
+    pub users_count: u64,
+    pub notes_count: u64,
+    pub following_count: u64,
+    pub followers_count: u64,
+    pub drive_usage: u64,
+    pub drive_files: u64,
+    pub latest_request_sent_at: Option<DateTime<Utc>>,
+    pub latest_status: Option<u16>,
+    pub latest_request_received_at: Option<DateTime<Utc>>,
+    pub last_communicated_at: DateTime<Utc>,
+    pub is_not_responding: bool,
+    pub is_suspended: bool,
+    pub software_name: Option<String>,
+    pub software_version: Option<String>,
+    pub open_registrations: Option<bool>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub maintainer_name: Option<String>,
+    pub maintainer_email: Option<String>,
+    pub info_updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub icon_url: Option<Url>,
+    #[serde(default)]
+    pub favicon_url: Option<Url>,
+    #[serde(default)]
+    pub theme_color: Option<String>,
+}
+
+impl_entity!(Instance);
+
+#[derive(PartialEq, Eq, Clone, Debug, Copy)]
+pub enum InstanceSortKey {
+    PubSub,
+    Notes,
+    Users,
+    Following,
+    Followers,
+    CaughtAt,
+    LastCommunicatedAt,
+    DriveUsage,
+    DriveFiles,
+}
+
+impl Display for InstanceSortKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InstanceSortKey::PubSub => f.write_str("pubSub"),
+            InstanceSortKey::Notes => f.write_str("notes"),
+            InstanceSortKey::Users => f.write_str("users"),
+            InstanceSortKey::Following => f.write_str("following"),
+            InstanceSortKey::Followers => f.write_str("followers"),
+            InstanceSortKey::CaughtAt => f.write_str("caughtAt"),
+            InstanceSortKey::LastCommunicatedAt => f.write_str("lastCommunicatedAt"),
+            InstanceSortKey::DriveUsage => f.write_str("driveUsage"),
+            InstanceSortKey::DriveFiles => f.write_str("driveFiles"),
+        }
+    }
+}
+
+#[derive(Debug, Error, Clone)]
+#[error("invalid sort key")]
+pub struct ParseInstanceSortKeyError {
+    _priv: (),
+}
+
+impl std::str::FromStr for InstanceSortKey {
+    type Err = ParseInstanceSortKeyError;
+
+    fn from_str(s: &str) -> Result<InstanceSortKey, Self::Err> {
+        match s {
+            "pubSub" | "PubSub" => Ok(InstanceSortKey::PubSub),
+            "notes" | "Notes" => Ok(InstanceSortKey::Notes),
+            "users" | "Users" => Ok(InstanceSortKey::Users),
+            "following" | "Following" => Ok(InstanceSortKey::Following),
+            "followers" | "Followers" => Ok(InstanceSortKey::Followers),
+            "caughtAt" | "CaughtAt" => Ok(InstanceSortKey::CaughtAt),
+            "lastCommunicatedAt" | "LastCommunicatedAt" => Ok(InstanceSortKey::LastCommunicatedAt),
+            "driveUsage" | "DriveUsage" => Ok(InstanceSortKey::DriveUsage),
+            "driveFiles" | "DriveFiles" => Ok(InstanceSortKey::DriveFiles),
+            _ => Err(ParseInstanceSortKeyError { _priv: () }),
+        }
+    }
+}
@@ -10,6 +10,10 @@ pub struct Muting {
     pub created_at: DateTime<Utc>,
     pub mutee_id: Id<User>,
     pub mutee: User,
+    #[cfg(feature = "12-108-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-108-0")))]
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 impl_entity!(Muting);
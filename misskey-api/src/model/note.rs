@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::fmt::{self, Display};
 use std::str::FromStr;
 
-use crate::model::{channel::Channel, drive::DriveFile, id::Id, user::User};
+use crate::model::{channel::Channel, drive::DriveFile, emoji::Emoji, id::Id, user::User};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -59,6 +59,166 @@ impl<S: Into<String>> From<S> for Reaction {
     }
 }
 
+impl Reaction {
+    /// Parses the reaction into the Unicode emoji or the custom emoji.
+    pub fn parse(&self) -> ParsedReaction {
+        ParsedReaction::from(self)
+    }
+}
+
+/// Reaction names used before reactions became emojis, and their emojis.
+const LEGACY_REACTIONS: [(&str, &str); 11] = [
+    ("like", "👍"),
+    ("love", "❤"),
+    ("laugh", "😆"),
+    ("hmm", "🤔"),
+    ("surprise", "😮"),
+    ("congrats", "🎉"),
+    ("angry", "💢"),
+    ("confused", "😥"),
+    ("rip", "😇"),
+    ("pudding", "🍮"),
+    ("star", "⭐"),
+];
+
+/// Parsed form of [`Reaction`].
+///
+/// The legacy reaction names such as `like` and `pudding` are converted into their emojis,
+/// and `:name@.:`, which is used for the local custom emojis in [`Note::reactions`],
+/// is the same as `:name:`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ParsedReaction {
+    /// The Unicode emoji.
+    Unicode(String),
+    /// `:name:` or `:name@.:`, the custom emoji of the local instance.
+    LocalCustom { name: String },
+    /// `:name@host:`, the custom emoji of the remote instance.
+    RemoteCustom { name: String, host: String },
+}
+
+impl ParsedReaction {
+    /// Returns the name of the custom emoji, or `None` if it is the Unicode emoji.
+    pub fn custom_emoji_name(&self) -> Option<&str> {
+        match self {
+            ParsedReaction::Unicode(_) => None,
+            ParsedReaction::LocalCustom { name } | ParsedReaction::RemoteCustom { name, .. } => {
+                Some(name)
+            }
+        }
+    }
+
+    /// Looks up the URL of the custom emoji in [`Note::emojis`].
+    pub fn url_from_note_emojis<'a>(&self, emojis: &'a [NoteEmoji]) -> Option<&'a Url> {
+        // reactions are packed as `name@.` and `name@host`, and emojis in the text as `name`
+        let find = |key: &str| emojis.iter().find(|emoji| emoji.name == key);
+        let emoji = match self {
+            ParsedReaction::Unicode(_) => None,
+            ParsedReaction::LocalCustom { name } => {
+                find(&format!("{}@.", name)).or_else(|| find(name))
+            }
+            ParsedReaction::RemoteCustom { name, host } => find(&format!("{}@{}", name, host)),
+        };
+        emoji.map(|emoji| &emoji.url)
+    }
+
+    /// Looks up the URL of the custom emoji in the emojis, such as [`Meta::emojis`][meta].
+    ///
+    /// [meta]: crate::model::meta::Meta::emojis
+    pub fn url_from_emojis<'a>(&self, emojis: &'a [Emoji]) -> Option<&'a Url> {
+        let (name, host) = match self {
+            ParsedReaction::Unicode(_) => return None,
+            ParsedReaction::LocalCustom { name } => (name, None),
+            ParsedReaction::RemoteCustom { name, host } => (name, Some(host)),
+        };
+        emojis
+            .iter()
+            .find(|emoji| &emoji.name == name && emoji.host.as_ref() == host)
+            .map(|emoji| &emoji.url)
+    }
+
+    /// Sums up the counts of the reactions by their parsed forms.
+    ///
+    /// This is useful to count the reactions in [`Note::reactions`] of several notes,
+    /// where the same emoji can appear in different forms such as `like` and `👍`.
+    pub fn aggregate<'a, I>(reactions: I) -> HashMap<ParsedReaction, u64>
+    where
+        I: IntoIterator<Item = (&'a Reaction, &'a u64)>,
+    {
+        let mut counts = HashMap::new();
+        for (reaction, count) in reactions {
+            *counts.entry(reaction.parse()).or_insert(0) += count;
+        }
+        counts
+    }
+}
+
+impl Display for ParsedReaction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParsedReaction::Unicode(emoji) => f.write_str(emoji),
+            ParsedReaction::LocalCustom { name } => write!(f, ":{}:", name),
+            ParsedReaction::RemoteCustom { name, host } => write!(f, ":{}@{}:", name, host),
+        }
+    }
+}
+
+impl FromStr for ParsedReaction {
+    type Err = std::convert::Infallible;
+    fn from_str(s: &str) -> Result<ParsedReaction, Self::Err> {
+        Ok(parse_reaction(s))
+    }
+}
+
+fn parse_reaction(s: &str) -> ParsedReaction {
+    let custom = s
+        .strip_prefix(':')
+        .and_then(|s| s.strip_suffix(':'))
+        .and_then(|s| {
+            let (name, host) = match s.split_once('@') {
+                Some((name, host)) => (name, Some(host)),
+                None => (s, None),
+            };
+            let is_name_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-');
+            let is_host_char =
+                |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | ':' | '-');
+            if name.is_empty() || !name.chars().all(is_name_char) {
+                return None;
+            }
+            match host {
+                None | Some(".") => Some(ParsedReaction::LocalCustom {
+                    name: name.to_string(),
+                }),
+                Some(host) if !host.is_empty() && host.chars().all(is_host_char) => {
+                    Some(ParsedReaction::RemoteCustom {
+                        name: name.to_string(),
+                        host: host.to_string(),
+                    })
+                }
+                Some(_) => None,
+            }
+        });
+    if let Some(custom) = custom {
+        return custom;
+    }
+    let emoji = LEGACY_REACTIONS
+        .iter()
+        .find(|(name, _)| *name == s)
+        .map_or(s, |(_, emoji)| emoji);
+    ParsedReaction::Unicode(emoji.to_string())
+}
+
+impl From<&Reaction> for ParsedReaction {
+    fn from(reaction: &Reaction) -> ParsedReaction {
+        parse_reaction(&reaction.0)
+    }
+}
+
+impl From<ParsedReaction> for Reaction {
+    fn from(reaction: ParsedReaction) -> Reaction {
+        Reaction(reaction.to_string())
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Copy)]
 #[serde(rename_all = "camelCase")]
 pub enum Visibility {
@@ -172,3 +332,115 @@ fn default_false() -> bool {
 }
 
 impl_entity!(Note);
+
+impl Note {
+    /// Returns the counts of the reactions to the note by their parsed forms.
+    ///
+    /// See [`ParsedReaction::aggregate`] for the details.
+    pub fn parsed_reactions(&self) -> HashMap<ParsedReaction, u64> {
+        ParsedReaction::aggregate(&self.reactions)
+    }
+
+    /// Returns the reactions to the note and their counts, from the most frequent one.
+    pub fn reaction_ranking(&self) -> Vec<(ParsedReaction, u64)> {
+        let mut ranking: Vec<_> = self.parsed_reactions().into_iter().collect();
+        ranking.sort_by(|(r1, c1), (r2, c2)| {
+            c2.cmp(c1).then_with(|| r1.to_string().cmp(&r2.to_string()))
+        });
+        ranking
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NoteEmoji, ParsedReaction, Reaction};
+
+    use std::collections::HashMap;
+
+    fn local(name: &str) -> ParsedReaction {
+        ParsedReaction::LocalCustom {
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Reaction::from("👍").parse(),
+            ParsedReaction::Unicode("👍".to_string())
+        );
+        assert_eq!(
+            Reaction::from("like").parse(),
+            ParsedReaction::Unicode("👍".to_string())
+        );
+        assert_eq!(
+            Reaction::from("pudding").parse(),
+            ParsedReaction::Unicode("🍮".to_string())
+        );
+        assert_eq!(Reaction::from(":ai:").parse(), local("ai"));
+        assert_eq!(Reaction::from(":ai@.:").parse(), local("ai"));
+        assert_eq!(
+            Reaction::from(":ai@misskey.io:").parse(),
+            ParsedReaction::RemoteCustom {
+                name: "ai".to_string(),
+                host: "misskey.io".to_string(),
+            }
+        );
+        assert_eq!(
+            Reaction::from("::").parse(),
+            ParsedReaction::Unicode("::".to_string())
+        );
+        assert_eq!(
+            Reaction::from(":a b:").parse(),
+            ParsedReaction::Unicode(":a b:".to_string())
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        for reaction in ["👍", ":ai:", ":ai@misskey.io:"] {
+            assert_eq!(Reaction::from(reaction).parse().to_string(), reaction);
+        }
+        assert_eq!(Reaction::from(local("ai")), Reaction::from(":ai:"));
+    }
+
+    #[test]
+    fn test_url_from_note_emojis() {
+        let emoji = |name: &str| NoteEmoji {
+            name: name.to_string(),
+            url: format!("https://example.com/{}.png", name).parse().unwrap(),
+        };
+        let emojis = vec![emoji("a"), emoji("b@."), emoji("c@misskey.io")];
+        let url = |reaction: &str| {
+            Reaction::from(reaction)
+                .parse()
+                .url_from_note_emojis(&emojis)
+                .map(|url| url.to_string())
+        };
+        assert_eq!(url(":a:").as_deref(), Some("https://example.com/a.png"));
+        assert_eq!(url(":b@.:").as_deref(), Some("https://example.com/b@..png"));
+        assert_eq!(
+            url(":c@misskey.io:").as_deref(),
+            Some("https://example.com/c@misskey.io.png")
+        );
+        assert_eq!(url(":c:"), None);
+        assert_eq!(url("👍"), None);
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let reactions: HashMap<Reaction, u64> = vec![
+            (Reaction::from("like"), 1),
+            (Reaction::from("👍"), 2),
+            (Reaction::from(":ai:"), 3),
+            (Reaction::from(":ai@.:"), 4),
+            (Reaction::from(":ai@misskey.io:"), 5),
+        ]
+        .into_iter()
+        .collect();
+        let counts = ParsedReaction::aggregate(&reactions);
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts[&ParsedReaction::Unicode("👍".to_string())], 3);
+        assert_eq!(counts[&local("ai")], 7);
+    }
+}
@@ -1,11 +1,35 @@
 use crate::model::note::Tag;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Debug, Clone, Default)]
 #[serde(transparent)]
 pub struct Query<T>(pub Vec<Vec<T>>);
 
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Query<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // newer Misskey also represents a conjunction of a single element as a bare element
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Conjunction<T> {
+            And(Vec<T>),
+            Atom(T),
+        }
+
+        let vec = Vec::<Conjunction<T>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|c| match c {
+                Conjunction::And(and) => and,
+                Conjunction::Atom(x) => vec![x],
+            })
+            .collect();
+        Ok(Query(vec))
+    }
+}
+
 impl<T> Query<T> {
     /// Creates an empty [`Query`].
     pub fn new() -> Self {
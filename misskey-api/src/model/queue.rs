@@ -0,0 +1,120 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueDomain {
+    Deliver,
+    Inbox,
+    Db,
+    ObjectStorage,
+}
+
+impl Display for QueueDomain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueueDomain::Deliver => f.write_str("deliver"),
+            QueueDomain::Inbox => f.write_str("inbox"),
+            QueueDomain::Db => f.write_str("db"),
+            QueueDomain::ObjectStorage => f.write_str("objectStorage"),
+        }
+    }
+}
+
+#[derive(Debug, Error, Clone)]
+#[error("invalid queue domain")]
+pub struct ParseQueueDomainError {
+    _priv: (),
+}
+
+impl std::str::FromStr for QueueDomain {
+    type Err = ParseQueueDomainError;
+
+    fn from_str(s: &str) -> Result<QueueDomain, Self::Err> {
+        match s {
+            "deliver" | "Deliver" => Ok(QueueDomain::Deliver),
+            "inbox" | "Inbox" => Ok(QueueDomain::Inbox),
+            "db" | "Db" => Ok(QueueDomain::Db),
+            "objectStorage" | "ObjectStorage" => Ok(QueueDomain::ObjectStorage),
+            _ => Err(ParseQueueDomainError { _priv: () }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueJobState {
+    Active,
+    Waiting,
+    Delayed,
+}
+
+impl Display for QueueJobState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueueJobState::Active => f.write_str("active"),
+            QueueJobState::Waiting => f.write_str("waiting"),
+            QueueJobState::Delayed => f.write_str("delayed"),
+        }
+    }
+}
+
+#[derive(Debug, Error, Clone)]
+#[error("invalid queue job state")]
+pub struct ParseQueueJobStateError {
+    _priv: (),
+}
+
+impl std::str::FromStr for QueueJobState {
+    type Err = ParseQueueJobStateError;
+
+    fn from_str(s: &str) -> Result<QueueJobState, Self::Err> {
+        match s {
+            "active" | "Active" => Ok(QueueJobState::Active),
+            "waiting" | "Waiting" => Ok(QueueJobState::Waiting),
+            "delayed" | "Delayed" => Ok(QueueJobState::Delayed),
+            _ => Err(ParseQueueJobStateError { _priv: () }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueJob {
+    pub id: String,
+    /// The payload of the job, which differs among the queue domains.
+    pub data: serde_json::Value,
+    pub attempts: u64,
+    pub max_attempts: u64,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueJobCounts {
+    pub waiting: u64,
+    pub active: u64,
+    pub completed: u64,
+    pub failed: u64,
+    pub delayed: u64,
+    #[serde(default)]
+    pub paused: u64,
+}
+
+/// The number of delayed jobs for a remote host.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "(String, u64)")]
+pub struct DelayedJobCount {
+    pub host: String,
+    pub count: u64,
+}
+
+impl From<(String, u64)> for DelayedJobCount {
+    fn from((host, count): (String, u64)) -> DelayedJobCount {
+        DelayedJobCount { host, count }
+    }
+}
@@ -0,0 +1,54 @@
+use std::fmt::{self, Display};
+
+use crate::model::id::Id;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use url::Url;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum RelayStatus {
+    Requesting,
+    Accepted,
+    Rejected,
+}
+
+impl Display for RelayStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RelayStatus::Requesting => f.write_str("requesting"),
+            RelayStatus::Accepted => f.write_str("accepted"),
+            RelayStatus::Rejected => f.write_str("rejected"),
+        }
+    }
+}
+
+#[derive(Debug, Error, Clone)]
+#[error("invalid relay status")]
+pub struct ParseRelayStatusError {
+    _priv: (),
+}
+
+impl std::str::FromStr for RelayStatus {
+    type Err = ParseRelayStatusError;
+
+    fn from_str(s: &str) -> Result<RelayStatus, Self::Err> {
+        match s {
+            "requesting" | "Requesting" => Ok(RelayStatus::Requesting),
+            "accepted" | "Accepted" => Ok(RelayStatus::Accepted),
+            "rejected" | "Rejected" => Ok(RelayStatus::Rejected),
+            _ => Err(ParseRelayStatusError { _priv: () }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Relay {
+    pub id: Id<Relay>,
+    pub inbox: Url,
+    pub status: RelayStatus,
+}
+
+impl_entity!(Relay);
@@ -0,0 +1,181 @@
+use std::fmt::{self, Display};
+
+use crate::model::{id::Id, user::User};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// Color of the stones, which is represented as a boolean in the API (`true` for black).
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Copy, Hash)]
+#[serde(from = "bool", into = "bool")]
+pub enum ReversiColor {
+    Black,
+    White,
+}
+
+impl ReversiColor {
+    /// Returns the color of the opponent.
+    pub fn opposite(self) -> ReversiColor {
+        match self {
+            ReversiColor::Black => ReversiColor::White,
+            ReversiColor::White => ReversiColor::Black,
+        }
+    }
+}
+
+impl From<bool> for ReversiColor {
+    fn from(black: bool) -> ReversiColor {
+        if black {
+            ReversiColor::Black
+        } else {
+            ReversiColor::White
+        }
+    }
+}
+
+impl From<ReversiColor> for bool {
+    fn from(color: ReversiColor) -> bool {
+        color == ReversiColor::Black
+    }
+}
+
+/// Setting of which user plays black.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Copy)]
+pub enum ReversiBw {
+    #[serde(rename = "random")]
+    Random,
+    #[serde(rename = "1")]
+    User1,
+    #[serde(rename = "2")]
+    User2,
+}
+
+impl Display for ReversiBw {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReversiBw::Random => f.write_str("random"),
+            ReversiBw::User1 => f.write_str("1"),
+            ReversiBw::User2 => f.write_str("2"),
+        }
+    }
+}
+
+#[derive(Debug, Error, Clone)]
+#[error("invalid reversi bw setting")]
+pub struct ParseReversiBwError {
+    _priv: (),
+}
+
+impl std::str::FromStr for ReversiBw {
+    type Err = ParseReversiBwError;
+
+    fn from_str(s: &str) -> Result<ReversiBw, Self::Err> {
+        match s {
+            "random" | "Random" => Ok(ReversiBw::Random),
+            "1" | "User1" => Ok(ReversiBw::User1),
+            "2" | "User2" => Ok(ReversiBw::User2),
+            _ => Err(ParseReversiBwError { _priv: () }),
+        }
+    }
+}
+
+/// A setting of the game, which can be changed before the game starts.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(rename_all = "camelCase", tag = "key", content = "value")]
+pub enum ReversiSetting {
+    /// Rows of the board, where `-` is an empty cell, `b` and `w` are cells with the stones
+    /// and others are cells where stones cannot be put.
+    Map(Vec<String>),
+    Bw(ReversiBw),
+    IsLlotheo(bool),
+    CanPutEverywhere(bool),
+    LoopedBoard(bool),
+}
+
+/// A move in the game.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReversiLog {
+    #[serde(default)]
+    pub at: Option<DateTime<Utc>>,
+    pub color: ReversiColor,
+    pub pos: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReversiGame {
+    pub id: Id<ReversiGame>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub is_started: bool,
+    pub is_ended: bool,
+    #[serde(default)]
+    pub form1: Option<Value>,
+    #[serde(default)]
+    pub form2: Option<Value>,
+    pub user1_accepted: bool,
+    pub user2_accepted: bool,
+    pub user1_id: Id<User>,
+    pub user2_id: Id<User>,
+    pub user1: User,
+    pub user2: User,
+    pub winner_id: Option<Id<User>>,
+    #[serde(default)]
+    pub winner: Option<User>,
+    pub surrendered: Option<Id<User>>,
+    /// Which user plays black, `1` or `2`. This is decided when the game starts.
+    pub black: Option<u8>,
+    pub bw: ReversiBw,
+    pub is_llotheo: bool,
+    pub can_put_everywhere: bool,
+    pub looped_board: bool,
+    /// Moves in the game, which is only included in the detailed game object.
+    #[serde(default)]
+    pub logs: Option<Vec<ReversiLog>>,
+    #[serde(default)]
+    pub map: Option<Vec<String>>,
+}
+
+impl_entity!(ReversiGame);
+
+impl ReversiGame {
+    /// Returns the ID of the user playing the color, if it is decided.
+    pub fn user_id_of(&self, color: ReversiColor) -> Option<Id<User>> {
+        let (black, white) = match self.black? {
+            1 => (self.user1_id, self.user2_id),
+            _ => (self.user2_id, self.user1_id),
+        };
+        match color {
+            ReversiColor::Black => Some(black),
+            ReversiColor::White => Some(white),
+        }
+    }
+
+    /// Returns the color played by the user, if it is decided and the user is a player.
+    pub fn color_of(&self, user_id: Id<User>) -> Option<ReversiColor> {
+        if self.user_id_of(ReversiColor::Black)? == user_id {
+            Some(ReversiColor::Black)
+        } else if self.user_id_of(ReversiColor::White)? == user_id {
+            Some(ReversiColor::White)
+        } else {
+            None
+        }
+    }
+}
+
+/// Invitation to a game.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReversiMatching {
+    pub id: Id<ReversiMatching>,
+    pub created_at: DateTime<Utc>,
+    pub parent_id: Id<User>,
+    pub parent: User,
+    pub child_id: Id<User>,
+    pub child: User,
+}
+
+impl_entity!(ReversiMatching);
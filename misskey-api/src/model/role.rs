@@ -0,0 +1,263 @@
+use std::fmt::{self, Display};
+
+use crate::model::{id::Id, user::User};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use url::Url;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum RoleTarget {
+    /// Users are assigned to the role manually by moderators.
+    Manual,
+    /// Users matching [`RoleCondFormula`] are assigned to the role automatically.
+    Conditional,
+}
+
+impl Display for RoleTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RoleTarget::Manual => f.write_str("manual"),
+            RoleTarget::Conditional => f.write_str("conditional"),
+        }
+    }
+}
+
+#[derive(Debug, Error, Clone)]
+#[error("invalid role target")]
+pub struct ParseRoleTargetError {
+    _priv: (),
+}
+
+impl std::str::FromStr for RoleTarget {
+    type Err = ParseRoleTargetError;
+
+    fn from_str(s: &str) -> Result<RoleTarget, Self::Err> {
+        match s {
+            "manual" | "Manual" => Ok(RoleTarget::Manual),
+            "conditional" | "Conditional" => Ok(RoleTarget::Conditional),
+            _ => Err(ParseRoleTargetError { _priv: () }),
+        }
+    }
+}
+
+/// Formula that decides which users are assigned to the conditional role.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum RoleCondFormula {
+    And {
+        values: Vec<RoleCondFormula>,
+    },
+    Or {
+        values: Vec<RoleCondFormula>,
+    },
+    Not {
+        value: Box<RoleCondFormula>,
+    },
+    IsLocal,
+    IsRemote,
+    /// The account was created less than `sec` seconds ago.
+    CreatedLessThan {
+        sec: u64,
+    },
+    /// The account was created more than `sec` seconds ago.
+    CreatedMoreThan {
+        sec: u64,
+    },
+    FollowersLessThanOrEq {
+        value: u64,
+    },
+    FollowersMoreThanOrEq {
+        value: u64,
+    },
+    FollowingLessThanOrEq {
+        value: u64,
+    },
+    FollowingMoreThanOrEq {
+        value: u64,
+    },
+}
+
+impl Default for RoleCondFormula {
+    /// Returns the formula that matches all users.
+    fn default() -> Self {
+        RoleCondFormula::And { values: Vec::new() }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Copy)]
+#[serde(from = "u8", into = "u8")]
+pub enum RolePolicyPriority {
+    Low,
+    Middle,
+    High,
+}
+
+impl From<u8> for RolePolicyPriority {
+    fn from(n: u8) -> RolePolicyPriority {
+        match n {
+            0 => RolePolicyPriority::Low,
+            1 => RolePolicyPriority::Middle,
+            _ => RolePolicyPriority::High,
+        }
+    }
+}
+
+impl From<RolePolicyPriority> for u8 {
+    fn from(priority: RolePolicyPriority) -> u8 {
+        match priority {
+            RolePolicyPriority::Low => 0,
+            RolePolicyPriority::Middle => 1,
+            RolePolicyPriority::High => 2,
+        }
+    }
+}
+
+/// A policy overridden by the role.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RolePolicy<T> {
+    /// When `true`, the default policy is used instead of `value`.
+    pub use_default: bool,
+    /// Priority among the roles of a user that override the same policy.
+    pub priority: RolePolicyPriority,
+    pub value: T,
+}
+
+impl<T> RolePolicy<T> {
+    /// Creates a policy that overrides the default with `value`.
+    pub fn new(value: T) -> Self {
+        RolePolicy {
+            use_default: false,
+            priority: RolePolicyPriority::Low,
+            value,
+        }
+    }
+}
+
+macro_rules! policies {
+    ($($(#[$m:meta])* $name:ident: $ty:ty,)*) => {
+        /// Policies applied to the users, such as the default policies of the instance.
+        #[derive(Serialize, Deserialize, PartialEq, Default, Clone, Debug)]
+        #[serde(rename_all = "camelCase")]
+        pub struct Policies {
+            $(
+                $(#[$m])*
+                #[serde(default, skip_serializing_if = "Option::is_none")]
+                pub $name: Option<$ty>,
+            )*
+        }
+
+        /// Policies overridden by the role.
+        #[derive(Serialize, Deserialize, PartialEq, Default, Clone, Debug)]
+        #[serde(rename_all = "camelCase")]
+        pub struct RolePolicies {
+            $(
+                $(#[$m])*
+                #[serde(default, skip_serializing_if = "Option::is_none")]
+                pub $name: Option<RolePolicy<$ty>>,
+            )*
+        }
+    };
+}
+
+policies! {
+    /// Whether the global timeline is available.
+    gtl_available: bool,
+    /// Whether the local timeline is available.
+    ltl_available: bool,
+    /// Whether the user can post public notes.
+    can_public_note: bool,
+    /// Whether the user can create invitation codes.
+    can_invite: bool,
+    /// Whether the user can manage custom emojis.
+    can_manage_custom_emojis: bool,
+    /// Whether the user can search notes.
+    can_search_notes: bool,
+    /// Whether the user can hide the ads.
+    can_hide_ads: bool,
+    /// Drive capacity in megabytes.
+    drive_capacity_mb: u64,
+    pin_limit: u64,
+    antenna_limit: u64,
+    word_mute_limit: u64,
+    webhook_limit: u64,
+    clip_limit: u64,
+    note_each_clips_limit: u64,
+    user_list_limit: u64,
+    user_each_user_lists_limit: u64,
+    /// Multiplier of the rate limits.
+    rate_limit_factor: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Role {
+    pub id: Id<Role>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub name: String,
+    pub description: String,
+    pub color: Option<String>,
+    #[serde(default)]
+    pub icon_url: Option<Url>,
+    pub target: RoleTarget,
+    pub cond_formula: RoleCondFormula,
+    pub is_public: bool,
+    pub is_administrator: bool,
+    pub is_moderator: bool,
+    #[serde(default)]
+    pub as_badge: bool,
+    #[serde(default)]
+    pub can_edit_members_by_moderator: bool,
+    #[serde(default)]
+    pub display_order: i64,
+    pub policies: RolePolicies,
+    #[serde(default)]
+    pub users_count: Option<u64>,
+}
+
+impl_entity!(Role);
+
+/// Role shown in the user's profile.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserRole {
+    pub id: Id<Role>,
+    pub name: String,
+    pub color: Option<String>,
+    #[serde(default)]
+    pub icon_url: Option<Url>,
+    pub description: String,
+    pub is_moderator: bool,
+    pub is_administrator: bool,
+    #[serde(default)]
+    pub display_order: i64,
+}
+
+/// Role shown as a badge next to the user's name.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BadgeRole {
+    pub name: String,
+    #[serde(default)]
+    pub icon_url: Option<Url>,
+    #[serde(default)]
+    pub display_order: i64,
+}
+
+/// Assignment of the user to the role.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleAssignment {
+    pub id: Id<RoleAssignment>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    pub user: User,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl_entity!(RoleAssignment);
@@ -4,7 +4,9 @@ use std::fmt::{self, Display};
 
 #[cfg(feature = "12-48-0")]
 use crate::model::notification::NotificationType;
-use crate::model::{id::Id, note::Note, page::Page};
+#[cfg(feature = "13-10-0")]
+use crate::model::role::{BadgeRole, UserRole};
+use crate::model::{id::Id, note::Note, page::Page, query::Query};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -186,6 +188,16 @@ pub struct User {
     #[cfg_attr(docsrs, doc(cfg(feature = "12-60-0")))]
     #[serde(default)]
     pub no_crawle: Option<bool>,
+    #[serde(default)]
+    pub muted_words: Option<Query<String>>,
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    #[serde(default)]
+    pub roles: Option<Vec<UserRole>>,
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    #[serde(default)]
+    pub badge_roles: Option<Vec<BadgeRole>>,
     #[cfg(feature = "12-48-0")]
     #[cfg_attr(docsrs, doc(cfg(feature = "12-48-0")))]
     #[serde(default)]
@@ -0,0 +1,116 @@
+use std::fmt::{self, Display};
+
+use crate::model::{id::Id, note::Note, user::User};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use url::Url;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Copy, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEventType {
+    Note,
+    Reply,
+    Renote,
+    Mention,
+    Follow,
+    Followed,
+    Unfollow,
+}
+
+impl Display for WebhookEventType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WebhookEventType::Note => f.write_str("note"),
+            WebhookEventType::Reply => f.write_str("reply"),
+            WebhookEventType::Renote => f.write_str("renote"),
+            WebhookEventType::Mention => f.write_str("mention"),
+            WebhookEventType::Follow => f.write_str("follow"),
+            WebhookEventType::Followed => f.write_str("followed"),
+            WebhookEventType::Unfollow => f.write_str("unfollow"),
+        }
+    }
+}
+
+#[derive(Debug, Error, Clone)]
+#[error("invalid webhook event type")]
+pub struct ParseWebhookEventTypeError {
+    _priv: (),
+}
+
+impl std::str::FromStr for WebhookEventType {
+    type Err = ParseWebhookEventTypeError;
+
+    fn from_str(s: &str) -> Result<WebhookEventType, Self::Err> {
+        match s {
+            "note" | "Note" => Ok(WebhookEventType::Note),
+            "reply" | "Reply" => Ok(WebhookEventType::Reply),
+            "renote" | "Renote" => Ok(WebhookEventType::Renote),
+            "mention" | "Mention" => Ok(WebhookEventType::Mention),
+            "follow" | "Follow" => Ok(WebhookEventType::Follow),
+            "followed" | "Followed" => Ok(WebhookEventType::Followed),
+            "unfollow" | "Unfollow" => Ok(WebhookEventType::Unfollow),
+            _ => Err(ParseWebhookEventTypeError { _priv: () }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: Id<Webhook>,
+    pub user_id: Id<User>,
+    pub name: String,
+    pub on: Vec<WebhookEventType>,
+    pub url: Url,
+    pub secret: String,
+    pub active: bool,
+    #[serde(default)]
+    pub latest_sent_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub latest_status: Option<u16>,
+}
+
+impl_entity!(Webhook);
+
+/// Event delivered to the webhook endpoint, along with its body.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "type", content = "body")]
+pub enum WebhookEvent {
+    Note { note: Box<Note> },
+    Reply { note: Box<Note> },
+    Renote { note: Box<Note> },
+    Mention { note: Box<Note> },
+    Follow { user: Box<User> },
+    Followed { user: Box<User> },
+    Unfollow { user: Box<User> },
+}
+
+impl WebhookEvent {
+    /// Returns the type of this event.
+    pub fn event_type(&self) -> WebhookEventType {
+        match self {
+            WebhookEvent::Note { .. } => WebhookEventType::Note,
+            WebhookEvent::Reply { .. } => WebhookEventType::Reply,
+            WebhookEvent::Renote { .. } => WebhookEventType::Renote,
+            WebhookEvent::Mention { .. } => WebhookEventType::Mention,
+            WebhookEvent::Follow { .. } => WebhookEventType::Follow,
+            WebhookEvent::Followed { .. } => WebhookEventType::Followed,
+            WebhookEvent::Unfollow { .. } => WebhookEventType::Unfollow,
+        }
+    }
+}
+
+/// Body of the request sent to the webhook endpoint.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    pub hook_id: Id<Webhook>,
+    pub user_id: Id<User>,
+    pub event_id: String,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: WebhookEvent,
+}
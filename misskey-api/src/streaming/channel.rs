@@ -14,6 +14,12 @@ pub enum NoOutgoing {}
 pub mod admin;
 pub mod antenna;
 pub mod drive;
+#[cfg(any(docsrs, not(feature = "13-10-0")))]
+#[cfg_attr(docsrs, doc(cfg(not(feature = "13-10-0"))))]
+pub mod games_reversi;
+#[cfg(any(docsrs, not(feature = "13-10-0")))]
+#[cfg_attr(docsrs, doc(cfg(not(feature = "13-10-0"))))]
+pub mod games_reversi_game;
 pub mod global_timeline;
 pub mod hashtag;
 pub mod home_timeline;
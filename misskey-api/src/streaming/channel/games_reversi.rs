@@ -0,0 +1,110 @@
+use crate::model::{
+    id::Id,
+    reversi::{ReversiGame, ReversiMatching},
+    user::User,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "type", content = "body")]
+pub enum GamesReversiStreamEvent {
+    /// The invitation sent by the user logged in is accepted.
+    Matched(ReversiGame),
+    /// The user logged in is invited to a game.
+    Invited(ReversiMatching),
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "type", content = "body")]
+pub enum Message {
+    /// Notifies the user of the invitation sent by the user logged in again.
+    Ping { id: Id<User> },
+}
+
+#[derive(Serialize, Default, Debug, Clone)]
+pub struct Request {}
+
+impl misskey_core::streaming::ConnectChannelRequest for Request {
+    type Incoming = GamesReversiStreamEvent;
+    type Outgoing = Message;
+
+    const NAME: &'static str = "gamesReversi";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GamesReversiStreamEvent, Message, Request};
+    use crate::test::{websocket::TestClient, ClientExt};
+
+    use futures::{future, SinkExt, StreamExt};
+
+    #[tokio::test]
+    async fn subscribe_unsubscribe() {
+        let client = TestClient::new().await;
+        let mut stream = client.channel(Request::default()).await.unwrap();
+        stream.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stream_invited() {
+        let client = TestClient::new().await;
+        let user = client.user.me().await;
+        let mut stream = client.user.channel(Request::default()).await.unwrap();
+
+        future::join(
+            client
+                .admin
+                .test(crate::endpoint::games::reversi::r#match::Request { user_id: user.id }),
+            async {
+                loop {
+                    match stream.next().await.unwrap().unwrap() {
+                        GamesReversiStreamEvent::Invited(_) => break,
+                        _ => continue,
+                    }
+                }
+            },
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn stream_matched() {
+        let client = TestClient::new().await;
+        let user = client.user.me().await;
+        let admin = client.admin.me().await;
+        let mut stream = client.admin.channel(Request::default()).await.unwrap();
+        client
+            .admin
+            .test(crate::endpoint::games::reversi::r#match::Request { user_id: user.id })
+            .await;
+
+        future::join(
+            client
+                .user
+                .test(crate::endpoint::games::reversi::r#match::Request { user_id: admin.id }),
+            async {
+                loop {
+                    match stream.next().await.unwrap().unwrap() {
+                        GamesReversiStreamEvent::Matched(_) => break,
+                        _ => continue,
+                    }
+                }
+            },
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn send_ping() {
+        let client = TestClient::new().await;
+        let user = client.user.me().await;
+        client
+            .admin
+            .test(crate::endpoint::games::reversi::r#match::Request { user_id: user.id })
+            .await;
+        let mut stream = client.admin.channel(Request::default()).await.unwrap();
+        stream.send(Message::Ping { id: user.id }).await.unwrap();
+    }
+}
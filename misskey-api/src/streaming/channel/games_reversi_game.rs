@@ -0,0 +1,193 @@
+use crate::model::{
+    id::Id,
+    reversi::{ReversiColor, ReversiGame, ReversiSetting},
+    user::User,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "type", content = "body")]
+pub enum GamesReversiGameStreamEvent {
+    /// Whether each user has accepted the settings.
+    ChangeAccepts {
+        user1: bool,
+        user2: bool,
+    },
+    UpdateSettings(ReversiSetting),
+    #[serde(rename_all = "camelCase")]
+    InitForm {
+        user_id: Id<User>,
+        form: Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    UpdateForm {
+        user_id: Id<User>,
+        id: String,
+        value: Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    Message {
+        user_id: Id<User>,
+        message: Value,
+    },
+    /// The game is started with the settings in the game.
+    Started(ReversiGame),
+    #[serde(rename_all = "camelCase")]
+    Ended {
+        winner_id: Option<Id<User>>,
+        game: ReversiGame,
+    },
+    /// A stone is put at `pos`, and `next` is the color to play next (`None` if the game is over).
+    Set {
+        #[serde(default)]
+        at: Option<DateTime<Utc>>,
+        color: ReversiColor,
+        pos: u64,
+        next: Option<ReversiColor>,
+    },
+    /// A user started watching the game.
+    Watching(Id<User>),
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", tag = "type", content = "body")]
+pub enum Message {
+    /// Accepts the current settings. The game starts when both users accept.
+    Accept {},
+    CancelAccept {},
+    UpdateSettings(ReversiSetting),
+    InitForm(Value),
+    UpdateForm {
+        id: String,
+        value: Value,
+    },
+    Message(Value),
+    /// Puts a stone at `pos`, which is the index of the cell in the map.
+    Set {
+        pos: u64,
+    },
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub game_id: Id<ReversiGame>,
+}
+
+impl misskey_core::streaming::ConnectChannelRequest for Request {
+    type Incoming = GamesReversiGameStreamEvent;
+    type Outgoing = Message;
+
+    const NAME: &'static str = "gamesReversiGame";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GamesReversiGameStreamEvent, Message, Request};
+    use crate::model::reversi::{ReversiBw, ReversiSetting};
+    use crate::test::{websocket::TestClient, ClientExt};
+
+    use futures::{SinkExt, StreamExt};
+
+    async fn create_game(client: &TestClient) -> crate::model::reversi::ReversiGame {
+        let user = client.user.me().await;
+        let admin = client.admin.me().await;
+        client
+            .admin
+            .test(crate::endpoint::games::reversi::r#match::Request { user_id: user.id })
+            .await;
+        client
+            .user
+            .test(crate::endpoint::games::reversi::r#match::Request { user_id: admin.id })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn subscribe_unsubscribe() {
+        let client = TestClient::new().await;
+        let game = create_game(&client).await;
+        let mut stream = client.channel(Request { game_id: game.id }).await.unwrap();
+        stream.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn update_settings() {
+        let client = TestClient::new().await;
+        let game = create_game(&client).await;
+        let mut admin_stream = client
+            .admin
+            .channel(Request { game_id: game.id })
+            .await
+            .unwrap();
+        let mut user_stream = client
+            .user
+            .channel(Request { game_id: game.id })
+            .await
+            .unwrap();
+
+        user_stream
+            .send(Message::UpdateSettings(ReversiSetting::Bw(
+                ReversiBw::User1,
+            )))
+            .await
+            .unwrap();
+        loop {
+            match admin_stream.next().await.unwrap().unwrap() {
+                GamesReversiGameStreamEvent::UpdateSettings(ReversiSetting::Bw(bw)) => {
+                    assert_eq!(bw, ReversiBw::User1);
+                    break;
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn start_and_set() {
+        let client = TestClient::new().await;
+        let game = create_game(&client).await;
+        let mut admin_stream = client
+            .admin
+            .channel(Request { game_id: game.id })
+            .await
+            .unwrap();
+        let mut user_stream = client
+            .user
+            .channel(Request { game_id: game.id })
+            .await
+            .unwrap();
+
+        admin_stream.send(Message::Accept {}).await.unwrap();
+        user_stream.send(Message::Accept {}).await.unwrap();
+        let game = loop {
+            match admin_stream.next().await.unwrap().unwrap() {
+                GamesReversiGameStreamEvent::Started(game) => break game,
+                _ => continue,
+            }
+        };
+
+        // find a legal move for black in the initial map
+        let map = game.map.clone().unwrap();
+        let width = map[0].len() as u64;
+        let pos = map
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.chars().enumerate().map(move |(x, c)| (x, y, c)))
+            .find(|&(_, _, c)| c == 'w')
+            .map(|(x, y, _)| x as u64 + (y as u64 + 1) * width)
+            .unwrap();
+        let admin = client.admin.me().await;
+        let black_stream =
+            if game.user_id_of(crate::model::reversi::ReversiColor::Black) == Some(admin.id) {
+                &mut admin_stream
+            } else {
+                &mut user_stream
+            };
+        black_stream.send(Message::Set { pos }).await.unwrap();
+    }
+}
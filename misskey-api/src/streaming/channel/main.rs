@@ -2,7 +2,7 @@
 use crate::model::registry::{RegistryKey, RegistryScope, RegistryValue};
 use crate::model::{
     antenna::Antenna, drive::DriveFile, id::Id, messaging::MessagingMessage, note::Note,
-    notification::Notification, signin::Signin, user::User,
+    notification::Notification, reversi::ReversiMatching, signin::Signin, user::User,
 };
 use crate::streaming::channel::NoOutgoing;
 
@@ -41,8 +41,7 @@ pub enum MainStreamEvent {
     ReadAllChannels,
     MyTokenRegenerated,
     ReversiNoInvites,
-    /// TODO: Implement
-    ReversiInvited {},
+    ReversiInvited(ReversiMatching),
     /// TODO: Implement
     PageEvent {},
     Signin(Signin),
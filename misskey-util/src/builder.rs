@@ -12,6 +12,8 @@ mod admin;
 mod antenna;
 mod clip;
 mod drive;
+mod hashtag;
+mod instance;
 mod me;
 mod messaging;
 mod misc;
@@ -21,9 +23,16 @@ mod user;
 
 #[cfg(feature = "12-47-0")]
 mod channel;
+#[cfg(feature = "12-79-0")]
+mod gallery;
+#[cfg(feature = "13-10-0")]
+mod role;
+#[cfg(feature = "12-112-0")]
+mod webhook;
 
 pub use admin::{
-    AnnouncementUpdateBuilder, EmojiUpdateBuilder, MetaUpdateBuilder, ServerLogListBuilder,
+    AnnouncementUpdateBuilder, EmojiUpdateBuilder, MetaUpdateBuilder, ServerFileListBuilder,
+    ServerLogListBuilder,
 };
 pub use antenna::{AntennaBuilder, AntennaUpdateBuilder};
 pub use clip::{ClipBuilder, ClipUpdateBuilder};
@@ -31,6 +40,8 @@ pub use drive::{
     DriveFileBuilder, DriveFileListBuilder, DriveFileUpdateBuilder, DriveFileUrlBuilder,
     DriveFolderUpdateBuilder,
 };
+pub use hashtag::HashtagListBuilder;
+pub use instance::InstanceListBuilder;
 pub use me::{IntoUserFields, MeUpdateBuilder};
 pub use messaging::MessagingMessageBuilder;
 pub use note::NoteBuilder;
@@ -41,6 +52,18 @@ pub use user::UserListBuilder;
 #[cfg_attr(docsrs, doc(cfg(feature = "12-47-0")))]
 pub use channel::{ChannelBuilder, ChannelUpdateBuilder};
 
+#[cfg(feature = "12-79-0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+pub use gallery::{GalleryPostBuilder, GalleryPostUpdateBuilder};
+
+#[cfg(feature = "12-112-0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "12-112-0")))]
+pub use webhook::{WebhookBuilder, WebhookUpdateBuilder};
+
+#[cfg(feature = "13-10-0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+pub use role::{RoleBuilder, RoleUpdateBuilder};
+
 #[cfg(feature = "12-27-0")]
 #[cfg_attr(docsrs, doc(cfg(feature = "12-27-0")))]
 pub use misc::NotificationBuilder;
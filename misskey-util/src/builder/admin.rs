@@ -1,13 +1,16 @@
+use crate::pager::{BackwardPager, BoxPager, PagerStream};
 use crate::Error;
 
+use mime::Mime;
 #[cfg(feature = "12-62-0")]
 use misskey_api::model::clip::Clip;
 #[cfg(feature = "12-9-0")]
 use misskey_api::model::emoji::Emoji;
 use misskey_api::model::{
     announcement::Announcement,
+    drive::DriveFile,
     log::{Log, LogLevel},
-    user::User,
+    user::{User, UserOrigin},
 };
 use misskey_api::{endpoint, EntityRef};
 use misskey_core::Client;
@@ -118,6 +121,65 @@ impl<C: Client> ServerLogListBuilder<C> {
     }
 }
 
+/// Builder for the [`server_files`][`crate::ClientExt::server_files`] method.
+pub struct ServerFileListBuilder<C> {
+    client: C,
+    request: endpoint::admin::drive::files::Request,
+}
+
+impl<C> ServerFileListBuilder<C> {
+    /// Creates a builder with the client.
+    pub fn new(client: C) -> Self {
+        let request = endpoint::admin::drive::files::Request::default();
+        ServerFileListBuilder { client, request }
+    }
+
+    /// Gets the request object for reuse.
+    pub fn as_request(&self) -> &endpoint::admin::drive::files::Request {
+        &self.request
+    }
+
+    /// Limits the listed files to those of the specified MIME type.
+    pub fn type_(&mut self, type_: Mime) -> &mut Self {
+        self.request.type_.replace(type_);
+        self
+    }
+
+    /// Limits the listed files by the origin of its owner.
+    pub fn origin(&mut self, origin: UserOrigin) -> &mut Self {
+        self.request.origin.replace(origin);
+        self
+    }
+
+    /// Limits the listed files to those of local users.
+    ///
+    /// This is equivalent to `.origin(UserOrigin::Local)`.
+    pub fn local(&mut self) -> &mut Self {
+        self.origin(UserOrigin::Local)
+    }
+
+    /// Limits the listed files to those of remote users.
+    ///
+    /// This is equivalent to `.origin(UserOrigin::Remote)`.
+    pub fn remote(&mut self) -> &mut Self {
+        self.origin(UserOrigin::Remote)
+    }
+
+    /// Limits the listed files to those of the users on the specified host.
+    pub fn host(&mut self, host: impl Into<String>) -> &mut Self {
+        self.request.hostname.replace(host.into());
+        self
+    }
+}
+
+impl<C: Client + Sync> ServerFileListBuilder<C> {
+    /// Lists the files.
+    pub fn list(&self) -> PagerStream<BoxPager<'_, C, DriveFile>> {
+        let pager = BackwardPager::new(&self.client, self.request.clone());
+        PagerStream::new(Box::pin(pager))
+    }
+}
+
 /// Builder for the [`update_meta`][`crate::ClientExt::update_meta`] method.
 pub struct MetaUpdateBuilder<C> {
     client: C,
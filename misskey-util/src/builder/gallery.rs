@@ -0,0 +1,175 @@
+use std::path::{Path, PathBuf};
+
+use crate::{Error, UploadFileClientExt};
+
+use misskey_api::model::{drive::DriveFile, gallery::GalleryPost};
+use misskey_api::{endpoint, EntityRef};
+use misskey_core::{Client, UploadFileClient};
+
+/// Builder for the [`build_gallery_post`][`crate::UploadFileClientExt::build_gallery_post`] method.
+pub struct GalleryPostBuilder<C> {
+    client: C,
+    request: endpoint::gallery::posts::create::Request,
+    paths: Vec<PathBuf>,
+}
+
+impl<C> GalleryPostBuilder<C> {
+    /// Creates a builder with the client.
+    pub fn new(client: C) -> Self {
+        let request = endpoint::gallery::posts::create::Request {
+            title: String::default(),
+            description: None,
+            file_ids: Vec::new(),
+            is_sensitive: None,
+        };
+        GalleryPostBuilder {
+            client,
+            request,
+            paths: Vec::new(),
+        }
+    }
+
+    /// Gets the request object for reuse.
+    ///
+    /// The files to be uploaded are not included in the request until the post is created.
+    pub fn as_request(&self) -> &endpoint::gallery::posts::create::Request {
+        &self.request
+    }
+
+    /// Sets the title of the post.
+    pub fn title(&mut self, title: impl Into<String>) -> &mut Self {
+        self.request.title = title.into();
+        self
+    }
+
+    /// Sets the description of the post.
+    pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.request.description.replace(description.into());
+        self
+    }
+
+    /// Sets whether the post contains NSFW content.
+    pub fn sensitive(&mut self, sensitive: bool) -> &mut Self {
+        self.request.is_sensitive.replace(sensitive);
+        self
+    }
+
+    /// Attaches the file already on the drive to the post.
+    pub fn attach_file(&mut self, file: impl EntityRef<DriveFile>) -> &mut Self {
+        self.request.file_ids.push(file.entity_ref());
+        self
+    }
+
+    /// Attaches the files already on the drive to the post.
+    pub fn attach_files(
+        &mut self,
+        files: impl IntoIterator<Item = impl EntityRef<DriveFile>>,
+    ) -> &mut Self {
+        let ids = files.into_iter().map(|file| file.entity_ref());
+        self.request.file_ids.extend(ids);
+        self
+    }
+
+    /// Attaches the file at the specified local path to the post.
+    ///
+    /// The file is uploaded to the drive when [`create`][GalleryPostBuilder::create] is called,
+    /// and attached after the files specified with [`attach_file`][GalleryPostBuilder::attach_file].
+    pub fn upload_file(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.paths.push(path.as_ref().to_owned());
+        self
+    }
+}
+
+impl<C: UploadFileClient + Sync> GalleryPostBuilder<C> {
+    /// Uploads the files and creates the post.
+    ///
+    /// The files uploaded before an error occurs are left on the drive.
+    pub async fn create(&self) -> Result<GalleryPost, Error<C::Error>> {
+        let mut request = self.request.clone();
+        for path in &self.paths {
+            let file = self.client.upload_file(path).await?;
+            request.file_ids.push(file.id);
+        }
+
+        let post = self
+            .client
+            .request(request)
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+        Ok(post)
+    }
+}
+
+/// Builder for the [`update_gallery_post`][`crate::ClientExt::update_gallery_post`] method.
+pub struct GalleryPostUpdateBuilder<C> {
+    client: C,
+    request: endpoint::gallery::posts::update::Request,
+}
+
+impl<C> GalleryPostUpdateBuilder<C> {
+    /// Creates a builder with the client and the post you are going to update.
+    pub fn new(client: C, post: GalleryPost) -> Self {
+        let GalleryPost {
+            id,
+            title,
+            description,
+            file_ids,
+            is_sensitive,
+            ..
+        } = post;
+        let request = endpoint::gallery::posts::update::Request {
+            post_id: id,
+            title,
+            description,
+            file_ids,
+            is_sensitive: Some(is_sensitive),
+        };
+        GalleryPostUpdateBuilder { client, request }
+    }
+
+    /// Gets the request object for reuse.
+    pub fn as_request(&self) -> &endpoint::gallery::posts::update::Request {
+        &self.request
+    }
+
+    /// Sets the title of the post.
+    pub fn title(&mut self, title: impl Into<String>) -> &mut Self {
+        self.request.title = title.into();
+        self
+    }
+
+    /// Sets the description of the post.
+    pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+        self.request.description.replace(description.into());
+        self
+    }
+
+    /// Sets whether the post contains NSFW content.
+    pub fn sensitive(&mut self, sensitive: bool) -> &mut Self {
+        self.request.is_sensitive.replace(sensitive);
+        self
+    }
+
+    /// Replaces the files attached to the post.
+    pub fn files(
+        &mut self,
+        files: impl IntoIterator<Item = impl EntityRef<DriveFile>>,
+    ) -> &mut Self {
+        self.request.file_ids = files.into_iter().map(|file| file.entity_ref()).collect();
+        self
+    }
+}
+
+impl<C: Client> GalleryPostUpdateBuilder<C> {
+    /// Updates the post.
+    pub async fn update(&self) -> Result<GalleryPost, Error<C::Error>> {
+        let post = self
+            .client
+            .request(&self.request)
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+        Ok(post)
+    }
+}
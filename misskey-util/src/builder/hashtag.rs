@@ -0,0 +1,95 @@
+use crate::Error;
+
+use misskey_api::endpoint;
+use misskey_api::model::{
+    hashtag::{Hashtag, HashtagSortKey},
+    sort::SortOrder,
+};
+use misskey_core::Client;
+
+/// Builder for the [`hashtags`][`crate::ClientExt::hashtags`] method.
+pub struct HashtagListBuilder<C> {
+    client: C,
+    request: endpoint::hashtags::list::Request,
+}
+
+impl<C> HashtagListBuilder<C> {
+    /// Creates a builder with the client.
+    ///
+    /// The hashtags are sorted in descending order by number of users who mentioned them
+    /// by default.
+    pub fn new(client: C) -> Self {
+        let request = endpoint::hashtags::list::Request::builder()
+            .sort(SortOrder::Descending(HashtagSortKey::MentionedUsers))
+            .build();
+        HashtagListBuilder { client, request }
+    }
+
+    /// Gets the request object for reuse.
+    pub fn as_request(&self) -> &endpoint::hashtags::list::Request {
+        &self.request
+    }
+
+    /// Limits the number of hashtags to be listed.
+    pub fn take(&mut self, length: u8) -> &mut Self {
+        self.request.limit.replace(length);
+        self
+    }
+
+    /// Sorts the results by the given order.
+    pub fn order(&mut self, order: SortOrder<HashtagSortKey>) -> &mut Self {
+        self.request.sort = order;
+        self
+    }
+
+    /// Sorts the results in descending order by the given key.
+    pub fn sort_by(&mut self, key: HashtagSortKey) -> &mut Self {
+        self.order(SortOrder::Descending(key))
+    }
+
+    /// Sorts the results in descending order by number of users who mentioned the hashtags.
+    ///
+    /// This is equivalent to `.sort_by(HashtagSortKey::MentionedUsers)`.
+    pub fn sort_by_mentioned_users(&mut self) -> &mut Self {
+        self.sort_by(HashtagSortKey::MentionedUsers)
+    }
+
+    /// Sorts the results in descending order by number of users who have the hashtags in
+    /// their profiles.
+    ///
+    /// This is equivalent to `.sort_by(HashtagSortKey::AttachedUsers)`.
+    pub fn sort_by_attached_users(&mut self) -> &mut Self {
+        self.sort_by(HashtagSortKey::AttachedUsers)
+    }
+
+    /// Limits the listed hashtags to those in the profiles of any users.
+    pub fn attached_to_user_only(&mut self) -> &mut Self {
+        self.request.attached_to_user_only.replace(true);
+        self
+    }
+
+    /// Limits the listed hashtags to those in the profiles of local users.
+    pub fn attached_to_local_user_only(&mut self) -> &mut Self {
+        self.request.attached_to_local_user_only.replace(true);
+        self
+    }
+
+    /// Limits the listed hashtags to those in the profiles of remote users.
+    pub fn attached_to_remote_user_only(&mut self) -> &mut Self {
+        self.request.attached_to_remote_user_only.replace(true);
+        self
+    }
+}
+
+impl<C: Client> HashtagListBuilder<C> {
+    /// Lists the hashtags.
+    pub async fn list(&self) -> Result<Vec<Hashtag>, Error<C::Error>> {
+        let hashtags = self
+            .client
+            .request(&self.request)
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+        Ok(hashtags)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::pager::{BoxPager, OffsetPager, PagerStream};
+
+use misskey_api::endpoint;
+use misskey_api::model::{
+    instance::{Instance, InstanceSortKey},
+    sort::SortOrder,
+};
+use misskey_core::Client;
+
+/// Builder for the [`federated_instances`][`crate::ClientExt::federated_instances`] method.
+pub struct InstanceListBuilder<C> {
+    client: C,
+    request: endpoint::federation::instances::Request,
+}
+
+impl<C> InstanceListBuilder<C> {
+    /// Creates a builder with the client.
+    pub fn new(client: C) -> Self {
+        let request = endpoint::federation::instances::Request::default();
+        InstanceListBuilder { client, request }
+    }
+
+    /// Gets the request object for reuse.
+    pub fn as_request(&self) -> &endpoint::federation::instances::Request {
+        &self.request
+    }
+
+    /// Sorts the results by the given order.
+    pub fn order(&mut self, order: SortOrder<InstanceSortKey>) -> &mut Self {
+        self.request.sort.replace(order);
+        self
+    }
+
+    /// Sorts the results in ascending order by the given key.
+    pub fn sort_by(&mut self, key: InstanceSortKey) -> &mut Self {
+        self.order(SortOrder::Ascending(key))
+    }
+
+    /// Sorts the results in ascending order by number of notes.
+    ///
+    /// This is equivalent to `.sort_by(InstanceSortKey::Notes)`.
+    pub fn sort_by_notes(&mut self) -> &mut Self {
+        self.sort_by(InstanceSortKey::Notes)
+    }
+
+    /// Sorts the results in ascending order by number of users.
+    ///
+    /// This is equivalent to `.sort_by(InstanceSortKey::Users)`.
+    pub fn sort_by_users(&mut self) -> &mut Self {
+        self.sort_by(InstanceSortKey::Users)
+    }
+
+    /// Sorts the results in ascending order by the date the instance was first seen.
+    ///
+    /// This is equivalent to `.sort_by(InstanceSortKey::CaughtAt)`.
+    pub fn sort_by_caught_date(&mut self) -> &mut Self {
+        self.sort_by(InstanceSortKey::CaughtAt)
+    }
+
+    /// Sorts the results in ascending order by the date of the last communication.
+    ///
+    /// This is equivalent to `.sort_by(InstanceSortKey::LastCommunicatedAt)`.
+    pub fn sort_by_last_communication(&mut self) -> &mut Self {
+        self.sort_by(InstanceSortKey::LastCommunicatedAt)
+    }
+
+    /// Limits the listed instances to those whose host contains the given string.
+    pub fn host(&mut self, host: impl Into<String>) -> &mut Self {
+        self.request.host.replace(host.into());
+        self
+    }
+
+    /// Limits the listed instances by whether they are blocked.
+    pub fn blocked(&mut self, blocked: bool) -> &mut Self {
+        self.request.blocked.replace(blocked);
+        self
+    }
+
+    /// Limits the listed instances by whether they are not responding.
+    pub fn not_responding(&mut self, not_responding: bool) -> &mut Self {
+        self.request.not_responding.replace(not_responding);
+        self
+    }
+
+    /// Limits the listed instances by whether the delivery to them is suspended.
+    pub fn suspended(&mut self, suspended: bool) -> &mut Self {
+        self.request.suspended.replace(suspended);
+        self
+    }
+
+    /// Limits the listed instances by whether they have followers or followees.
+    pub fn federating(&mut self, federating: bool) -> &mut Self {
+        self.request.federating.replace(federating);
+        self
+    }
+
+    /// Limits the listed instances by whether local users follow them.
+    pub fn subscribing(&mut self, subscribing: bool) -> &mut Self {
+        self.request.subscribing.replace(subscribing);
+        self
+    }
+
+    /// Limits the listed instances by whether they follow local users.
+    pub fn publishing(&mut self, publishing: bool) -> &mut Self {
+        self.request.publishing.replace(publishing);
+        self
+    }
+}
+
+impl<C: Client + Sync> InstanceListBuilder<C> {
+    /// Lists the instances.
+    pub fn list(&self) -> PagerStream<BoxPager<'_, C, Instance>> {
+        let pager = OffsetPager::new(&self.client, self.request.clone());
+        PagerStream::new(Box::pin(pager))
+    }
+}
@@ -84,6 +84,8 @@ impl<C> MeUpdateBuilder<C> {
 
     update_builder_string_option_field! {
         pub name;
+        ///
+        /// The description is written in MFM (see [`MfmBuilder`][`misskey_api::mfm::MfmBuilder`]).
         pub description;
         pub language { lang };
         pub location;
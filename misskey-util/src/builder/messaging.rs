@@ -30,6 +30,9 @@ impl<C> MessagingMessageBuilder<C> {
     }
 
     /// Sets the text content of the message.
+    ///
+    /// See [`MfmBuilder`][`misskey_api::mfm::MfmBuilder`] to include user-supplied strings
+    /// in the text without escaping them by hand.
     pub fn text(&mut self, text: impl Into<String>) -> &mut Self {
         self.request.text.replace(text.into());
         self
@@ -61,6 +61,9 @@ impl<C> NoteBuilder<C> {
     }
 
     /// Sets the text of the note.
+    ///
+    /// The text is written in MFM, which can be composed safely from user-supplied strings
+    /// with [`MfmBuilder`][`misskey_api::mfm::MfmBuilder`].
     pub fn text(&mut self, text: impl Into<String>) -> &mut Self {
         self.request.text.replace(text.into());
         self
@@ -0,0 +1,202 @@
+use crate::Error;
+
+use misskey_api::endpoint;
+use misskey_api::model::role::{Role, RoleCondFormula, RolePolicies, RoleTarget};
+use misskey_core::Client;
+use url::Url;
+
+macro_rules! impl_role_fields {
+    () => {
+        /// Sets the name of the role.
+        pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
+            self.request.name = name.into();
+            self
+        }
+
+        /// Sets the description of the role.
+        pub fn description(&mut self, description: impl Into<String>) -> &mut Self {
+            self.request.description = description.into();
+            self
+        }
+
+        /// Sets the color of the role, such as `#ff0000`.
+        pub fn color(&mut self, color: impl Into<String>) -> &mut Self {
+            self.request.color.replace(color.into());
+            self
+        }
+
+        /// Sets the URL of the icon of the role.
+        pub fn icon_url(&mut self, icon_url: Url) -> &mut Self {
+            self.request.icon_url.replace(icon_url);
+            self
+        }
+
+        /// Makes the role assigned manually by moderators.
+        pub fn manual(&mut self) -> &mut Self {
+            self.request.target = RoleTarget::Manual;
+            self
+        }
+
+        /// Makes the role assigned automatically to the users matching the formula.
+        pub fn conditional(&mut self, formula: RoleCondFormula) -> &mut Self {
+            self.request.target = RoleTarget::Conditional;
+            self.request.cond_formula = formula;
+            self
+        }
+
+        /// Sets whether the role is shown in the profiles of the users.
+        pub fn public(&mut self, public: bool) -> &mut Self {
+            self.request.is_public = public;
+            self
+        }
+
+        /// Sets whether the users with the role have moderator privileges.
+        pub fn moderator(&mut self, moderator: bool) -> &mut Self {
+            self.request.is_moderator = moderator;
+            self
+        }
+
+        /// Sets whether the users with the role have administrator privileges.
+        pub fn administrator(&mut self, administrator: bool) -> &mut Self {
+            self.request.is_administrator = administrator;
+            self
+        }
+
+        /// Sets whether the role is shown as a badge next to the names of the users.
+        pub fn badge(&mut self, badge: bool) -> &mut Self {
+            self.request.as_badge = badge;
+            self
+        }
+
+        /// Sets whether moderators can assign and unassign the role.
+        pub fn editable_by_moderator(&mut self, editable: bool) -> &mut Self {
+            self.request.can_edit_members_by_moderator = editable;
+            self
+        }
+
+        /// Sets the display order of the role.
+        pub fn display_order(&mut self, display_order: i64) -> &mut Self {
+            self.request.display_order = display_order;
+            self
+        }
+
+        /// Sets the policies overridden by the role.
+        pub fn policies(&mut self, policies: RolePolicies) -> &mut Self {
+            self.request.policies = policies;
+            self
+        }
+    };
+}
+
+/// Builder for the [`build_role`][`crate::ClientExt::build_role`] method.
+pub struct RoleBuilder<C> {
+    client: C,
+    request: endpoint::admin::roles::create::Request,
+}
+
+impl<C> RoleBuilder<C> {
+    /// Creates a builder with the client.
+    pub fn new(client: C) -> Self {
+        let request = endpoint::admin::roles::create::Request::builder()
+            .name(String::default())
+            .build();
+        RoleBuilder { client, request }
+    }
+
+    /// Gets the request object for reuse.
+    pub fn as_request(&self) -> &endpoint::admin::roles::create::Request {
+        &self.request
+    }
+
+    impl_role_fields!();
+}
+
+impl<C: Client> RoleBuilder<C> {
+    /// Creates the role.
+    pub async fn create(&self) -> Result<Role, Error<C::Error>> {
+        let role = self
+            .client
+            .request(&self.request)
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+        Ok(role)
+    }
+}
+
+/// Builder for the [`update_role`][`crate::ClientExt::update_role`] method.
+pub struct RoleUpdateBuilder<C> {
+    client: C,
+    request: endpoint::admin::roles::update::Request,
+}
+
+impl<C> RoleUpdateBuilder<C> {
+    /// Creates a builder with the client and the role you are going to update.
+    pub fn new(client: C, role: Role) -> Self {
+        let Role {
+            id,
+            name,
+            description,
+            color,
+            icon_url,
+            target,
+            cond_formula,
+            is_public,
+            is_moderator,
+            is_administrator,
+            as_badge,
+            can_edit_members_by_moderator,
+            display_order,
+            policies,
+            ..
+        } = role;
+        let request = endpoint::admin::roles::update::Request {
+            role_id: id,
+            name,
+            description,
+            color,
+            icon_url,
+            target,
+            cond_formula,
+            is_public,
+            is_moderator,
+            is_administrator,
+            as_badge,
+            can_edit_members_by_moderator,
+            display_order,
+            policies,
+        };
+        RoleUpdateBuilder { client, request }
+    }
+
+    /// Gets the request object for reuse.
+    pub fn as_request(&self) -> &endpoint::admin::roles::update::Request {
+        &self.request
+    }
+
+    impl_role_fields!();
+
+    /// Deletes the color of the role.
+    pub fn delete_color(&mut self) -> &mut Self {
+        self.request.color.take();
+        self
+    }
+
+    /// Deletes the icon of the role.
+    pub fn delete_icon_url(&mut self) -> &mut Self {
+        self.request.icon_url.take();
+        self
+    }
+}
+
+impl<C: Client> RoleUpdateBuilder<C> {
+    /// Updates the role.
+    pub async fn update(&self) -> Result<(), Error<C::Error>> {
+        self.client
+            .request(&self.request)
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use crate::Error;
+
+use misskey_api::endpoint;
+use misskey_api::model::webhook::{Webhook, WebhookEventType};
+use misskey_core::Client;
+use url::Url;
+
+/// Builder for the [`build_webhook`][`crate::ClientExt::build_webhook`] method.
+pub struct WebhookBuilder<C> {
+    client: C,
+    request: endpoint::i::webhooks::create::Request,
+}
+
+impl<C> WebhookBuilder<C> {
+    /// Creates a builder with the client and the URL the events are sent to.
+    pub fn new(client: C, url: Url) -> Self {
+        let request = endpoint::i::webhooks::create::Request {
+            name: String::default(),
+            url,
+            secret: String::default(),
+            on: Vec::new(),
+        };
+        WebhookBuilder { client, request }
+    }
+
+    /// Gets the request object for reuse.
+    pub fn as_request(&self) -> &endpoint::i::webhooks::create::Request {
+        &self.request
+    }
+
+    /// Sets the name of the webhook.
+    pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.request.name = name.into();
+        self
+    }
+
+    /// Sets the secret sent in the `X-Misskey-Hook-Secret` header.
+    pub fn secret(&mut self, secret: impl Into<String>) -> &mut Self {
+        self.request.secret = secret.into();
+        self
+    }
+
+    /// Adds the type of events to be sent to the webhook.
+    pub fn on(&mut self, event: WebhookEventType) -> &mut Self {
+        if !self.request.on.contains(&event) {
+            self.request.on.push(event);
+        }
+        self
+    }
+
+    /// Sets the types of events to be sent to the webhook.
+    pub fn events(&mut self, events: impl IntoIterator<Item = WebhookEventType>) -> &mut Self {
+        self.request.on = events.into_iter().collect();
+        self
+    }
+}
+
+impl<C: Client> WebhookBuilder<C> {
+    /// Creates the webhook.
+    pub async fn create(&self) -> Result<Webhook, Error<C::Error>> {
+        let webhook = self
+            .client
+            .request(&self.request)
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+        Ok(webhook)
+    }
+}
+
+/// Builder for the [`update_webhook`][`crate::ClientExt::update_webhook`] method.
+pub struct WebhookUpdateBuilder<C> {
+    client: C,
+    request: endpoint::i::webhooks::update::Request,
+}
+
+impl<C> WebhookUpdateBuilder<C> {
+    /// Creates a builder with the client and the webhook you are going to update.
+    pub fn new(client: C, webhook: Webhook) -> Self {
+        let Webhook {
+            id,
+            name,
+            url,
+            secret,
+            on,
+            active,
+            ..
+        } = webhook;
+        let request = endpoint::i::webhooks::update::Request {
+            webhook_id: id,
+            name,
+            url,
+            secret,
+            on,
+            active,
+        };
+        WebhookUpdateBuilder { client, request }
+    }
+
+    /// Gets the request object for reuse.
+    pub fn as_request(&self) -> &endpoint::i::webhooks::update::Request {
+        &self.request
+    }
+
+    /// Sets the name of the webhook.
+    pub fn name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.request.name = name.into();
+        self
+    }
+
+    /// Sets the URL the events are sent to.
+    pub fn url(&mut self, url: Url) -> &mut Self {
+        self.request.url = url;
+        self
+    }
+
+    /// Sets the secret sent in the `X-Misskey-Hook-Secret` header.
+    pub fn secret(&mut self, secret: impl Into<String>) -> &mut Self {
+        self.request.secret = secret.into();
+        self
+    }
+
+    /// Adds the type of events to be sent to the webhook.
+    pub fn on(&mut self, event: WebhookEventType) -> &mut Self {
+        if !self.request.on.contains(&event) {
+            self.request.on.push(event);
+        }
+        self
+    }
+
+    /// Stops sending the type of events to the webhook.
+    pub fn off(&mut self, event: WebhookEventType) -> &mut Self {
+        self.request.on.retain(|e| *e != event);
+        self
+    }
+
+    /// Sets the types of events to be sent to the webhook.
+    pub fn events(&mut self, events: impl IntoIterator<Item = WebhookEventType>) -> &mut Self {
+        self.request.on = events.into_iter().collect();
+        self
+    }
+
+    /// Sets whether the webhook is active.
+    pub fn active(&mut self, active: bool) -> &mut Self {
+        self.request.active = active;
+        self
+    }
+}
+
+impl<C: Client> WebhookUpdateBuilder<C> {
+    /// Updates the webhook.
+    pub async fn update(&self) -> Result<(), Error<C::Error>> {
+        self.client
+            .request(&self.request)
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(feature = "12-67-0")]
 use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 
 #[cfg(feature = "12-9-0")]
 use crate::builder::EmojiUpdateBuilder;
@@ -9,26 +10,42 @@ use crate::builder::NotificationBuilder;
 use crate::builder::{
     AnnouncementUpdateBuilder, AntennaBuilder, AntennaUpdateBuilder, DriveFileBuilder,
     DriveFileListBuilder, DriveFileUpdateBuilder, DriveFileUrlBuilder, DriveFolderUpdateBuilder,
-    MeUpdateBuilder, MessagingMessageBuilder, MetaUpdateBuilder, NoteBuilder, PageBuilder,
-    PageUpdateBuilder, ServerLogListBuilder, UserListBuilder,
+    HashtagListBuilder, InstanceListBuilder, MeUpdateBuilder, MessagingMessageBuilder,
+    MetaUpdateBuilder, NoteBuilder, PageBuilder, PageUpdateBuilder, ServerFileListBuilder,
+    ServerLogListBuilder, UserListBuilder,
 };
 #[cfg(feature = "12-47-0")]
 use crate::builder::{ChannelBuilder, ChannelUpdateBuilder};
 #[cfg(feature = "12-57-0")]
 use crate::builder::{ClipBuilder, ClipUpdateBuilder};
+#[cfg(feature = "12-79-0")]
+use crate::builder::{GalleryPostBuilder, GalleryPostUpdateBuilder};
+#[cfg(feature = "13-10-0")]
+use crate::builder::{RoleBuilder, RoleUpdateBuilder};
+#[cfg(feature = "12-112-0")]
+use crate::builder::{WebhookBuilder, WebhookUpdateBuilder};
 use crate::pager::{BackwardPager, BoxPager, ForwardPager, OffsetPager, PagerStream};
 use crate::Error;
-use crate::{TimelineCursor, TimelineRange};
+use crate::{TimelineCursor, TimelineRange, TrendEvent, WordMuteFilter};
 
 #[cfg(feature = "12-13-0")]
 use chrono::DateTime;
 use chrono::Utc;
-use futures::{future::BoxFuture, stream::TryStreamExt};
+use futures::{
+    future::BoxFuture,
+    stream::{BoxStream, TryStreamExt},
+};
 use mime::Mime;
 #[cfg(feature = "12-47-0")]
 use misskey_api::model::channel::Channel;
+#[cfg(feature = "12-79-0")]
+use misskey_api::model::gallery::GalleryPost;
 #[cfg(feature = "12-67-0")]
 use misskey_api::model::registry::{RegistryKey, RegistryScope, RegistryValue};
+#[cfg(feature = "13-10-0")]
+use misskey_api::model::role::{Policies, Role, RoleAssignment};
+#[cfg(feature = "12-112-0")]
+use misskey_api::model::webhook::{Webhook, WebhookEventType};
 use misskey_api::model::{
     abuse_user_report::AbuseUserReport,
     announcement::Announcement,
@@ -36,8 +53,10 @@ use misskey_api::model::{
     clip::Clip,
     drive::{DriveFile, DriveFolder},
     emoji::Emoji,
-    following::FollowRequest,
+    following::{FollowRequest, Following},
+    hashtag::Hashtag,
     id::Id,
+    instance::Instance,
     log::ModerationLog,
     messaging::MessagingMessage,
     meta::Meta,
@@ -45,7 +64,10 @@ use misskey_api::model::{
     notification::Notification,
     page::Page,
     query::Query,
-    user::{User, UserRelation},
+    queue::{DelayedJobCount, QueueDomain, QueueJob, QueueJobState},
+    relay::Relay,
+    sort::SortOrder,
+    user::{User, UserRelation, UserSortKey},
     user_group::{UserGroup, UserGroupInvitation},
     user_list::UserList,
 };
@@ -243,6 +265,71 @@ macro_rules! impl_timeline_method {
         }
     };
 }
+
+/// Splits a handle such as `acct:alice@example.com` or `@alice` into the username and the host.
+fn parse_acct(s: &str) -> Option<(&str, Option<&str>)> {
+    let acct = s
+        .strip_prefix("acct:")
+        .or_else(|| s.strip_prefix('@'))
+        .unwrap_or(s);
+    if acct.contains(['/', ':']) {
+        return None;
+    }
+    match acct.split_once('@') {
+        Some(("", _)) | Some((_, "")) => None,
+        Some((username, host)) => Some((username, Some(host))),
+        // bare words without the prefix are not regarded as handles
+        None if acct.is_empty() || acct.len() == s.len() => None,
+        None => Some((acct, None)),
+    }
+}
+
+/// Splits a profile URL such as `https://example.com/@alice` into the username and the host.
+fn parse_profile_url(s: &str) -> Option<(String, String)> {
+    let url = url::Url::parse(s).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let mut segments = url.path_segments()?;
+    let acct = segments.next()?.strip_prefix('@')?;
+    if segments.next().is_some() {
+        return None;
+    }
+    match acct.split_once('@') {
+        Some(("", _)) | Some((_, "")) => None,
+        Some((username, host)) => Some((username.to_owned(), host.to_owned())),
+        None if acct.is_empty() => None,
+        None => Some((acct.to_owned(), url.host_str()?.to_owned())),
+    }
+}
+
+/// Looks up the user with the username and the host among the users known to the server.
+async fn search_user<C: Client + ?Sized>(
+    client: &C,
+    username: &str,
+    host: Option<&str>,
+) -> Result<Option<User>, Error<C::Error>> {
+    let users = client
+        .request(endpoint::users::search_by_username_and_host::Request {
+            username: Some(username.to_owned()),
+            host: host.map(ToOwned::to_owned),
+            detail: None,
+            limit: Some(100),
+            offset: None,
+        })
+        .await
+        .map_err(Error::Client)?
+        .into_result()?;
+    let user = users.into_iter().find(|user| {
+        user.username.eq_ignore_ascii_case(username)
+            && match (&user.host, host) {
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                (None, None) => true,
+                _ => false,
+            }
+    });
+    Ok(user)
+}
 // }}}
 
 /// An extension trait for [`Client`][client] that provides convenient high-level APIs.
@@ -351,10 +438,50 @@ pub trait ClientExt: Client + Sync {
     fn mute(&self, user: impl EntityRef<User>) -> BoxFuture<Result<(), Error<Self::Error>>> {
         let user_id = user.entity_ref();
         Box::pin(async move {
-            self.request(endpoint::mute::create::Request { user_id })
-                .await
-                .map_err(Error::Client)?
-                .into_result()?;
+            self.request(endpoint::mute::create::Request {
+                user_id,
+                #[cfg(feature = "12-108-0")]
+                expires_at: None,
+            })
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Mutes the specified user until the given time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use misskey_util::ClientExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let client = misskey_test::test_client().await?;
+    /// # let user = client.me().await?;
+    /// use chrono::{Duration, Utc};
+    ///
+    /// client.mute_until(&user, Utc::now() + Duration::days(1)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "12-108-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-108-0")))]
+    fn mute_until(
+        &self,
+        user: impl EntityRef<User>,
+        expires_at: DateTime<Utc>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let user_id = user.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::mute::create::Request {
+                user_id,
+                expires_at: Some(expires_at),
+            })
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
             Ok(())
         })
     }
@@ -371,6 +498,40 @@ pub trait ClientExt: Client + Sync {
         })
     }
 
+    /// Mutes the renotes by the specified user.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn mute_renotes(
+        &self,
+        user: impl EntityRef<User>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let user_id = user.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::renote_mute::create::Request { user_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Unmutes the renotes by the specified user.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn unmute_renotes(
+        &self,
+        user: impl EntityRef<User>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let user_id = user.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::renote_mute::delete::Request { user_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
     /// Blocks the specified user.
     fn block(&self, user: impl EntityRef<User>) -> BoxFuture<Result<User, Error<Self::Error>>> {
         let user_id = user.entity_ref();
@@ -563,6 +724,46 @@ pub trait ClientExt: Client + Sync {
         PagerStream::new(Box::pin(pager))
     }
 
+    /// Lists the users whose renotes are muted by the user logged in with this client.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn renote_muting_users(&self) -> PagerStream<BoxPager<'_, Self, User>> {
+        let pager = BackwardPager::new(self, endpoint::renote_mute::list::Request::default())
+            .map_ok(|v| v.into_iter().map(|m| m.mutee).collect());
+        PagerStream::new(Box::pin(pager))
+    }
+
+    /// Returns a filter that hides notes by the words muted by the user logged in with this client.
+    ///
+    /// See [`WordMuteFilter`] for how the notes are matched.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use misskey_util::{ClientExt, StreamingClientExt};
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let client = misskey_test::test_websocket_client(misskey_test::env::token()).await?;
+    /// use futures::{future, stream::TryStreamExt};
+    ///
+    /// let filter = client.word_mute_filter().await?;
+    /// let mut notes = client
+    ///     .home_timeline()
+    ///     .await?
+    ///     .try_filter(|note| future::ready(!filter.is_muted(note)));
+    /// while let Some(note) = notes.try_next().await? {
+    ///     println!("{:?}", note.text);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn word_mute_filter(&self) -> BoxFuture<'_, Result<WordMuteFilter, Error<Self::Error>>> {
+        Box::pin(async move {
+            let me = self.me().await?;
+            Ok(WordMuteFilter::for_user(&me))
+        })
+    }
+
     /// Lists the users blocked by the user logged in with this client.
     fn blocking_users(&self) -> PagerStream<BoxPager<Self, User>> {
         let pager = BackwardPager::new(self, endpoint::blocking::list::Request::default())
@@ -3250,17 +3451,42 @@ pub trait ClientExt: Client + Sync {
     }
     // }}}
 
-    // {{{ Admin
-    /// Sets moderator privileges for the specified user.
+    // {{{ Gallery
+    /// Creates a gallery post with the given title and the files already on the drive.
     ///
-    /// This operation may require this client to be logged in with an admin account.
-    fn add_moderator(
+    /// To upload local files and attach them to the post, use
+    /// [`build_gallery_post`][`UploadFileClientExt::build_gallery_post`] instead.
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn create_gallery_post(
         &self,
-        user: impl EntityRef<User>,
-    ) -> BoxFuture<Result<(), Error<Self::Error>>> {
-        let user_id = user.entity_ref();
+        title: impl Into<String>,
+        files: impl IntoIterator<Item = impl EntityRef<DriveFile>>,
+    ) -> BoxFuture<'_, Result<GalleryPost, Error<Self::Error>>> {
+        let request = endpoint::gallery::posts::create::Request::builder()
+            .title(title)
+            .file_ids(files.into_iter().map(|file| file.entity_ref()).collect())
+            .build();
         Box::pin(async move {
-            self.request(endpoint::admin::moderators::add::Request { user_id })
+            let post = self
+                .request(request)
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(post)
+        })
+    }
+
+    /// Deletes the specified gallery post.
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn delete_gallery_post(
+        &self,
+        post: impl EntityRef<GalleryPost>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let post_id = post.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::gallery::posts::delete::Request { post_id })
                 .await
                 .map_err(Error::Client)?
                 .into_result()?;
@@ -3268,16 +3494,47 @@ pub trait ClientExt: Client + Sync {
         })
     }
 
-    /// Removes moderator privileges for the specified user.
+    /// Gets the corresponding gallery post from the ID.
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn get_gallery_post(
+        &self,
+        id: Id<GalleryPost>,
+    ) -> BoxFuture<'_, Result<GalleryPost, Error<Self::Error>>> {
+        Box::pin(async move {
+            let post = self
+                .request(endpoint::gallery::posts::show::Request { post_id: id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(post)
+        })
+    }
+
+    /// Updates the specified gallery post.
     ///
-    /// This operation may require this client to be logged in with an admin account.
-    fn remove_moderator(
+    /// This method actually returns a builder, namely [`GalleryPostUpdateBuilder`].
+    /// You can chain the method calls to it corresponding to the fields you want to update.
+    /// Finally, calling [`update`][builder_update] method will actually perform the update.
+    /// See [`GalleryPostUpdateBuilder`] for the fields that can be updated.
+    ///
+    /// [builder_update]: GalleryPostUpdateBuilder::update
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn update_gallery_post(&self, post: GalleryPost) -> GalleryPostUpdateBuilder<&Self> {
+        GalleryPostUpdateBuilder::new(self, post)
+    }
+
+    /// Gives a like to the specified gallery post.
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn like_gallery_post(
         &self,
-        user: impl EntityRef<User>,
-    ) -> BoxFuture<Result<(), Error<Self::Error>>> {
-        let user_id = user.entity_ref();
+        post: impl EntityRef<GalleryPost>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let post_id = post.entity_ref();
         Box::pin(async move {
-            self.request(endpoint::admin::moderators::remove::Request { user_id })
+            self.request(endpoint::gallery::posts::like::Request { post_id })
                 .await
                 .map_err(Error::Client)?
                 .into_result()?;
@@ -3285,87 +3542,104 @@ pub trait ClientExt: Client + Sync {
         })
     }
 
-    /// Promotes the specified note until the time.
-    ///
-    /// This operation may require moderator privileges.
-    #[cfg(feature = "12-13-0")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "12-13-0")))]
-    fn promote_note(
+    /// Removes a like from the specified gallery post.
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn unlike_gallery_post(
         &self,
-        note: impl EntityRef<Note>,
-        expires_at: DateTime<Utc>,
-    ) -> BoxFuture<Result<(), Error<Self::Error>>> {
-        let note_id = note.entity_ref();
+        post: impl EntityRef<GalleryPost>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let post_id = post.entity_ref();
         Box::pin(async move {
-            self.request(endpoint::admin::promo::create::Request {
-                note_id,
-                expires_at,
-            })
-            .await
-            .map_err(Error::Client)?
-            .into_result()?;
+            self.request(endpoint::gallery::posts::unlike::Request { post_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
             Ok(())
         })
     }
 
-    /// Lists the abuse user reports.
-    ///
-    /// This operation may require moderator privileges.
-    fn abuse_user_reports(&self) -> PagerStream<BoxPager<Self, AbuseUserReport>> {
+    /// Lists the gallery posts created by the user logged in with this client.
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn gallery_posts(&self) -> PagerStream<BoxPager<'_, Self, GalleryPost>> {
+        let pager = BackwardPager::new(self, endpoint::i::gallery::posts::Request::default());
+        PagerStream::new(Box::pin(pager))
+    }
+
+    /// Lists the gallery posts liked by the user logged in with this client.
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn liked_gallery_posts(&self) -> PagerStream<BoxPager<'_, Self, GalleryPost>> {
+        let pager = BackwardPager::new(self, endpoint::i::gallery::likes::Request::default())
+            .map_ok(|v| v.into_iter().map(|l| l.post).collect());
+        PagerStream::new(Box::pin(pager))
+    }
+
+    /// Lists the gallery posts created by the specified user.
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn user_gallery_posts(
+        &self,
+        user: impl EntityRef<User>,
+    ) -> PagerStream<BoxPager<'_, Self, GalleryPost>> {
         let pager = BackwardPager::new(
             self,
-            endpoint::admin::abuse_user_reports::Request::default(),
+            endpoint::users::gallery::posts::Request::builder()
+                .user_id(user.entity_ref())
+                .build(),
         );
         PagerStream::new(Box::pin(pager))
     }
 
-    /// Removes the specified abuse user report.
-    ///
-    /// This operation may require moderator privileges.
-    #[cfg(any(docsrs, not(feature = "12-49-0")))]
-    #[cfg_attr(docsrs, doc(cfg(not(feature = "12-49-0"))))]
-    fn remove_abuse_user_report(
+    /// Lists the recent gallery posts in the instance.
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn recent_gallery_posts(&self) -> PagerStream<BoxPager<'_, Self, GalleryPost>> {
+        let pager = BackwardPager::new(self, endpoint::gallery::posts::Request::default());
+        PagerStream::new(Box::pin(pager))
+    }
+
+    /// Lists the featured gallery posts.
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn featured_gallery_posts(
         &self,
-        report: impl EntityRef<AbuseUserReport>,
-    ) -> BoxFuture<Result<(), Error<Self::Error>>> {
-        let report_id = report.entity_ref();
+    ) -> BoxFuture<'_, Result<Vec<GalleryPost>, Error<Self::Error>>> {
         Box::pin(async move {
-            self.request(endpoint::admin::remove_abuse_user_report::Request { report_id })
+            let posts = self
+                .request(endpoint::gallery::featured::Request::default())
                 .await
                 .map_err(Error::Client)?
                 .into_result()?;
-            Ok(())
+            Ok(posts)
         })
     }
 
-    /// Marks the specified abuse user report as resolved.
-    ///
-    /// This operation may require moderator privileges.
-    #[cfg(feature = "12-49-0")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "12-49-0")))]
-    fn resolve_abuse_user_report(
-        &self,
-        report: impl EntityRef<AbuseUserReport>,
-    ) -> BoxFuture<Result<(), Error<Self::Error>>> {
-        let report_id = report.entity_ref();
+    /// Lists the popular gallery posts.
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn popular_gallery_posts(&self) -> BoxFuture<'_, Result<Vec<GalleryPost>, Error<Self::Error>>> {
         Box::pin(async move {
-            self.request(endpoint::admin::resolve_abuse_user_report::Request { report_id })
+            let posts = self
+                .request(endpoint::gallery::popular::Request::default())
                 .await
                 .map_err(Error::Client)?
                 .into_result()?;
-            Ok(())
+            Ok(posts)
         })
     }
+    // }}}
 
-    /// Lists the server logs in the instance.
-    ///
-    /// This method actually returns a builder, namely [`ServerLogListBuilder`].
-    /// You can specify how you want to list users by chaining methods.
-    /// The [`list`][builder_list] method of the builder fetches the actual logs.
+    // {{{ Hashtag
+    /// Returns a builder for listing the hashtags.
     ///
-    /// This operation may require moderator privileges.
+    /// The returned builder provides methods to filter and sort the hashtags,
+    /// and you can chain them to list hashtags incrementally.
+    /// Finally, calling [`list`][builder_list] method will actually list the hashtags.
+    /// See [`HashtagListBuilder`] for the provided methods.
     ///
-    /// [builder_list]: ServerLogListBuilder::list
+    /// [builder_list]: HashtagListBuilder::list
     ///
     /// # Examples
     ///
@@ -3374,90 +3648,1037 @@ pub trait ClientExt: Client + Sync {
     /// # #[tokio::main]
     /// # async fn main() -> anyhow::Result<()> {
     /// # let client = misskey_test::test_client().await?;
-    /// # use misskey_api as misskey;
-    /// // Get a first 10 entries of 'info' logs with 'chart' domain
-    /// let logs = client
-    ///     .server_logs()
+    /// // Get 10 hashtags most used in the profiles of local users.
+    /// let hashtags = client
+    ///     .hashtags()
+    ///     .attached_to_local_user_only()
+    ///     .sort_by_attached_users()
     ///     .take(10)
-    ///     .info()
-    ///     .with_domain("chart")
     ///     .list()
     ///     .await?;
     /// # Ok(())
     /// # }
     /// ```
-    fn server_logs(&self) -> ServerLogListBuilder<&Self> {
-        ServerLogListBuilder::new(self)
-    }
-
-    /// Lists the moderation logs in the instance.
-    ///
-    /// This operation may require moderator privileges.
-    fn moderation_logs(&self) -> PagerStream<BoxPager<Self, ModerationLog>> {
-        let pager = BackwardPager::new(
-            self,
-            endpoint::admin::show_moderation_logs::Request::default(),
-        );
-        PagerStream::new(Box::pin(pager))
+    fn hashtags(&self) -> HashtagListBuilder<&Self> {
+        HashtagListBuilder::new(self)
     }
 
-    /// Silences the specified user.
-    ///
-    /// This operation may require moderator privileges.
-    fn silence(&self, user: impl EntityRef<User>) -> BoxFuture<Result<(), Error<Self::Error>>> {
-        let user_id = user.entity_ref();
+    /// Gets the information about the specified hashtag.
+    fn hashtag(&self, tag: impl Into<Tag>) -> BoxFuture<'_, Result<Hashtag, Error<Self::Error>>> {
+        let tag = tag.into();
         Box::pin(async move {
-            self.request(endpoint::admin::silence_user::Request { user_id })
+            let hashtag = self
+                .request(endpoint::hashtags::show::Request { tag })
                 .await
                 .map_err(Error::Client)?
                 .into_result()?;
-            Ok(())
+            Ok(hashtag)
         })
     }
 
-    /// Suspends the specified user.
-    ///
-    /// This operation may require moderator privileges.
-    fn suspend(&self, user: impl EntityRef<User>) -> BoxFuture<Result<(), Error<Self::Error>>> {
-        let user_id = user.entity_ref();
-        Box::pin(async move {
-            self.request(endpoint::admin::suspend_user::Request { user_id })
-                .await
-                .map_err(Error::Client)?
-                .into_result()?;
-            Ok(())
-        })
+    /// Searches for the hashtags that start with the specified query.
+    fn search_hashtags(&self, query: impl Into<String>) -> PagerStream<BoxPager<'_, Self, String>> {
+        let pager = OffsetPager::new(
+            self,
+            endpoint::hashtags::search::Request::builder()
+                .query(query)
+                .build(),
+        );
+        PagerStream::new(Box::pin(pager))
     }
 
-    /// Unsilences the specified user.
+    /// Lists the users who have the specified hashtag in their profiles.
     ///
-    /// This operation may require moderator privileges.
-    fn unsilence(&self, user: impl EntityRef<User>) -> BoxFuture<Result<(), Error<Self::Error>>> {
-        let user_id = user.entity_ref();
+    /// The users are sorted in descending order by number of followers.
+    fn hashtag_users(
+        &self,
+        tag: impl Into<Tag>,
+    ) -> BoxFuture<'_, Result<Vec<User>, Error<Self::Error>>> {
+        let tag = tag.into();
         Box::pin(async move {
-            self.request(endpoint::admin::unsilence_user::Request { user_id })
+            let users = self
+                .request(
+                    endpoint::hashtags::users::Request::builder()
+                        .tag(tag)
+                        .sort(SortOrder::Descending(UserSortKey::Follower))
+                        .build(),
+                )
                 .await
                 .map_err(Error::Client)?
                 .into_result()?;
-            Ok(())
+            Ok(users)
         })
     }
 
-    /// Unsuspends the specified user.
-    ///
-    /// This operation may require moderator privileges.
-    fn unsuspend(&self, user: impl EntityRef<User>) -> BoxFuture<Result<(), Error<Self::Error>>> {
-        let user_id = user.entity_ref();
+    /// Lists the currently trending hashtags, most trending first.
+    fn trending_hashtags(
+        &self,
+    ) -> BoxFuture<'_, Result<Vec<endpoint::hashtags::trend::TrendingHashtag>, Error<Self::Error>>>
+    {
         Box::pin(async move {
-            self.request(endpoint::admin::unsuspend_user::Request { user_id })
+            let hashtags = self
+                .request(endpoint::hashtags::trend::Request::default())
                 .await
                 .map_err(Error::Client)?
                 .into_result()?;
-            Ok(())
+            Ok(hashtags)
         })
     }
 
-    /// Updates the instance information.
+    /// Watches the trending hashtags by polling them with the specified interval.
+    ///
+    /// The returned stream yields [`TrendEvent`]s whenever hashtags enter or leave the trend,
+    /// or their ranks change. The first poll is performed immediately and yields
+    /// [`TrendEvent::Entered`] for each of the hashtags in the trend at that point.
+    /// The stream keeps polling after it yields an error.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use misskey_util::ClientExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let client = misskey_test::test_client().await?;
+    /// use std::time::Duration;
+    ///
+    /// use futures::stream::TryStreamExt;
+    /// use misskey_util::TrendEvent;
+    ///
+    /// let mut trend = client.watch_hashtag_trend(Duration::from_secs(60));
+    /// while let Some(event) = trend.try_next().await? {
+    ///     if let TrendEvent::Entered { hashtag, rank } = event {
+    ///         println!("{} is now trending at #{}", hashtag.tag, rank);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn watch_hashtag_trend(
+        &self,
+        interval: Duration,
+    ) -> BoxStream<'_, Result<TrendEvent, Error<Self::Error>>>
+    where
+        Self::Error: Send,
+    {
+        crate::trend::watch(self, interval)
+    }
+    // }}}
+
+    // {{{ Webhook
+    /// Creates a webhook that sends the specified types of events to the given URL.
+    ///
+    /// Misskey sends the events with the `secret` in the `X-Misskey-Hook-Secret` header.
+    /// See [`webhook`][`crate::webhook`] module for receiving them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use misskey_util::ClientExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let client = misskey_test::test_client().await?;
+    /// # use misskey_api as misskey;
+    /// use misskey::model::webhook::WebhookEventType;
+    ///
+    /// let webhook = client
+    ///     .create_webhook(
+    ///         "mentions",
+    ///         "https://example.com/webhook".parse()?,
+    ///         "secret",
+    ///         vec![WebhookEventType::Mention, WebhookEventType::Reply],
+    ///     )
+    ///     .await?;
+    /// assert_eq!(webhook.on.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "12-112-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-112-0")))]
+    fn create_webhook(
+        &self,
+        name: impl Into<String>,
+        url: Url,
+        secret: impl Into<String>,
+        events: impl IntoIterator<Item = WebhookEventType>,
+    ) -> BoxFuture<'_, Result<Webhook, Error<Self::Error>>> {
+        let name = name.into();
+        let secret = secret.into();
+        let events: Vec<_> = events.into_iter().collect();
+        Box::pin(async move {
+            self.build_webhook(url)
+                .name(name)
+                .secret(secret)
+                .events(events)
+                .create()
+                .await
+        })
+    }
+
+    /// Returns a builder for creating a webhook that sends events to the given URL.
+    ///
+    /// The returned builder provides methods to customize details of the webhook,
+    /// and you can chain them to create a webhook incrementally.
+    /// Finally, calling [`create`][builder_create] method will actually create a webhook.
+    /// See [`WebhookBuilder`] for the provided methods.
+    ///
+    /// [builder_create]: WebhookBuilder::create
+    #[cfg(feature = "12-112-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-112-0")))]
+    fn build_webhook(&self, url: Url) -> WebhookBuilder<&Self> {
+        WebhookBuilder::new(self, url)
+    }
+
+    /// Deletes the specified webhook.
+    #[cfg(feature = "12-112-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-112-0")))]
+    fn delete_webhook(
+        &self,
+        webhook: impl EntityRef<Webhook>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let webhook_id = webhook.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::i::webhooks::delete::Request { webhook_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Gets the corresponding webhook from the ID.
+    #[cfg(feature = "12-112-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-112-0")))]
+    fn get_webhook(&self, id: Id<Webhook>) -> BoxFuture<'_, Result<Webhook, Error<Self::Error>>> {
+        Box::pin(async move {
+            let webhook = self
+                .request(endpoint::i::webhooks::show::Request { webhook_id: id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(webhook)
+        })
+    }
+
+    /// Updates the webhook.
+    ///
+    /// This method actually returns a builder, namely [`WebhookUpdateBuilder`].
+    /// You can chain the method calls to it corresponding to the fields you want to update.
+    /// Finally, calling [`update`][builder_update] method will actually perform the update.
+    /// See [`WebhookUpdateBuilder`] for the fields that can be updated.
+    ///
+    /// [builder_update]: WebhookUpdateBuilder::update
+    #[cfg(feature = "12-112-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-112-0")))]
+    fn update_webhook(&self, webhook: Webhook) -> WebhookUpdateBuilder<&Self> {
+        WebhookUpdateBuilder::new(self, webhook)
+    }
+
+    /// Lists the webhooks created by the user logged in with this client.
+    #[cfg(feature = "12-112-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-112-0")))]
+    fn webhooks(&self) -> BoxFuture<'_, Result<Vec<Webhook>, Error<Self::Error>>> {
+        Box::pin(async move {
+            let webhooks = self
+                .request(endpoint::i::webhooks::list::Request::default())
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(webhooks)
+        })
+    }
+    // }}}
+
+    // {{{ Admin
+    /// Sets moderator privileges for the specified user.
+    ///
+    /// This operation may require this client to be logged in with an admin account.
+    #[cfg(any(docsrs, not(feature = "13-10-0")))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "13-10-0"))))]
+    fn add_moderator(
+        &self,
+        user: impl EntityRef<User>,
+    ) -> BoxFuture<Result<(), Error<Self::Error>>> {
+        let user_id = user.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::admin::moderators::add::Request { user_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Removes moderator privileges for the specified user.
+    ///
+    /// This operation may require this client to be logged in with an admin account.
+    #[cfg(any(docsrs, not(feature = "13-10-0")))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "13-10-0"))))]
+    fn remove_moderator(
+        &self,
+        user: impl EntityRef<User>,
+    ) -> BoxFuture<Result<(), Error<Self::Error>>> {
+        let user_id = user.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::admin::moderators::remove::Request { user_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Promotes the specified note until the time.
+    ///
+    /// This operation may require moderator privileges.
+    #[cfg(feature = "12-13-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-13-0")))]
+    fn promote_note(
+        &self,
+        note: impl EntityRef<Note>,
+        expires_at: DateTime<Utc>,
+    ) -> BoxFuture<Result<(), Error<Self::Error>>> {
+        let note_id = note.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::admin::promo::create::Request {
+                note_id,
+                expires_at,
+            })
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Lists the abuse user reports.
+    ///
+    /// This operation may require moderator privileges.
+    fn abuse_user_reports(&self) -> PagerStream<BoxPager<Self, AbuseUserReport>> {
+        let pager = BackwardPager::new(
+            self,
+            endpoint::admin::abuse_user_reports::Request::default(),
+        );
+        PagerStream::new(Box::pin(pager))
+    }
+
+    /// Removes the specified abuse user report.
+    ///
+    /// This operation may require moderator privileges.
+    #[cfg(any(docsrs, not(feature = "12-49-0")))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "12-49-0"))))]
+    fn remove_abuse_user_report(
+        &self,
+        report: impl EntityRef<AbuseUserReport>,
+    ) -> BoxFuture<Result<(), Error<Self::Error>>> {
+        let report_id = report.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::admin::remove_abuse_user_report::Request { report_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Marks the specified abuse user report as resolved.
+    ///
+    /// This operation may require moderator privileges.
+    #[cfg(feature = "12-49-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-49-0")))]
+    fn resolve_abuse_user_report(
+        &self,
+        report: impl EntityRef<AbuseUserReport>,
+    ) -> BoxFuture<Result<(), Error<Self::Error>>> {
+        let report_id = report.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::admin::resolve_abuse_user_report::Request { report_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Lists the server logs in the instance.
+    ///
+    /// This method actually returns a builder, namely [`ServerLogListBuilder`].
+    /// You can specify how you want to list users by chaining methods.
+    /// The [`list`][builder_list] method of the builder fetches the actual logs.
+    ///
+    /// This operation may require moderator privileges.
+    ///
+    /// [builder_list]: ServerLogListBuilder::list
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use misskey_util::ClientExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let client = misskey_test::test_client().await?;
+    /// # use misskey_api as misskey;
+    /// // Get a first 10 entries of 'info' logs with 'chart' domain
+    /// let logs = client
+    ///     .server_logs()
+    ///     .take(10)
+    ///     .info()
+    ///     .with_domain("chart")
+    ///     .list()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn server_logs(&self) -> ServerLogListBuilder<&Self> {
+        ServerLogListBuilder::new(self)
+    }
+
+    /// Lists the moderation logs in the instance.
+    ///
+    /// This operation may require moderator privileges.
+    fn moderation_logs(&self) -> PagerStream<BoxPager<Self, ModerationLog>> {
+        let pager = BackwardPager::new(
+            self,
+            endpoint::admin::show_moderation_logs::Request::default(),
+        );
+        PagerStream::new(Box::pin(pager))
+    }
+
+    /// Silences the specified user.
+    ///
+    /// This operation may require moderator privileges.
+    #[cfg(any(docsrs, not(feature = "13-10-0")))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "13-10-0"))))]
+    fn silence(&self, user: impl EntityRef<User>) -> BoxFuture<Result<(), Error<Self::Error>>> {
+        let user_id = user.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::admin::silence_user::Request { user_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Suspends the specified user.
+    ///
+    /// This operation may require moderator privileges.
+    fn suspend(&self, user: impl EntityRef<User>) -> BoxFuture<Result<(), Error<Self::Error>>> {
+        let user_id = user.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::admin::suspend_user::Request { user_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Unsilences the specified user.
+    ///
+    /// This operation may require moderator privileges.
+    #[cfg(any(docsrs, not(feature = "13-10-0")))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "13-10-0"))))]
+    fn unsilence(&self, user: impl EntityRef<User>) -> BoxFuture<Result<(), Error<Self::Error>>> {
+        let user_id = user.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::admin::unsilence_user::Request { user_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Unsuspends the specified user.
+    ///
+    /// This operation may require moderator privileges.
+    fn unsuspend(&self, user: impl EntityRef<User>) -> BoxFuture<Result<(), Error<Self::Error>>> {
+        let user_id = user.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::admin::unsuspend_user::Request { user_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Creates a role with the given name that is assigned manually.
+    ///
+    /// This operation may require this client to be logged in with an admin account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use misskey_util::ClientExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let client = misskey_test::test_admin_client().await?;
+    /// # let user = client.me().await?;
+    /// use chrono::{Duration, Utc};
+    ///
+    /// let role = client.create_role("trial").await?;
+    /// client
+    ///     .assign_role_until(&role, &user, Utc::now() + Duration::days(7))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn create_role(
+        &self,
+        name: impl Into<String>,
+    ) -> BoxFuture<'_, Result<Role, Error<Self::Error>>> {
+        let name = name.into();
+        Box::pin(async move { self.build_role().name(name).create().await })
+    }
+
+    /// Returns a builder for creating a role.
+    ///
+    /// The returned builder provides methods to customize details of the role,
+    /// and you can chain them to create a role incrementally.
+    /// Finally, calling [`create`][builder_create] method will actually create a role.
+    /// See [`RoleBuilder`] for the provided methods.
+    ///
+    /// This operation may require this client to be logged in with an admin account.
+    ///
+    /// [builder_create]: RoleBuilder::create
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn build_role(&self) -> RoleBuilder<&Self> {
+        RoleBuilder::new(self)
+    }
+
+    /// Updates the role.
+    ///
+    /// This method actually returns a builder, namely [`RoleUpdateBuilder`].
+    /// You can chain the method calls to it corresponding to the fields you want to update.
+    /// Finally, calling [`update`][builder_update] method will actually perform the update.
+    /// See [`RoleUpdateBuilder`] for the fields that can be updated.
+    ///
+    /// This operation may require this client to be logged in with an admin account.
+    ///
+    /// [builder_update]: RoleUpdateBuilder::update
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn update_role(&self, role: Role) -> RoleUpdateBuilder<&Self> {
+        RoleUpdateBuilder::new(self, role)
+    }
+
+    /// Deletes the specified role.
+    ///
+    /// This operation may require this client to be logged in with an admin account.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn delete_role(
+        &self,
+        role: impl EntityRef<Role>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let role_id = role.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::admin::roles::delete::Request { role_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Gets the corresponding role from the ID.
+    ///
+    /// This operation may require moderator privileges.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn get_role(&self, id: Id<Role>) -> BoxFuture<'_, Result<Role, Error<Self::Error>>> {
+        Box::pin(async move {
+            let role = self
+                .request(endpoint::admin::roles::show::Request { role_id: id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(role)
+        })
+    }
+
+    /// Lists all the roles in the instance.
+    ///
+    /// This operation may require moderator privileges.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn roles(&self) -> BoxFuture<'_, Result<Vec<Role>, Error<Self::Error>>> {
+        Box::pin(async move {
+            let roles = self
+                .request(endpoint::admin::roles::list::Request::default())
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(roles)
+        })
+    }
+
+    /// Assigns the specified user to the role.
+    ///
+    /// This operation may require moderator privileges.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn assign_role(
+        &self,
+        role: impl EntityRef<Role>,
+        user: impl EntityRef<User>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let request = endpoint::admin::roles::assign::Request {
+            role_id: role.entity_ref(),
+            user_id: user.entity_ref(),
+            expires_at: None,
+        };
+        Box::pin(async move {
+            self.request(request)
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Assigns the specified user to the role until the given time.
+    ///
+    /// This operation may require moderator privileges.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn assign_role_until(
+        &self,
+        role: impl EntityRef<Role>,
+        user: impl EntityRef<User>,
+        expires_at: DateTime<Utc>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let request = endpoint::admin::roles::assign::Request {
+            role_id: role.entity_ref(),
+            user_id: user.entity_ref(),
+            expires_at: Some(expires_at),
+        };
+        Box::pin(async move {
+            self.request(request)
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Unassigns the specified user from the role.
+    ///
+    /// This operation may require moderator privileges.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn unassign_role(
+        &self,
+        role: impl EntityRef<Role>,
+        user: impl EntityRef<User>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let request = endpoint::admin::roles::unassign::Request {
+            role_id: role.entity_ref(),
+            user_id: user.entity_ref(),
+        };
+        Box::pin(async move {
+            self.request(request)
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Lists the assignments of the users to the specified role.
+    ///
+    /// This operation may require moderator privileges.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn role_assignments(
+        &self,
+        role: impl EntityRef<Role>,
+    ) -> PagerStream<BoxPager<'_, Self, RoleAssignment>> {
+        let pager = BackwardPager::new(
+            self,
+            endpoint::admin::roles::users::Request::builder()
+                .role_id(role.entity_ref())
+                .build(),
+        );
+        PagerStream::new(Box::pin(pager))
+    }
+
+    /// Updates the policies applied to the users without roles overriding them.
+    ///
+    /// This operation may require this client to be logged in with an admin account.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn update_default_policies(
+        &self,
+        policies: Policies,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        Box::pin(async move {
+            self.request(endpoint::admin::roles::update_default_policies::Request { policies })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Lists the public roles in the instance.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn public_roles(&self) -> BoxFuture<'_, Result<Vec<Role>, Error<Self::Error>>> {
+        Box::pin(async move {
+            let roles = self
+                .request(endpoint::roles::list::Request::default())
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(roles)
+        })
+    }
+
+    /// Lists the users assigned to the specified public role.
+    #[cfg(feature = "13-10-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "13-10-0")))]
+    fn public_role_users(
+        &self,
+        role: impl EntityRef<Role>,
+    ) -> PagerStream<BoxPager<'_, Self, User>> {
+        let pager = BackwardPager::new(
+            self,
+            endpoint::roles::users::Request::builder()
+                .role_id(role.entity_ref())
+                .build(),
+        )
+        .map_ok(|v| v.into_iter().map(|a| a.user).collect());
+        PagerStream::new(Box::pin(pager))
+    }
+
+    /// Suspends the delivery to the instance of the specified host.
+    ///
+    /// This operation may require moderator privileges.
+    fn suspend_instance(
+        &self,
+        host: impl Into<String>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let host = host.into();
+        Box::pin(async move {
+            self.request(endpoint::admin::federation::update_instance::Request {
+                host,
+                is_suspended: true,
+            })
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Resumes the delivery to the instance of the specified host.
+    ///
+    /// This operation may require moderator privileges.
+    fn unsuspend_instance(
+        &self,
+        host: impl Into<String>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let host = host.into();
+        Box::pin(async move {
+            self.request(endpoint::admin::federation::update_instance::Request {
+                host,
+                is_suspended: false,
+            })
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Deletes all the files of the users on the instance of the specified host.
+    ///
+    /// This operation may require moderator privileges.
+    fn delete_instance_files(
+        &self,
+        host: impl Into<String>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let host = host.into();
+        Box::pin(async move {
+            self.request(endpoint::admin::federation::delete_all_files::Request { host })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Removes all the following relationships between the local users and the users on the instance of the specified host.
+    ///
+    /// This operation may require moderator privileges.
+    fn remove_instance_following(
+        &self,
+        host: impl Into<String>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let host = host.into();
+        Box::pin(async move {
+            self.request(endpoint::admin::federation::remove_all_following::Request { host })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Fetches the metadata of the instance of the specified host again.
+    ///
+    /// This operation may require moderator privileges.
+    fn refresh_instance_metadata(
+        &self,
+        host: impl Into<String>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let host = host.into();
+        Box::pin(async move {
+            self.request(
+                endpoint::admin::federation::refresh_remote_instance_metadata::Request { host },
+            )
+            .await
+            .map_err(Error::Client)?
+            .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Adds a relay with the specified inbox URL.
+    ///
+    /// This operation may require this client to be logged in with an admin account.
+    fn add_relay(&self, inbox: Url) -> BoxFuture<'_, Result<Relay, Error<Self::Error>>> {
+        Box::pin(async move {
+            let relay = self
+                .request(endpoint::admin::relays::add::Request { inbox })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(relay)
+        })
+    }
+
+    /// Removes the relay with the specified inbox URL.
+    ///
+    /// This operation may require this client to be logged in with an admin account.
+    fn remove_relay(&self, inbox: Url) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        Box::pin(async move {
+            self.request(endpoint::admin::relays::remove::Request { inbox })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Lists the relays.
+    ///
+    /// This operation may require this client to be logged in with an admin account.
+    fn relays(&self) -> BoxFuture<'_, Result<Vec<Relay>, Error<Self::Error>>> {
+        Box::pin(async move {
+            let relays = self
+                .request(endpoint::admin::relays::list::Request::default())
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(relays)
+        })
+    }
+
+    /// Gets the number of jobs in each job queue.
+    ///
+    /// This operation may require moderator privileges.
+    fn queue_stats(
+        &self,
+    ) -> BoxFuture<'_, Result<endpoint::admin::queue::stats::Response, Error<Self::Error>>> {
+        Box::pin(async move {
+            let stats = self
+                .request(endpoint::admin::queue::stats::Request::default())
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(stats)
+        })
+    }
+
+    /// Lists the jobs in the specified state in the job queue of the specified domain.
+    ///
+    /// Up to 100 jobs are listed.
+    /// This operation may require moderator privileges.
+    fn queue_jobs(
+        &self,
+        domain: QueueDomain,
+        state: QueueJobState,
+    ) -> BoxFuture<'_, Result<Vec<QueueJob>, Error<Self::Error>>> {
+        Box::pin(async move {
+            let jobs = self
+                .request(
+                    endpoint::admin::queue::jobs::Request::builder()
+                        .domain(domain)
+                        .state(state)
+                        .limit(100)
+                        .build(),
+                )
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(jobs)
+        })
+    }
+
+    /// Removes all the jobs from all the job queues.
+    ///
+    /// This operation may require moderator privileges.
+    fn clear_queues(&self) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        Box::pin(async move {
+            self.request(endpoint::admin::queue::clear::Request::default())
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Lists the hosts with the number of delayed jobs in the deliver queue.
+    ///
+    /// This operation may require moderator privileges.
+    fn delayed_deliveries(
+        &self,
+    ) -> BoxFuture<'_, Result<Vec<DelayedJobCount>, Error<Self::Error>>> {
+        Box::pin(async move {
+            let delayed = self
+                .request(endpoint::admin::queue::deliver_delayed::Request::default())
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(delayed)
+        })
+    }
+
+    /// Lists the hosts with the number of delayed jobs in the inbox queue.
+    ///
+    /// This operation may require moderator privileges.
+    fn delayed_inbox_jobs(
+        &self,
+    ) -> BoxFuture<'_, Result<Vec<DelayedJobCount>, Error<Self::Error>>> {
+        Box::pin(async move {
+            let delayed = self
+                .request(endpoint::admin::queue::inbox_delayed::Request::default())
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(delayed)
+        })
+    }
+
+    /// Returns a builder for listing the files of all users in the instance.
+    ///
+    /// The returned builder provides methods to filter the files,
+    /// and you can chain them to list files incrementally.
+    /// Finally, calling [`list`][builder_list] method will return a [`Stream`][stream] of files.
+    /// See [`ServerFileListBuilder`] for the provided methods.
+    ///
+    /// This operation may require moderator privileges.
+    ///
+    /// [builder_list]: ServerFileListBuilder::list
+    /// [stream]: futures::stream::Stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use misskey_util::ClientExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let client = misskey_test::test_admin_client().await?;
+    /// # use misskey_api as misskey;
+    /// use futures::stream::TryStreamExt;
+    /// use mime::IMAGE_STAR;
+    /// use misskey::model::drive::DriveFile;
+    ///
+    /// // Get a list of image files cached from remote users
+    /// let images: Vec<DriveFile> = client
+    ///     .server_files()
+    ///     .remote()
+    ///     .type_(IMAGE_STAR)
+    ///     .list()
+    ///     .try_collect()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn server_files(&self) -> ServerFileListBuilder<&Self> {
+        ServerFileListBuilder::new(self)
+    }
+
+    /// Gets the detailed information about the specified file, including those only visible to moderators.
+    ///
+    /// This operation may require moderator privileges.
+    fn server_file(
+        &self,
+        file: impl EntityRef<DriveFile>,
+    ) -> BoxFuture<'_, Result<endpoint::admin::drive::show_file::Response, Error<Self::Error>>>
+    {
+        let file_id = file.entity_ref();
+        Box::pin(async move {
+            let file = self
+                .request(endpoint::admin::drive::show_file::Request::WithFileId { file_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(file)
+        })
+    }
+
+    /// Deletes the files cached from remote instances.
+    ///
+    /// The files are deleted in the background on the server.
+    /// This operation may require moderator privileges.
+    fn clean_remote_files(&self) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        Box::pin(async move {
+            self.request(endpoint::admin::drive::clean_remote_files::Request::default())
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Deletes the files that are not owned by any user.
+    ///
+    /// This operation may require moderator privileges.
+    fn cleanup_drive(&self) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        Box::pin(async move {
+            self.request(endpoint::admin::drive::cleanup::Request::default())
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Deletes all the files of the specified user.
+    ///
+    /// This operation may require moderator privileges.
+    fn delete_all_files_of_user(
+        &self,
+        user: impl EntityRef<User>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let user_id = user.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::admin::delete_all_files_of_a_user::Request { user_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+
+    /// Updates the instance information.
     ///
     /// This method actually returns a builder, namely [`MetaUpdateBuilder`].
     /// You can chain the method calls to it corresponding to the fields you want to update.
@@ -3668,6 +4889,216 @@ pub trait ClientExt: Client + Sync {
     }
     // }}}
 
+    // {{{ Federation
+    /// Returns a builder for listing the instances known to this server.
+    ///
+    /// The returned builder provides methods to filter and sort the instances,
+    /// and you can chain them to list instances incrementally.
+    /// Finally, calling [`list`][builder_list] method will return a [`Stream`][stream] of instances.
+    /// See [`InstanceListBuilder`] for the provided methods.
+    ///
+    /// [builder_list]: InstanceListBuilder::list
+    /// [stream]: futures::stream::Stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use misskey_util::ClientExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let client = misskey_test::test_client().await?;
+    /// # use misskey_api as misskey;
+    /// use futures::stream::TryStreamExt;
+    /// use misskey::model::instance::Instance;
+    ///
+    /// // Get a list of federating instances that are not responding.
+    /// let instances: Vec<Instance> = client
+    ///     .federated_instances()
+    ///     .federating(true)
+    ///     .not_responding(true)
+    ///     .sort_by_last_communication()
+    ///     .list()
+    ///     .try_collect()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn federated_instances(&self) -> InstanceListBuilder<&Self> {
+        InstanceListBuilder::new(self)
+    }
+
+    /// Gets the information about the instance of the specified host.
+    ///
+    /// Returns `None` if the instance is not known to this server.
+    fn instance(
+        &self,
+        host: impl Into<String>,
+    ) -> BoxFuture<'_, Result<Option<Instance>, Error<Self::Error>>> {
+        let host = host.into();
+        Box::pin(async move {
+            let instance = self
+                .request(endpoint::federation::show_instance::Request { host })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(instance)
+        })
+    }
+
+    /// Lists the users on the instance of the specified host.
+    fn federated_users(&self, host: impl Into<String>) -> PagerStream<BoxPager<'_, Self, User>> {
+        let pager = BackwardPager::new(
+            self,
+            endpoint::federation::users::Request::builder()
+                .host(host)
+                .build(),
+        );
+        PagerStream::new(Box::pin(pager))
+    }
+
+    /// Lists the following relationships in which the followee is on the specified host.
+    fn federated_followers(
+        &self,
+        host: impl Into<String>,
+    ) -> PagerStream<BoxPager<'_, Self, Following>> {
+        let pager = BackwardPager::new(
+            self,
+            endpoint::federation::followers::Request::builder()
+                .host(host)
+                .build(),
+        )
+        .map_ok(|v| v.into_iter().map(|f| f.following).collect());
+        PagerStream::new(Box::pin(pager))
+    }
+
+    /// Lists the following relationships in which the follower is on the specified host.
+    fn federated_following(
+        &self,
+        host: impl Into<String>,
+    ) -> PagerStream<BoxPager<'_, Self, Following>> {
+        let pager = BackwardPager::new(
+            self,
+            endpoint::federation::following::Request::builder()
+                .host(host)
+                .build(),
+        )
+        .map_ok(|v| v.into_iter().map(|f| f.following).collect());
+        PagerStream::new(Box::pin(pager))
+    }
+
+    /// Resolves the specified URI into the local [`User`] or [`Note`] object.
+    ///
+    /// The URI can be the URL of a note or a user on any instance, such as
+    /// `https://other.example/notes/xyz` or `https://other.example/@alice`, which is fetched
+    /// from the remote instance if needed. It can also be a handle such as
+    /// `acct:alice@other.example` or `@alice@other.example`, which is looked up with
+    /// [`users/show`][`endpoint::users::show`]. Handles without the host or with the host of this
+    /// server refer to the local users.
+    ///
+    /// When a handle or a profile URL cannot be resolved, for example because the remote instance
+    /// is unreachable, the user is searched among the users already known to this server with
+    /// [`users/search_by_username_and_host`][`endpoint::users::search_by_username_and_host`].
+    ///
+    /// Returns `None` if no such object is found.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use misskey_util::ClientExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let client = misskey_test::test_client().await?;
+    /// # use misskey_api as misskey;
+    /// use misskey::endpoint::ap::show::Response;
+    ///
+    /// match client.resolve_uri("https://other.example/notes/xyz").await? {
+    ///     Some(Response::Note(note)) => println!("note: {:?}", note.text),
+    ///     Some(Response::User(user)) => println!("user: {}", user.username),
+    ///     None => println!("not found"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn resolve_uri(
+        &self,
+        uri: impl Into<String>,
+    ) -> BoxFuture<'_, Result<Option<endpoint::ap::show::Response>, Error<Self::Error>>> {
+        let uri = uri.into();
+        Box::pin(async move {
+            if let Some((username, host)) = parse_acct(&uri) {
+                // the server treats its own host as local, and fetches unknown remote users
+                let result = self
+                    .request(endpoint::users::show::Request::WithUsername {
+                        username: username.to_owned(),
+                        host: host.map(ToOwned::to_owned),
+                    })
+                    .await
+                    .map_err(Error::Client)?
+                    .into_result();
+                let user = match result {
+                    Ok(user) => Some(user),
+                    Err(e)
+                        if e.code == "NO_SUCH_USER"
+                            || e.code == "FAILED_TO_RESOLVE_REMOTE_USER" =>
+                    {
+                        search_user(self, username, host).await?
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                return Ok(user.map(|user| endpoint::ap::show::Response::User(Box::new(user))));
+            }
+
+            let result = self
+                .request(endpoint::ap::show::Request { uri: uri.clone() })
+                .await
+                .map_err(Error::Client)?
+                .into_result();
+            match result {
+                Ok(object) => Ok(Some(object)),
+                Err(e) if e.code == "NO_SUCH_OBJECT" => match parse_profile_url(&uri) {
+                    Some((username, host)) => {
+                        let user = search_user(self, &username, Some(&host)).await?;
+                        Ok(user.map(|user| endpoint::ap::show::Response::User(Box::new(user))))
+                    }
+                    None => Ok(None),
+                },
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    /// Fetches the raw ActivityPub object at the specified URI from the remote instance.
+    fn fetch_ap_object(
+        &self,
+        uri: impl Into<String>,
+    ) -> BoxFuture<'_, Result<serde_json::Value, Error<Self::Error>>> {
+        let uri = uri.into();
+        Box::pin(async move {
+            let object = self
+                .request(endpoint::ap::get::Request { uri })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(object)
+        })
+    }
+
+    /// Updates the information of the specified remote user by fetching it from its instance.
+    fn update_remote_user(
+        &self,
+        user: impl EntityRef<User>,
+    ) -> BoxFuture<'_, Result<(), Error<Self::Error>>> {
+        let user_id = user.entity_ref();
+        Box::pin(async move {
+            self.request(endpoint::federation::update_remote_user::Request { user_id })
+                .await
+                .map_err(Error::Client)?
+                .into_result()?;
+            Ok(())
+        })
+    }
+    // }}}
+
     // {{{ Miscellaneous
     /// Gets information about the instance.
     fn meta(&self) -> BoxFuture<Result<Meta, Error<Self::Error>>> {
@@ -3749,6 +5180,312 @@ pub trait UploadFileClientExt: UploadFileClient + Sync {
     fn build_file(&self, path: impl AsRef<Path>) -> DriveFileBuilder<&Self> {
         DriveFileBuilder::with_path(self, path)
     }
+
+    /// Returns a builder for creating a gallery post.
+    ///
+    /// The returned builder provides methods to customize details of the post,
+    /// and you can chain them to create a post incrementally.
+    /// Local files given to the builder are uploaded to the drive and attached to the post
+    /// when calling [`create`][builder_create] method.
+    /// See [`GalleryPostBuilder`] for the provided methods.
+    ///
+    /// [builder_create]: GalleryPostBuilder::create
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use misskey_util::{ClientExt, UploadFileClientExt};
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let client = misskey_test::test_client().await?;
+    /// let post = client
+    ///     .build_gallery_post()
+    ///     .title("My photos")
+    ///     .description("Taken last weekend")
+    ///     .upload_file("photo1.jpg")
+    ///     .upload_file("photo2.jpg")
+    ///     .create()
+    ///     .await?;
+    ///
+    /// assert_eq!(post.files.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "12-79-0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "12-79-0")))]
+    fn build_gallery_post(&self) -> GalleryPostBuilder<&Self> {
+        GalleryPostBuilder::new(self)
+    }
 }
 
 impl<C: UploadFileClient + Sync> UploadFileClientExt for C {}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_acct, parse_profile_url};
+
+    #[test]
+    fn parse_acct_prefixes() {
+        assert_eq!(
+            parse_acct("acct:alice@example.com"),
+            Some(("alice", Some("example.com")))
+        );
+        assert_eq!(
+            parse_acct("@alice@example.com"),
+            Some(("alice", Some("example.com")))
+        );
+        assert_eq!(
+            parse_acct("alice@example.com"),
+            Some(("alice", Some("example.com")))
+        );
+        assert_eq!(parse_acct("acct:alice"), Some(("alice", None)));
+        assert_eq!(parse_acct("@alice"), Some(("alice", None)));
+    }
+
+    #[test]
+    fn parse_acct_bare_word() {
+        assert_eq!(parse_acct("alice"), None);
+    }
+
+    #[test]
+    fn parse_acct_empty() {
+        assert_eq!(parse_acct(""), None);
+        assert_eq!(parse_acct("@"), None);
+        assert_eq!(parse_acct("acct:"), None);
+        assert_eq!(parse_acct("@@example.com"), None);
+        assert_eq!(parse_acct("acct:@example.com"), None);
+        assert_eq!(parse_acct("@alice@"), None);
+        assert_eq!(parse_acct("acct:alice@"), None);
+    }
+
+    #[test]
+    fn parse_acct_url() {
+        assert_eq!(parse_acct("https://example.com/@alice"), None);
+        assert_eq!(parse_acct("https://example.com/notes/xyz"), None);
+        assert_eq!(parse_acct("@alice@example.com/notes"), None);
+        assert_eq!(parse_acct("acct:alice@example.com:3000"), None);
+    }
+
+    #[test]
+    fn parse_profile_url_forms() {
+        assert_eq!(
+            parse_profile_url("https://example.com/@alice"),
+            Some(("alice".to_string(), "example.com".to_string()))
+        );
+        assert_eq!(
+            parse_profile_url("https://example.com/@alice@other.example"),
+            Some(("alice".to_string(), "other.example".to_string()))
+        );
+        assert_eq!(parse_profile_url("https://example.com/notes/xyz"), None);
+        assert_eq!(parse_profile_url("https://example.com/@alice/notes"), None);
+        assert_eq!(parse_profile_url("https://example.com/@"), None);
+        assert_eq!(parse_profile_url("@alice@example.com"), None);
+    }
+
+    // the IDs in the responses are in the aid format
+    #[cfg(feature = "aid")]
+    mod federation {
+        use crate::test::StubClient;
+        use crate::ClientExt;
+
+        use futures::stream::TryStreamExt;
+        use serde_json::{json, Value};
+
+        fn following(id: &str, followee_id: &str, follower_id: &str) -> Value {
+            json!({
+                "id": id,
+                "createdAt": "2020-01-01T00:00:00.000Z",
+                "followeeId": followee_id,
+                "followerId": follower_id,
+                "followee": {
+                    "id": followee_id,
+                    "username": "user",
+                    "name": null,
+                    "avatarUrl": null,
+                    "avatarBlurhash": null,
+                    "avatarColor": null,
+                    "bannerColor": null,
+                    "emojis": [],
+                    "host": null,
+                },
+            })
+        }
+
+        #[tokio::test]
+        async fn federated_following_pages_by_following_id() {
+            let client = StubClient::default();
+            client.respond(json!([
+                following("8ejiidh50m", "8dhemt9ubf", "8dhe5zqidm"),
+                following("8ejiidh40m", "8dhe5zqidm", "8dhemt9ubf"),
+            ]));
+            client.respond(json!([following("8ejiidh30m", "8dhemt9ubf", "8dhe5zqidm")]));
+            client.respond(json!([]));
+
+            let followings: Vec<_> = client
+                .federated_following("example.com")
+                .try_collect()
+                .await
+                .unwrap();
+            let ids: Vec<_> = followings.iter().map(|f| f.id.to_string()).collect();
+            assert_eq!(ids, vec!["8ejiidh50m", "8ejiidh40m", "8ejiidh30m"]);
+
+            // the next pages are requested with the ID of the following, not of the followee
+            let until_ids: Vec<_> = client
+                .requests()
+                .into_iter()
+                .map(|(endpoint, params)| {
+                    assert_eq!(endpoint, "federation/following");
+                    params.get("untilId").cloned()
+                })
+                .collect();
+            assert_eq!(
+                until_ids,
+                vec![None, Some(json!("8ejiidh40m")), Some(json!("8ejiidh30m"))]
+            );
+        }
+
+        #[tokio::test]
+        async fn federated_followers_pages_by_following_id() {
+            let client = StubClient::default();
+            client.respond(json!([following("8ejiidh50m", "8dhemt9ubf", "8dhe5zqidm")]));
+            client.respond(json!([following("8ejiidh40m", "8dhe5zqidm", "8dhemt9ubf")]));
+            client.respond(json!([]));
+
+            let followers: Vec<_> = client
+                .federated_followers("example.com")
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(followers.len(), 2);
+
+            let requests = client.requests();
+            assert_eq!(requests.len(), 3);
+            assert_eq!(requests[0].0, "federation/followers");
+            assert_eq!(requests[1].1["untilId"], json!("8ejiidh50m"));
+            assert_eq!(requests[2].1["untilId"], json!("8ejiidh40m"));
+        }
+    }
+
+    // the IDs in the responses are in the aid format
+    #[cfg(feature = "aid")]
+    mod resolve_uri {
+        use crate::test::StubClient;
+        use crate::ClientExt;
+
+        use misskey_api::endpoint::ap::show::Response;
+        use serde_json::{json, Value};
+
+        fn user(username: &str, host: Option<&str>) -> Value {
+            json!({
+                "id": "8dhemt9ubf",
+                "username": username,
+                "name": null,
+                "avatarUrl": null,
+                "avatarBlurhash": null,
+                "avatarColor": null,
+                "bannerColor": null,
+                "emojis": [],
+                "host": host,
+            })
+        }
+
+        fn error(code: &str) -> Value {
+            json!({
+                "error": {
+                    "id": "00000000-0000-0000-0000-000000000000",
+                    "message": "error",
+                    "code": code,
+                    "kind": "client",
+                },
+            })
+        }
+
+        fn username(response: Option<Response>) -> Option<(String, Option<String>)> {
+            match response {
+                Some(Response::User(user)) => Some((user.username, user.host)),
+                _ => None,
+            }
+        }
+
+        #[tokio::test]
+        async fn handle() {
+            let client = StubClient::default();
+            client.respond(user("alice", Some("example.com")));
+
+            let response = client.resolve_uri("@alice@example.com").await.unwrap();
+            assert_eq!(
+                username(response),
+                Some(("alice".to_string(), Some("example.com".to_string())))
+            );
+            assert_eq!(
+                client.requests(),
+                vec![(
+                    "users/show",
+                    json!({ "username": "alice", "host": "example.com" })
+                )]
+            );
+        }
+
+        #[tokio::test]
+        async fn handle_falls_back_to_search() {
+            let client = StubClient::default();
+            client.respond(error("FAILED_TO_RESOLVE_REMOTE_USER"));
+            client.respond(json!([
+                user("alice2", Some("example.com")),
+                user("Alice", Some("example.com")),
+            ]));
+
+            let response = client.resolve_uri("acct:alice@example.com").await.unwrap();
+            assert_eq!(
+                username(response),
+                Some(("Alice".to_string(), Some("example.com".to_string())))
+            );
+            let requests = client.requests();
+            assert_eq!(requests[1].0, "users/search-by-username-and-host");
+            assert_eq!(requests[1].1["username"], json!("alice"));
+            assert_eq!(requests[1].1["host"], json!("example.com"));
+        }
+
+        #[tokio::test]
+        async fn handle_not_found() {
+            let client = StubClient::default();
+            client.respond(error("NO_SUCH_USER"));
+            client.respond(json!([user("alice2", None)]));
+
+            let response = client.resolve_uri("@alice").await.unwrap();
+            assert!(response.is_none());
+        }
+
+        #[tokio::test]
+        async fn profile_url_falls_back_to_search() {
+            let client = StubClient::default();
+            client.respond(error("NO_SUCH_OBJECT"));
+            client.respond(json!([user("alice", Some("example.com"))]));
+
+            let response = client
+                .resolve_uri("https://example.com/@alice")
+                .await
+                .unwrap();
+            assert_eq!(
+                username(response),
+                Some(("alice".to_string(), Some("example.com".to_string())))
+            );
+            let requests = client.requests();
+            assert_eq!(requests[0].0, "ap/show");
+            assert_eq!(requests[1].0, "users/search-by-username-and-host");
+        }
+
+        #[tokio::test]
+        async fn note_url_not_found() {
+            let client = StubClient::default();
+            client.respond(error("NO_SUCH_OBJECT"));
+
+            let response = client
+                .resolve_uri("https://example.com/notes/xyz")
+                .await
+                .unwrap();
+            assert!(response.is_none());
+            assert_eq!(client.requests().len(), 1);
+        }
+    }
+}
@@ -173,6 +173,21 @@ pub use streaming::StreamingClientExt;
 
 pub mod builder;
 pub mod pager;
+pub mod polling;
+pub mod reversi;
+
+#[cfg(feature = "12-112-0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "12-112-0")))]
+pub mod webhook;
 
 mod timeline;
 pub use timeline::{TimelineCursor, TimelineRange};
+
+mod trend;
+pub use trend::TrendEvent;
+
+mod word_mute;
+pub use word_mute::WordMuteFilter;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,731 @@
+//! Polling-based implementation of [`StreamingClient`].
+//!
+//! [`PollingClient`] wraps any [`Client`] and emulates the streaming API by repeatedly
+//! calling the REST endpoints, so that code written against [`StreamingClient`] (and
+//! [`StreamingClientExt`][crate::StreamingClientExt]) runs even where WebSocket connections are
+//! not available.
+//!
+//! The following channels are emulated:
+//!
+//! | Channel | Endpoint | Events |
+//! |---------|----------|--------|
+//! | `homeTimeline` | `notes/timeline` | `note` |
+//! | `localTimeline` | `notes/local-timeline` | `note` |
+//! | `hybridTimeline` | `notes/hybrid-timeline` | `note` |
+//! | `globalTimeline` | `notes/global-timeline` | `note` |
+//! | `userList` | `notes/user-list-timeline` | `note` |
+//! | `antenna` | `antennas/notes` | `note` |
+//! | `channel` | `channels/timeline` | `note` |
+//! | `main` | `i/notifications` | `notification` |
+//!
+//! Note subscriptions are emulated by polling `notes/show` and `notes/reactions`, which
+//! yields `reacted`, `unreacted` and `deleted` events. Other channels, messages sent to the
+//! channels, and the broadcast stream are not supported.
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{self, Debug, Display};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::{
+    future::{self, BoxFuture, FutureExt},
+    sink::Sink,
+    stream::{self, BoxStream, Stream, StreamExt},
+};
+use futures_timer::Delay;
+use misskey_core::model::{ApiError, ApiResult};
+use misskey_core::streaming::{
+    BroadcastEvent, BroadcastStream, ChannelStream, ConnectChannelRequest, StreamingClient,
+    SubNoteEvent, SubNoteStream,
+};
+use misskey_core::{Client, Request};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Map, Value};
+
+/// The maximum number of items fetched with a single request.
+const PAGE_SIZE: u64 = 100;
+
+macro_rules! raw_request {
+    ($name:ident, $endpoint:literal, $response:ty) => {
+        #[derive(Serialize)]
+        #[serde(transparent)]
+        struct $name(Map<String, Value>);
+
+        impl Request for $name {
+            type Response = $response;
+            const ENDPOINT: &'static str = $endpoint;
+        }
+
+        impl From<Map<String, Value>> for $name {
+            fn from(params: Map<String, Value>) -> Self {
+                $name(params)
+            }
+        }
+    };
+}
+
+raw_request!(HomeTimeline, "notes/timeline", Vec<Value>);
+raw_request!(LocalTimeline, "notes/local-timeline", Vec<Value>);
+raw_request!(HybridTimeline, "notes/hybrid-timeline", Vec<Value>);
+raw_request!(GlobalTimeline, "notes/global-timeline", Vec<Value>);
+raw_request!(UserListTimeline, "notes/user-list-timeline", Vec<Value>);
+raw_request!(AntennaNotes, "antennas/notes", Vec<Value>);
+raw_request!(ChannelTimeline, "channels/timeline", Vec<Value>);
+raw_request!(Notifications, "i/notifications", Vec<Value>);
+raw_request!(ShowNote, "notes/show", Value);
+raw_request!(NoteReactions, "notes/reactions", Vec<Value>);
+
+/// Possible errors from [`PollingClient`].
+pub enum PollingError<E> {
+    /// Errors from underlying client, namely `E`.
+    Client(E),
+    /// Errors from Misskey API.
+    Api(ApiError),
+    /// Failed to convert the fetched items into the events.
+    Json(serde_json::Error),
+    /// The operation is not supported by [`PollingClient`].
+    Unsupported(String),
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for PollingError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PollingError::Client(err) => Some(err),
+            PollingError::Api(err) => Some(err),
+            PollingError::Json(err) => Some(err),
+            PollingError::Unsupported(_) => None,
+        }
+    }
+}
+
+impl<E: std::error::Error> Display for PollingError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PollingError::Client(err) => Display::fmt(err, f),
+            PollingError::Api(_) => write!(f, "Misskey API returned an error"),
+            PollingError::Json(err) => write!(f, "JSON error: {}", err),
+            PollingError::Unsupported(what) => {
+                write!(f, "{} is not supported by polling client", what)
+            }
+        }
+    }
+}
+
+impl<E: std::error::Error> Debug for PollingError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PollingError::Client(err) => f.debug_tuple("Client").field(&err).finish(),
+            PollingError::Api(err) => f.debug_tuple("Api").field(&err).finish(),
+            PollingError::Json(err) => f.debug_tuple("Json").field(&err).finish(),
+            PollingError::Unsupported(what) => f.debug_tuple("Unsupported").field(&what).finish(),
+        }
+    }
+}
+
+impl<E> From<ApiError> for PollingError<E> {
+    fn from(err: ApiError) -> Self {
+        PollingError::Api(err)
+    }
+}
+
+impl<E> From<serde_json::Error> for PollingError<E> {
+    fn from(err: serde_json::Error) -> Self {
+        PollingError::Json(err)
+    }
+}
+
+/// Poll intervals of [`PollingClient`].
+#[derive(Debug, Clone)]
+pub struct PollingConfig {
+    /// Sets an interval duration of polling the timelines.
+    pub timeline_interval: Duration,
+    /// Sets an interval duration of polling the notifications for the main channel.
+    pub main_interval: Duration,
+    /// Sets an interval duration of polling the subscribed notes.
+    pub note_interval: Duration,
+}
+
+impl PollingConfig {
+    /// Creates a `PollingConfig` that uses the given interval for all kinds of polling.
+    pub fn with_interval(interval: Duration) -> PollingConfig {
+        PollingConfig {
+            timeline_interval: interval,
+            main_interval: interval,
+            note_interval: interval,
+        }
+    }
+}
+
+impl Default for PollingConfig {
+    /// All intervals are 5 secs by default.
+    fn default() -> PollingConfig {
+        PollingConfig::with_interval(Duration::from_secs(5))
+    }
+}
+
+/// [`StreamingClient`] implementation that polls the REST endpoints using [`Client`].
+///
+/// See the [module-level documentation][self] for the emulated channels.
+/// The returned streams keep polling after they yield an error, and stop polling when they are
+/// dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use misskey_util::StreamingClientExt;
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// use std::time::Duration;
+///
+/// use futures::stream::TryStreamExt;
+/// use misskey_util::polling::{PollingClient, PollingConfig};
+///
+/// # let http_client = misskey_test::test_http_client(misskey_test::env::token())?;
+/// let config = PollingConfig::with_interval(Duration::from_secs(10));
+/// let client = PollingClient::with_config(http_client, config);
+///
+/// let mut home = client.home_timeline().await?;
+/// while let Some(note) = home.try_next().await? {
+///     println!("{}", note.text.unwrap_or_default());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PollingClient<C> {
+    client: C,
+    config: PollingConfig,
+}
+
+impl<C> PollingClient<C> {
+    /// Creates a `PollingClient` with the default poll intervals.
+    pub fn new(client: C) -> Self {
+        PollingClient::with_config(client, PollingConfig::default())
+    }
+
+    /// Creates a `PollingClient` with the given poll intervals.
+    pub fn with_config(client: C, config: PollingConfig) -> Self {
+        PollingClient { client, config }
+    }
+
+    /// Returns a reference to the underlying client.
+    pub fn get_ref(&self) -> &C {
+        &self.client
+    }
+
+    /// Consumes this `PollingClient`, returning the underlying client.
+    pub fn into_inner(self) -> C {
+        self.client
+    }
+}
+
+impl<C: Client> Client for PollingClient<C> {
+    type Error = C::Error;
+
+    fn request<R: Request>(
+        &self,
+        request: R,
+    ) -> BoxFuture<'_, Result<ApiResult<R::Response>, Self::Error>> {
+        self.client.request(request)
+    }
+}
+
+impl<C> StreamingClient for PollingClient<C>
+where
+    C: Client + Sync,
+    C::Error: Send + 'static,
+{
+    type Error = PollingError<C::Error>;
+
+    fn subnote<E: SubNoteEvent>(
+        &self,
+        note_id: String,
+    ) -> BoxFuture<'_, Result<SubNoteStream<'_, E, Self::Error>, Self::Error>> {
+        let poller = NotePoller {
+            client: &self.client,
+            note_id,
+            reactions: None,
+            deleted: false,
+        };
+        let stream = polling_stream(poller, self.config.note_interval)
+            .map(|res| res.and_then(|v| serde_json::from_value(v).map_err(Into::into)));
+        future::ready(Ok(stream.boxed())).boxed()
+    }
+
+    fn channel<R: ConnectChannelRequest>(
+        &self,
+        request: R,
+    ) -> BoxFuture<'_, Result<ChannelStream<'_, R, Self::Error>, Self::Error>> {
+        let params = match serde_json::to_value(&request) {
+            Ok(Value::Object(params)) => params,
+            Ok(_) => Map::new(),
+            Err(e) => return future::ready(Err(e.into())).boxed(),
+        };
+
+        let client = &self.client;
+        let timeline = self.config.timeline_interval;
+        let stream = match R::NAME {
+            "homeTimeline" => paginated::<_, HomeTimeline>(client, params, "note", timeline),
+            "localTimeline" => paginated::<_, LocalTimeline>(client, params, "note", timeline),
+            "hybridTimeline" => paginated::<_, HybridTimeline>(client, params, "note", timeline),
+            "globalTimeline" => paginated::<_, GlobalTimeline>(client, params, "note", timeline),
+            "userList" => paginated::<_, UserListTimeline>(client, params, "note", timeline),
+            "antenna" => paginated::<_, AntennaNotes>(client, params, "note", timeline),
+            "channel" => paginated::<_, ChannelTimeline>(client, params, "note", timeline),
+            "main" => {
+                // watching the channel does not mark the notifications as read
+                let mut params = params;
+                params.insert("markAsRead".to_string(), json!(false));
+                paginated::<_, Notifications>(
+                    client,
+                    params,
+                    "notification",
+                    self.config.main_interval,
+                )
+            }
+            name => {
+                let err = PollingError::Unsupported(format!("{} channel", name));
+                return future::ready(Err(err)).boxed();
+            }
+        };
+
+        let stream = PollingChannel {
+            inner: stream,
+            _marker: PhantomData,
+        };
+        future::ready(Ok(Box::pin(stream) as ChannelStream<R, Self::Error>)).boxed()
+    }
+
+    fn broadcast<E: BroadcastEvent>(
+        &self,
+    ) -> BoxFuture<'_, Result<BroadcastStream<'_, E, Self::Error>, Self::Error>> {
+        let err = PollingError::Unsupported("broadcast stream".to_string());
+        future::ready(Err(err)).boxed()
+    }
+}
+
+/// Stream of channel events, which does not accept outgoing messages.
+struct PollingChannel<'a, I, O, E> {
+    inner: BoxStream<'a, Result<Value, PollingError<E>>>,
+    _marker: PhantomData<fn() -> (I, O)>,
+}
+
+impl<I: DeserializeOwned, O, E> Stream for PollingChannel<'_, I, O, E> {
+    type Item = Result<I, PollingError<E>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let result = futures::ready!(self.inner.poll_next_unpin(cx))
+            .map(|res| res.and_then(|v| serde_json::from_value(v).map_err(Into::into)));
+        Poll::Ready(result)
+    }
+}
+
+impl<I, O, E> Sink<O> for PollingChannel<'_, I, O, E> {
+    type Error = PollingError<E>;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, _item: O) -> Result<(), Self::Error> {
+        Err(PollingError::Unsupported(
+            "sending messages to channel".to_string(),
+        ))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+trait Poller: Send {
+    type Error;
+
+    /// Fetches new events, or returns `None` when no more events will come.
+    fn poll(&mut self) -> BoxFuture<'_, Result<Option<Vec<Value>>, Self::Error>>;
+}
+
+fn polling_stream<'a, P>(poller: P, interval: Duration) -> BoxStream<'a, Result<Value, P::Error>>
+where
+    P: Poller + 'a,
+    P::Error: Send,
+{
+    // the first poll is performed immediately to get the starting point
+    let state = (poller, VecDeque::new(), None);
+    stream::unfold(Some(state), move |state| async move {
+        let (mut poller, mut queue, mut delay) = state?;
+        loop {
+            if let Some(event) = queue.pop_front() {
+                return Some((Ok(event), Some((poller, queue, delay))));
+            }
+
+            if let Some(delay) = delay.take() {
+                delay.await;
+            }
+            delay = Some(Delay::new(interval));
+
+            match poller.poll().await {
+                Ok(Some(events)) => queue.extend(events),
+                Ok(None) => return None,
+                Err(e) => return Some((Err(e), Some((poller, queue, delay)))),
+            }
+        }
+    })
+    .boxed()
+}
+
+fn paginated<'a, C, Q>(
+    client: &'a C,
+    params: Map<String, Value>,
+    event_type: &'static str,
+    interval: Duration,
+) -> BoxStream<'a, Result<Value, PollingError<C::Error>>>
+where
+    C: Client + Sync + ?Sized,
+    C::Error: Send,
+    Q: Request<Response = Vec<Value>> + From<Map<String, Value>> + Send + 'a,
+{
+    let poller = PaginatedPoller::<C, Q> {
+        client,
+        params,
+        event_type,
+        since_id: None,
+        is_started: false,
+        _marker: PhantomData,
+    };
+    polling_stream(poller, interval)
+}
+
+/// Fetches the items newer than the last one using `sinceId`.
+struct PaginatedPoller<'a, C: ?Sized, Q> {
+    client: &'a C,
+    params: Map<String, Value>,
+    event_type: &'static str,
+    since_id: Option<String>,
+    is_started: bool,
+    _marker: PhantomData<fn() -> Q>,
+}
+
+fn item_id(item: &Value) -> Option<&str> {
+    item.get("id").and_then(Value::as_str)
+}
+
+impl<'a, C, Q> PaginatedPoller<'a, C, Q>
+where
+    C: Client + Sync + ?Sized,
+    Q: Request<Response = Vec<Value>> + From<Map<String, Value>>,
+{
+    async fn fetch(&self, limit: u64) -> Result<Vec<Value>, PollingError<C::Error>> {
+        let mut params = self.params.clone();
+        params.insert("limit".to_string(), json!(limit));
+        if let Some(since_id) = &self.since_id {
+            params.insert("sinceId".to_string(), json!(since_id));
+        }
+
+        let mut items = self
+            .client
+            .request(Q::from(params))
+            .await
+            .map_err(PollingError::Client)?
+            .into_result()?;
+        // IDs are sortable in the order of creation
+        items.sort_by(|a, b| item_id(a).cmp(&item_id(b)));
+        Ok(items)
+    }
+
+    async fn poll_new(&mut self) -> Result<Option<Vec<Value>>, PollingError<C::Error>> {
+        if !self.is_started {
+            // only the items created after the subscription are yielded
+            let latest = self.fetch(1).await?;
+            self.since_id = latest.last().and_then(item_id).map(ToOwned::to_owned);
+            self.is_started = true;
+            return Ok(Some(Vec::new()));
+        }
+
+        let mut events = Vec::new();
+        loop {
+            let items = self.fetch(PAGE_SIZE).await?;
+            let is_last_page = (items.len() as u64) < PAGE_SIZE;
+            if let Some(id) = items.last().and_then(item_id) {
+                self.since_id = Some(id.to_owned());
+            }
+            events.extend(
+                items
+                    .into_iter()
+                    .map(|item| json!({ "type": self.event_type, "body": item })),
+            );
+            if is_last_page {
+                return Ok(Some(events));
+            }
+        }
+    }
+}
+
+impl<'a, C, Q> Poller for PaginatedPoller<'a, C, Q>
+where
+    C: Client + Sync + ?Sized,
+    C::Error: Send,
+    Q: Request<Response = Vec<Value>> + From<Map<String, Value>> + Send,
+{
+    type Error = PollingError<C::Error>;
+
+    fn poll(&mut self) -> BoxFuture<'_, Result<Option<Vec<Value>>, Self::Error>> {
+        self.poll_new().boxed()
+    }
+}
+
+/// Tracks the reactions and the deletion of the note.
+struct NotePoller<'a, C: ?Sized> {
+    client: &'a C,
+    note_id: String,
+    /// reaction ID to the event body, `None` before the first poll
+    reactions: Option<Vec<(String, Value)>>,
+    deleted: bool,
+}
+
+impl<'a, C> NotePoller<'a, C>
+where
+    C: Client + Sync + ?Sized,
+{
+    async fn poll_new(&mut self) -> Result<Option<Vec<Value>>, PollingError<C::Error>> {
+        if self.deleted {
+            return Ok(None);
+        }
+
+        let mut params = Map::new();
+        params.insert("noteId".to_string(), json!(self.note_id));
+
+        let show = self
+            .client
+            .request(ShowNote(params.clone()))
+            .await
+            .map_err(PollingError::Client)?;
+        match show.into_result() {
+            Ok(_) => {}
+            Err(e) if e.code == "NO_SUCH_NOTE" => {
+                self.deleted = true;
+                let body = json!({ "deletedAt": chrono::Utc::now().to_rfc3339() });
+                return Ok(Some(vec![json!({ "type": "deleted", "body": body })]));
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        params.insert("limit".to_string(), json!(PAGE_SIZE));
+        let items = self
+            .client
+            .request(NoteReactions(params))
+            .await
+            .map_err(PollingError::Client)?
+            .into_result()?;
+        // removed reactions can only be detected when all of them are fetched
+        let is_complete = (items.len() as u64) < PAGE_SIZE;
+        let mut current: Vec<(String, Value)> = items
+            .iter()
+            .filter_map(|item| {
+                let id = item_id(item)?.to_owned();
+                let body = json!({
+                    "reaction": item.get("type")?,
+                    "userId": item.get("user")?.get("id")?,
+                });
+                Some((id, body))
+            })
+            .collect();
+        current.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let previous = match self.reactions.replace(current.clone()) {
+            Some(previous) => previous,
+            None => return Ok(Some(Vec::new())),
+        };
+
+        let previous_ids: HashSet<_> = previous.iter().map(|(id, _)| id.as_str()).collect();
+        let current_ids: HashSet<_> = current.iter().map(|(id, _)| id.as_str()).collect();
+        let oldest_id = current.first().map(|(id, _)| id.as_str());
+
+        let mut events = Vec::new();
+        for (id, body) in &previous {
+            let is_in_range =
+                is_complete || matches!(oldest_id, Some(oldest) if id.as_str() > oldest);
+            if is_in_range && !current_ids.contains(id.as_str()) {
+                events.push(json!({ "type": "unreacted", "body": body }));
+            }
+        }
+        for (id, body) in &current {
+            if !previous_ids.contains(id.as_str()) {
+                events.push(json!({ "type": "reacted", "body": body }));
+            }
+        }
+        Ok(Some(events))
+    }
+}
+
+impl<'a, C> Poller for NotePoller<'a, C>
+where
+    C: Client + Sync + ?Sized,
+    C::Error: Send,
+{
+    type Error = PollingError<C::Error>;
+
+    fn poll(&mut self) -> BoxFuture<'_, Result<Option<Vec<Value>>, Self::Error>> {
+        self.poll_new().boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+    use std::time::Duration;
+
+    use super::{HomeTimeline, PaginatedPoller, PollingClient, PollingConfig, PAGE_SIZE};
+    use crate::test::StubClient;
+
+    use futures::{FutureExt, StreamExt};
+    use misskey_api::streaming::channel::main;
+    use misskey_core::streaming::StreamingClient;
+    use serde_json::{json, Map, Value};
+
+    fn poller(client: &StubClient) -> PaginatedPoller<'_, StubClient, HomeTimeline> {
+        PaginatedPoller {
+            client,
+            params: Map::new(),
+            event_type: "note",
+            since_id: None,
+            is_started: false,
+            _marker: PhantomData,
+        }
+    }
+
+    fn note(id: &str) -> Value {
+        json!({ "id": id })
+    }
+
+    fn event(id: &str) -> Value {
+        json!({ "type": "note", "body": { "id": id } })
+    }
+
+    #[tokio::test]
+    async fn first_poll_yields_nothing() {
+        let client = StubClient::default();
+        let mut poller = poller(&client);
+
+        client.respond(json!([note("b")]));
+        assert_eq!(poller.poll_new().await.unwrap(), Some(Vec::new()));
+        assert_eq!(poller.since_id.as_deref(), Some("b"));
+        assert_eq!(
+            client.requests(),
+            vec![("notes/timeline", json!({ "limit": 1 }))]
+        );
+    }
+
+    #[tokio::test]
+    async fn first_poll_on_empty_timeline() {
+        let client = StubClient::default();
+        let mut poller = poller(&client);
+
+        client.respond(json!([]));
+        assert_eq!(poller.poll_new().await.unwrap(), Some(Vec::new()));
+        assert_eq!(poller.since_id, None);
+
+        client.respond(json!([note("a")]));
+        assert_eq!(poller.poll_new().await.unwrap(), Some(vec![event("a")]));
+        assert_eq!(poller.since_id.as_deref(), Some("a"));
+        assert_eq!(
+            client.requests(),
+            vec![
+                ("notes/timeline", json!({ "limit": 1 })),
+                ("notes/timeline", json!({ "limit": PAGE_SIZE })),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn since_id_tracking() {
+        let client = StubClient::default();
+        let mut poller = poller(&client);
+
+        client.respond(json!([note("b")]));
+        poller.poll_new().await.unwrap();
+
+        // the items are yielded in the order of the IDs
+        client.respond(json!([note("d"), note("c")]));
+        assert_eq!(
+            poller.poll_new().await.unwrap(),
+            Some(vec![event("c"), event("d")])
+        );
+        assert_eq!(poller.since_id.as_deref(), Some("d"));
+
+        // since_id is kept when nothing new is found
+        client.respond(json!([]));
+        assert_eq!(poller.poll_new().await.unwrap(), Some(Vec::new()));
+        assert_eq!(poller.since_id.as_deref(), Some("d"));
+
+        client.respond(json!([note("e")]));
+        assert_eq!(poller.poll_new().await.unwrap(), Some(vec![event("e")]));
+
+        assert_eq!(
+            client.requests(),
+            vec![
+                ("notes/timeline", json!({ "limit": 1 })),
+                (
+                    "notes/timeline",
+                    json!({ "limit": PAGE_SIZE, "sinceId": "b" })
+                ),
+                (
+                    "notes/timeline",
+                    json!({ "limit": PAGE_SIZE, "sinceId": "d" })
+                ),
+                (
+                    "notes/timeline",
+                    json!({ "limit": PAGE_SIZE, "sinceId": "d" })
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_all_pages() {
+        let client = StubClient::default();
+        let mut poller = poller(&client);
+
+        client.respond(json!([note("a")]));
+        poller.poll_new().await.unwrap();
+
+        let ids: Vec<String> = (0..PAGE_SIZE + 1).map(|i| format!("b{:03}", i)).collect();
+        let (first, second) = ids.split_at(PAGE_SIZE as usize);
+        client.respond(first.iter().map(|id| note(id)).collect());
+        client.respond(second.iter().map(|id| note(id)).collect());
+
+        let events = poller.poll_new().await.unwrap().unwrap();
+        assert_eq!(events, ids.iter().map(|id| event(id)).collect::<Vec<_>>());
+        assert_eq!(poller.since_id.as_ref(), ids.last());
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[1].1["sinceId"], json!("a"));
+        assert_eq!(requests[2].1["sinceId"], json!(first.last().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn main_channel_does_not_mark_as_read() {
+        let client = StubClient::default();
+        let config = PollingConfig::with_interval(Duration::from_secs(3600));
+        let client = PollingClient::with_config(client, config);
+
+        client.get_ref().respond(json!([]));
+        let mut stream = client.channel(main::Request::default()).await.unwrap();
+        // the first poll is performed immediately, and then the stream waits for the interval
+        assert!(stream.next().now_or_never().is_none());
+        assert_eq!(
+            client.get_ref().requests(),
+            vec![(
+                "i/notifications",
+                json!({ "limit": 1, "markAsRead": false })
+            )]
+        );
+    }
+}
@@ -0,0 +1,561 @@
+//! Rules of reversi games on Misskey.
+//!
+//! [`Board`] follows the same rules as the server, including the custom maps and the options
+//! of the game, so you can track the state of a game and choose moves in your bots.
+//!
+//! ```
+//! use misskey_api::model::reversi::ReversiColor;
+//! use misskey_util::reversi::{Board, BoardOptions};
+//!
+//! let map = [
+//!     "--------", "--------", "--------", "---wb---",
+//!     "---bw---", "--------", "--------", "--------",
+//! ];
+//! let mut board = Board::new(map, BoardOptions::default())?;
+//! assert_eq!(board.turn(), Some(ReversiColor::Black));
+//! assert_eq!(board.puttable_places(ReversiColor::Black), vec![19, 26, 37, 44]);
+//!
+//! let flipped = board.put(ReversiColor::Black, 19)?;
+//! assert_eq!(flipped, vec![27]);
+//! assert_eq!(board.count(ReversiColor::Black), 4);
+//! assert_eq!(board.count(ReversiColor::White), 1);
+//! assert_eq!(board.turn(), Some(ReversiColor::White));
+//! # Ok::<(), misskey_util::reversi::BoardError>(())
+//! ```
+
+use std::fmt::{self, Display};
+
+pub use misskey_api::model::reversi::ReversiColor;
+use misskey_api::model::reversi::ReversiGame;
+
+const DIRECTIONS: [(i64, i64); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// Possible errors from [`Board`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardError {
+    /// The map is empty or its rows are not of the same length.
+    InvalidMap,
+    /// The game is already over.
+    Ended,
+    /// It is not the turn of the color.
+    NotYourTurn(ReversiColor),
+    /// A stone of the color cannot be put at the position.
+    CannotPut(ReversiColor, u64),
+}
+
+impl std::error::Error for BoardError {}
+
+impl Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardError::InvalidMap => write!(f, "invalid reversi map"),
+            BoardError::Ended => write!(f, "the game is already over"),
+            BoardError::NotYourTurn(color) => write!(f, "not the turn of {:?}", color),
+            BoardError::CannotPut(color, pos) => {
+                write!(f, "{:?} stone cannot be put at {}", color, pos)
+            }
+        }
+    }
+}
+
+/// Options of the game, which correspond to the settings in [`ReversiGame`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BoardOptions {
+    /// The player with fewer stones wins.
+    pub is_llotheo: bool,
+    /// Stones can be put on any empty cell, even if they flip nothing.
+    pub can_put_everywhere: bool,
+    /// The edges of the board are connected to the opposite ones.
+    pub looped_board: bool,
+}
+
+/// A cell of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    /// A cell where stones cannot be put.
+    Wall,
+    /// An empty cell.
+    Empty,
+    /// A cell with the stone of the color.
+    Stone(ReversiColor),
+}
+
+/// State of a reversi game.
+///
+/// See the [module-level documentation][self] for an example.
+#[derive(Debug, Clone)]
+pub struct Board {
+    width: u64,
+    height: u64,
+    cells: Vec<Cell>,
+    options: BoardOptions,
+    turn: Option<ReversiColor>,
+}
+
+impl Board {
+    /// Creates a board from the rows of the map.
+    ///
+    /// In the map, `-` is an empty cell, `b` and `w` are cells with black and white stones,
+    /// and the other characters are walls.
+    pub fn new(
+        map: impl IntoIterator<Item = impl AsRef<str>>,
+        options: BoardOptions,
+    ) -> Result<Self, BoardError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for row in map {
+            let len = cells.len();
+            cells.extend(row.as_ref().chars().map(|c| match c {
+                '-' => Cell::Empty,
+                'b' => Cell::Stone(ReversiColor::Black),
+                'w' => Cell::Stone(ReversiColor::White),
+                _ => Cell::Wall,
+            }));
+            let row_width = (cells.len() - len) as u64;
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(BoardError::InvalidMap);
+            }
+            height += 1;
+        }
+        let width = match width {
+            Some(width) if width > 0 => width,
+            _ => return Err(BoardError::InvalidMap),
+        };
+
+        let mut board = Board {
+            width,
+            height,
+            cells,
+            options,
+            turn: None,
+        };
+        // some maps have no places for black to put at first, or are over before starting
+        board.calc_turn(ReversiColor::White);
+        Ok(board)
+    }
+
+    /// Creates a board with the map and the settings of the game, and replays the moves in it.
+    ///
+    /// The map and the moves are only included in the detailed game object, such as the one
+    /// obtained from `games/reversi/games/show` and the `started` event of the game channel.
+    pub fn from_game(game: &ReversiGame) -> Result<Self, BoardError> {
+        let map = game.map.as_ref().ok_or(BoardError::InvalidMap)?;
+        let options = BoardOptions {
+            is_llotheo: game.is_llotheo,
+            can_put_everywhere: game.can_put_everywhere,
+            looped_board: game.looped_board,
+        };
+        let mut board = Board::new(map, options)?;
+        for log in game.logs.iter().flatten() {
+            board.put(log.color, log.pos)?;
+        }
+        Ok(board)
+    }
+
+    /// Returns the number of columns of the board.
+    pub fn width(&self) -> u64 {
+        self.width
+    }
+
+    /// Returns the number of rows of the board.
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    /// Returns the options of the game.
+    pub fn options(&self) -> BoardOptions {
+        self.options
+    }
+
+    /// Converts the position to the coordinates `(x, y)`.
+    pub fn pos_to_xy(&self, pos: u64) -> (u64, u64) {
+        (pos % self.width, pos / self.width)
+    }
+
+    /// Converts the coordinates `(x, y)` to the position.
+    pub fn xy_to_pos(&self, x: u64, y: u64) -> u64 {
+        x + y * self.width
+    }
+
+    /// Returns the cell at the position, or `None` if it is out of the board.
+    pub fn get(&self, pos: u64) -> Option<Cell> {
+        self.cells.get(pos as usize).copied()
+    }
+
+    /// Returns the number of stones of the color.
+    pub fn count(&self, color: ReversiColor) -> usize {
+        self.cells
+            .iter()
+            .filter(|&&cell| cell == Cell::Stone(color))
+            .count()
+    }
+
+    /// Returns the color to play next, or `None` if the game is over.
+    pub fn turn(&self) -> Option<ReversiColor> {
+        self.turn
+    }
+
+    /// Returns `true` if neither color can put stones anymore.
+    pub fn is_ended(&self) -> bool {
+        self.turn.is_none()
+    }
+
+    /// Returns the color of the winner, or `None` if the game is not over or is a draw.
+    pub fn winner(&self) -> Option<ReversiColor> {
+        if !self.is_ended() {
+            return None;
+        }
+        let black = self.count(ReversiColor::Black);
+        let white = self.count(ReversiColor::White);
+        if black == white {
+            None
+        } else if (black > white) != self.options.is_llotheo {
+            Some(ReversiColor::Black)
+        } else {
+            Some(ReversiColor::White)
+        }
+    }
+
+    /// Returns `true` if a stone of the color can be put at the position.
+    pub fn can_put(&self, color: ReversiColor, pos: u64) -> bool {
+        if self.get(pos) != Some(Cell::Empty) {
+            return false;
+        }
+        self.options.can_put_everywhere || !self.effects(color, pos).is_empty()
+    }
+
+    /// Returns the positions where stones of the color can be put, in ascending order.
+    pub fn puttable_places(&self, color: ReversiColor) -> Vec<u64> {
+        (0..self.cells.len() as u64)
+            .filter(|&pos| self.can_put(color, pos))
+            .collect()
+    }
+
+    /// Returns the positions of the stones flipped when a stone of the color is put at the position,
+    /// in ascending order.
+    pub fn effects(&self, color: ReversiColor, pos: u64) -> Vec<u64> {
+        let mut effects: Vec<u64> = DIRECTIONS
+            .iter()
+            .flat_map(|&direction| self.effects_in_line(color, pos, direction))
+            .collect();
+        // lines on looped boards can reach the same stones from both sides
+        effects.sort_unstable();
+        effects.dedup();
+        effects
+    }
+
+    fn effects_in_line(&self, color: ReversiColor, init: u64, (dx, dy): (i64, i64)) -> Vec<u64> {
+        let (width, height) = (self.width as i64, self.height as i64);
+        let (x, y) = self.pos_to_xy(init);
+        let (mut x, mut y) = (x as i64, y as i64);
+        let mut found = Vec::new();
+        loop {
+            x += dx;
+            y += dy;
+            if self.options.looped_board {
+                x = x.rem_euclid(width);
+                y = y.rem_euclid(height);
+                // stones can be sandwiched by the stone itself when the line loops back to it
+                if self.xy_to_pos(x as u64, y as u64) == init {
+                    return found;
+                }
+            } else if x < 0 || y < 0 || x >= width || y >= height {
+                return Vec::new();
+            }
+
+            let pos = self.xy_to_pos(x as u64, y as u64);
+            match self.cells[pos as usize] {
+                Cell::Wall | Cell::Empty => return Vec::new(),
+                Cell::Stone(stone) if stone == color => return found,
+                Cell::Stone(_) => found.push(pos),
+            }
+        }
+    }
+
+    /// Puts a stone of the color at the position and returns the positions of the flipped stones.
+    ///
+    /// Fails if it is not the turn of the color or the stone cannot be put there.
+    pub fn put(&mut self, color: ReversiColor, pos: u64) -> Result<Vec<u64>, BoardError> {
+        match self.turn {
+            None => return Err(BoardError::Ended),
+            Some(turn) if turn != color => return Err(BoardError::NotYourTurn(color)),
+            Some(_) => {}
+        }
+        if !self.can_put(color, pos) {
+            return Err(BoardError::CannotPut(color, pos));
+        }
+
+        let effects = self.effects(color, pos);
+        self.cells[pos as usize] = Cell::Stone(color);
+        for &flipped in &effects {
+            self.cells[flipped as usize] = Cell::Stone(color);
+        }
+        self.calc_turn(color);
+        Ok(effects)
+    }
+
+    fn calc_turn(&mut self, prev: ReversiColor) {
+        self.turn = [prev.opposite(), prev]
+            .iter()
+            .copied()
+            .find(|&color| self.can_put_somewhere(color));
+    }
+
+    fn can_put_somewhere(&self, color: ReversiColor) -> bool {
+        (0..self.cells.len() as u64).any(|pos| self.can_put(color, pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Board, BoardError, BoardOptions, Cell, ReversiColor};
+
+    const BLACK: ReversiColor = ReversiColor::Black;
+    const WHITE: ReversiColor = ReversiColor::White;
+
+    fn board(map: &[&str], options: BoardOptions) -> Board {
+        Board::new(map, options).unwrap()
+    }
+
+    #[test]
+    fn flip_in_all_directions() {
+        let map = ["b-b-b", "-www-", "bw-wb", "-www-", "b-b-b"];
+        let mut board = board(&map, BoardOptions::default());
+        assert_eq!(board.effects(BLACK, 12), vec![6, 7, 8, 11, 13, 16, 17, 18]);
+
+        let flipped = board.put(BLACK, 12).unwrap();
+        assert_eq!(flipped, vec![6, 7, 8, 11, 13, 16, 17, 18]);
+        assert_eq!(board.count(BLACK), 17);
+        assert_eq!(board.count(WHITE), 0);
+    }
+
+    #[test]
+    fn walls_stop_lines() {
+        let open = board(&["bw--"], BoardOptions::default());
+        assert_eq!(open.effects(BLACK, 2), vec![1]);
+
+        let walled = board(&["bw#-"], BoardOptions::default());
+        assert_eq!(walled.get(2), Some(Cell::Wall));
+        assert!(walled.effects(BLACK, 3).is_empty());
+        assert!(!walled.can_put(BLACK, 3));
+        assert!(!walled.can_put(BLACK, 2));
+    }
+
+    #[test]
+    fn edges_stop_lines() {
+        let board = board(&["-w", "--"], BoardOptions::default());
+        assert!(board.effects(BLACK, 0).is_empty());
+    }
+
+    #[test]
+    fn looped_board() {
+        let map = ["b--w", "----", "----"];
+        let flat = board(&map, BoardOptions::default());
+        assert!(flat.effects(BLACK, 2).is_empty());
+
+        let options = BoardOptions {
+            looped_board: true,
+            ..BoardOptions::default()
+        };
+        let looped = board(&map, options);
+        assert_eq!(looped.effects(BLACK, 2), vec![3]);
+    }
+
+    #[test]
+    fn looped_board_sandwich_by_itself() {
+        let options = BoardOptions {
+            looped_board: true,
+            ..BoardOptions::default()
+        };
+        let mut board = board(&["-www", "----", "----"], options);
+        assert_eq!(board.puttable_places(BLACK), vec![0]);
+
+        let flipped = board.put(BLACK, 0).unwrap();
+        assert_eq!(flipped, vec![1, 2, 3]);
+        assert_eq!(board.count(BLACK), 4);
+        assert_eq!(board.count(WHITE), 0);
+    }
+
+    #[test]
+    fn can_put_everywhere() {
+        let map = ["b-", "--"];
+        let strict = board(&map, BoardOptions::default());
+        assert!(strict.puttable_places(BLACK).is_empty());
+        assert!(strict.is_ended());
+
+        let options = BoardOptions {
+            can_put_everywhere: true,
+            ..BoardOptions::default()
+        };
+        let mut board = board(&map, options);
+        assert_eq!(board.turn(), Some(BLACK));
+        assert_eq!(board.puttable_places(BLACK), vec![1, 2, 3]);
+        assert!(!board.can_put(BLACK, 0));
+
+        assert_eq!(board.put(BLACK, 3), Ok(Vec::new()));
+        assert_eq!(board.turn(), Some(WHITE));
+        assert_eq!(board.puttable_places(WHITE), vec![1, 2]);
+    }
+
+    #[test]
+    fn pass_turn() {
+        let mut board = board(&["-wbw-"], BoardOptions::default());
+        assert_eq!(board.turn(), Some(BLACK));
+        assert!(board.puttable_places(WHITE).is_empty());
+        assert_eq!(board.put(WHITE, 0), Err(BoardError::NotYourTurn(WHITE)));
+
+        assert_eq!(board.put(BLACK, 0), Ok(vec![1]));
+        // white has nowhere to put, so black plays again
+        assert_eq!(board.turn(), Some(BLACK));
+        assert_eq!(board.put(BLACK, 2), Err(BoardError::CannotPut(BLACK, 2)));
+        assert_eq!(board.put(BLACK, 4), Ok(vec![3]));
+    }
+
+    #[test]
+    fn end_of_game() {
+        let mut board = board(&["-wbw-"], BoardOptions::default());
+        assert!(!board.is_ended());
+        assert_eq!(board.winner(), None);
+
+        board.put(BLACK, 0).unwrap();
+        board.put(BLACK, 4).unwrap();
+        assert!(board.is_ended());
+        assert_eq!(board.turn(), None);
+        assert_eq!(board.winner(), Some(BLACK));
+        assert_eq!(board.put(WHITE, 0), Err(BoardError::Ended));
+    }
+
+    #[test]
+    fn winner() {
+        let board = board(&["bww"], BoardOptions::default());
+        assert!(board.is_ended());
+        assert_eq!(board.winner(), Some(WHITE));
+
+        let draw = Board::new(["bw"], BoardOptions::default()).unwrap();
+        assert!(draw.is_ended());
+        assert_eq!(draw.winner(), None);
+    }
+
+    #[test]
+    fn llotheo_inverts_winner() {
+        let options = BoardOptions {
+            is_llotheo: true,
+            ..BoardOptions::default()
+        };
+        let board = board(&["bww"], options);
+        assert_eq!(board.winner(), Some(BLACK));
+
+        let draw = Board::new(["bw"], options).unwrap();
+        assert_eq!(draw.winner(), None);
+    }
+
+    #[test]
+    fn invalid_map() {
+        let options = BoardOptions::default();
+        assert_eq!(
+            Board::new(["---", "--"], options).unwrap_err(),
+            BoardError::InvalidMap
+        );
+        assert_eq!(
+            Board::new(Vec::<&str>::new(), options).unwrap_err(),
+            BoardError::InvalidMap
+        );
+        assert_eq!(
+            Board::new(["", ""], options).unwrap_err(),
+            BoardError::InvalidMap
+        );
+    }
+
+    #[test]
+    fn board_size() {
+        let board = board(&["----", "-bw-", "----"], BoardOptions::default());
+        assert_eq!((board.width(), board.height()), (4, 3));
+        assert_eq!(board.pos_to_xy(6), (2, 1));
+        assert_eq!(board.xy_to_pos(2, 1), 6);
+        assert_eq!(board.get(5), Some(Cell::Stone(BLACK)));
+        assert_eq!(board.get(12), None);
+    }
+
+    // the IDs in the game object are in the aid format
+    #[cfg(feature = "aid")]
+    fn game(logs: serde_json::Value) -> misskey_api::model::reversi::ReversiGame {
+        let user = |id: &str| {
+            serde_json::json!({
+                "id": id,
+                "username": "user",
+                "name": null,
+                "avatarUrl": null,
+                "avatarBlurhash": null,
+                "avatarColor": null,
+                "bannerColor": null,
+                "emojis": [],
+                "host": null,
+            })
+        };
+        serde_json::from_value(serde_json::json!({
+            "id": "8ejiidh50m",
+            "createdAt": "2020-01-01T00:00:00.000Z",
+            "startedAt": "2020-01-01T00:00:00.000Z",
+            "isStarted": true,
+            "isEnded": false,
+            "user1Accepted": true,
+            "user2Accepted": true,
+            "user1Id": "8dhemt9ubf",
+            "user2Id": "8dhe5zqidm",
+            "user1": user("8dhemt9ubf"),
+            "user2": user("8dhe5zqidm"),
+            "winnerId": null,
+            "surrendered": null,
+            "black": 1,
+            "bw": "random",
+            "isLlotheo": false,
+            "canPutEverywhere": false,
+            "loopedBoard": false,
+            "logs": logs,
+            "map": [
+                "--------", "--------", "--------", "---wb---",
+                "---bw---", "--------", "--------", "--------",
+            ],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "aid")]
+    fn from_game() {
+        let game = game(serde_json::json!([
+            { "color": true, "pos": 19 },
+            { "color": false, "pos": 18 },
+        ]));
+        let board = Board::from_game(&game).unwrap();
+        assert_eq!(board.get(18), Some(Cell::Stone(WHITE)));
+        assert_eq!(board.get(19), Some(Cell::Stone(BLACK)));
+        assert_eq!(board.get(27), Some(Cell::Stone(WHITE)));
+        assert_eq!(board.count(BLACK), 3);
+        assert_eq!(board.count(WHITE), 3);
+        assert_eq!(board.turn(), Some(BLACK));
+    }
+
+    #[test]
+    #[cfg(feature = "aid")]
+    fn from_game_with_invalid_log() {
+        let game = game(serde_json::json!([
+            { "color": true, "pos": 19 },
+            { "color": true, "pos": 18 },
+        ]));
+        assert_eq!(
+            Board::from_game(&game).unwrap_err(),
+            BoardError::NotYourTurn(BLACK)
+        );
+    }
+}
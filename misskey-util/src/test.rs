@@ -0,0 +1,40 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::Mutex;
+
+use futures::future::{BoxFuture, FutureExt};
+use misskey_core::model::ApiResult;
+use misskey_core::{Client, Request};
+use serde_json::Value;
+
+/// Client that records the requests and replies with the prepared responses.
+#[derive(Default)]
+pub(crate) struct StubClient {
+    requests: Mutex<Vec<(&'static str, Value)>>,
+    responses: Mutex<VecDeque<Value>>,
+}
+
+impl StubClient {
+    pub(crate) fn respond(&self, response: Value) {
+        self.responses.lock().unwrap().push_back(response);
+    }
+
+    /// Takes the requests made so far as pairs of the endpoint and the parameters.
+    pub(crate) fn requests(&self) -> Vec<(&'static str, Value)> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+impl Client for StubClient {
+    type Error = Infallible;
+
+    fn request<R: Request>(
+        &self,
+        request: R,
+    ) -> BoxFuture<'_, Result<ApiResult<R::Response>, Self::Error>> {
+        let params = serde_json::to_value(&request).unwrap();
+        self.requests.lock().unwrap().push((R::ENDPOINT, params));
+        let response = self.responses.lock().unwrap().pop_front().unwrap();
+        async move { Ok(serde_json::from_value(response).unwrap()) }.boxed()
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use crate::Error;
+
+use futures::stream::{self, BoxStream, StreamExt};
+use futures_timer::Delay;
+use misskey_api::endpoint::{self, hashtags::trend::TrendingHashtag};
+use misskey_api::model::note::Tag;
+use misskey_core::Client;
+
+/// Change in the trending hashtags, yielded from
+/// [`watch_hashtag_trend`][`crate::ClientExt::watch_hashtag_trend`].
+///
+/// Ranks start from 1, which is the most trending hashtag.
+#[derive(Debug, Clone)]
+pub enum TrendEvent {
+    /// The hashtag has entered the trend.
+    Entered {
+        /// The hashtag in the trend.
+        hashtag: TrendingHashtag,
+        /// The rank of the hashtag.
+        rank: usize,
+    },
+    /// The hashtag has left the trend.
+    Left {
+        /// The hashtag that has left.
+        tag: Tag,
+        /// The rank of the hashtag before it left.
+        rank: usize,
+    },
+    /// The rank of the hashtag in the trend has changed.
+    RankChanged {
+        /// The hashtag in the trend.
+        hashtag: TrendingHashtag,
+        /// The previous rank of the hashtag.
+        from: usize,
+        /// The current rank of the hashtag.
+        to: usize,
+    },
+}
+
+/// Computes the events that turn `previous` into `current`.
+fn diff(previous: &[Tag], current: &[TrendingHashtag]) -> Vec<TrendEvent> {
+    let previous_ranks: HashMap<&Tag, usize> = previous.iter().zip(1..).collect();
+    let current_ranks: HashMap<&Tag, usize> = current
+        .iter()
+        .zip(1..)
+        .map(|(hashtag, rank)| (&hashtag.tag, rank))
+        .collect();
+
+    let mut events = Vec::new();
+    for (tag, rank) in previous.iter().zip(1..) {
+        if !current_ranks.contains_key(tag) {
+            events.push(TrendEvent::Left {
+                tag: tag.clone(),
+                rank,
+            });
+        }
+    }
+    for (hashtag, rank) in current.iter().zip(1..) {
+        match previous_ranks.get(&hashtag.tag) {
+            None => events.push(TrendEvent::Entered {
+                hashtag: hashtag.clone(),
+                rank,
+            }),
+            Some(&from) if from != rank => events.push(TrendEvent::RankChanged {
+                hashtag: hashtag.clone(),
+                from,
+                to: rank,
+            }),
+            Some(_) => {}
+        }
+    }
+    events
+}
+
+pub(crate) fn watch<C>(
+    client: &C,
+    interval: Duration,
+) -> BoxStream<'_, Result<TrendEvent, Error<C::Error>>>
+where
+    C: Client + Sync + ?Sized,
+    C::Error: Send,
+{
+    // the first poll is performed immediately, which yields `Entered` for the initial trend
+    let state = (Vec::new(), VecDeque::new(), None);
+    stream::unfold(
+        state,
+        move |(mut previous, mut queue, mut delay)| async move {
+            loop {
+                if let Some(event) = queue.pop_front() {
+                    return Some((Ok(event), (previous, queue, delay)));
+                }
+
+                if let Some(delay) = delay.take() {
+                    delay.await;
+                }
+                delay = Some(Delay::new(interval));
+
+                let result = client
+                    .request(endpoint::hashtags::trend::Request::default())
+                    .await
+                    .map_err(Error::Client)
+                    .and_then(|res| res.into_result().map_err(Into::into));
+                match result {
+                    Ok(current) => {
+                        queue.extend(diff(&previous, &current));
+                        previous = current.into_iter().map(|hashtag| hashtag.tag).collect();
+                    }
+                    Err(e) => return Some((Err(e), (previous, queue, delay))),
+                }
+            }
+        },
+    )
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, TrendEvent};
+
+    use misskey_api::endpoint::hashtags::trend::TrendingHashtag;
+    use misskey_api::model::note::Tag;
+
+    fn tags(names: &[&str]) -> Vec<Tag> {
+        names.iter().map(|name| Tag(name.to_string())).collect()
+    }
+
+    fn trend(names: &[&str]) -> Vec<TrendingHashtag> {
+        tags(names)
+            .into_iter()
+            .map(|tag| TrendingHashtag {
+                tag,
+                chart: Vec::new(),
+                users_count: 0,
+            })
+            .collect()
+    }
+
+    /// Converts the events into `(kind, tag, from, to)` to compare them easily.
+    fn summary(events: Vec<TrendEvent>) -> Vec<(&'static str, String, usize, usize)> {
+        events
+            .into_iter()
+            .map(|event| match event {
+                TrendEvent::Entered { hashtag, rank } => ("entered", hashtag.tag.0, 0, rank),
+                TrendEvent::Left { tag, rank } => ("left", tag.0, rank, 0),
+                TrendEvent::RankChanged { hashtag, from, to } => {
+                    ("changed", hashtag.tag.0, from, to)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn empty_previous() {
+        let events = diff(&[], &trend(&["a", "b"]));
+        assert_eq!(
+            summary(events),
+            vec![
+                ("entered", "a".to_string(), 0, 1),
+                ("entered", "b".to_string(), 0, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn unchanged() {
+        let events = diff(&tags(&["a", "b"]), &trend(&["a", "b"]));
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn entered_and_left() {
+        let events = diff(&tags(&["a", "b", "c"]), &trend(&["a", "d", "c"]));
+        assert_eq!(
+            summary(events),
+            vec![
+                ("left", "b".to_string(), 2, 0),
+                ("entered", "d".to_string(), 0, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn all_left() {
+        let events = diff(&tags(&["a", "b"]), &[]);
+        assert_eq!(
+            summary(events),
+            vec![
+                ("left", "a".to_string(), 1, 0),
+                ("left", "b".to_string(), 2, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn rank_changed() {
+        let events = diff(&tags(&["a", "b", "c", "d"]), &trend(&["b", "a", "c", "e"]));
+        assert_eq!(
+            summary(events),
+            vec![
+                ("left", "d".to_string(), 4, 0),
+                ("changed", "b".to_string(), 2, 1),
+                ("changed", "a".to_string(), 1, 2),
+                ("entered", "e".to_string(), 0, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn rank_changed_by_leaving() {
+        // `c` is at the same rank and `d` moves up after `b` left
+        let events = diff(&tags(&["a", "b", "c", "d"]), &trend(&["a", "d", "c"]));
+        assert_eq!(
+            summary(events),
+            vec![
+                ("left", "b".to_string(), 2, 0),
+                ("changed", "d".to_string(), 4, 2),
+            ]
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Receiving events sent from webhooks.
+//!
+//! Misskey sends the events a webhook is subscribed to as `POST` requests to the configured URL,
+//! with the secret of the webhook in the [`X-Misskey-Hook-Secret`][SECRET_HEADER] header.
+//! [`WebhookReceiver`] checks the secret and parses the request body into [`WebhookPayload`],
+//! so you can receive events from any HTTP server without keeping a streaming connection open.
+//!
+//! The following is a minimal local stand-in of an HTTP server that accepts a single request.
+//!
+//! ```
+//! use std::io::{BufRead, BufReader, Read, Write};
+//! use std::net::{TcpListener, TcpStream};
+//!
+//! use misskey_api::model::webhook::WebhookEvent;
+//! use misskey_util::webhook::{ReceiveError, WebhookReceiver};
+//!
+//! let listener = TcpListener::bind("127.0.0.1:0")?;
+//! let addr = listener.local_addr()?;
+//!
+//! // send a request as Misskey does
+//! let body = r#"{
+//!     "hookId": "8g6gkqhp7n", "userId": "8g6gkqhp7m", "eventId": "event",
+//!     "createdAt": 1609459200000, "type": "followed",
+//!     "body": { "user": { "id": "8g6gkqhp7l", "username": "alice" } }
+//! }"#;
+//! let mut client = TcpStream::connect(addr)?;
+//! write!(
+//!     client,
+//!     "POST /webhook HTTP/1.1\r\n\
+//!      Content-Type: application/json\r\n\
+//!      X-Misskey-Hook-Secret: secret\r\n\
+//!      Content-Length: {}\r\n\r\n{}",
+//!     body.len(),
+//!     body
+//! )?;
+//!
+//! let (stream, _) = listener.accept()?;
+//! let mut reader = BufReader::new(stream);
+//! let mut headers = Vec::new();
+//! let mut content_length = 0;
+//! loop {
+//!     let mut line = String::new();
+//!     reader.read_line(&mut line)?;
+//!     let line = line.trim_end();
+//!     if line.is_empty() {
+//!         break;
+//!     }
+//!     if let Some((name, value)) = line.split_once(": ") {
+//!         if name.eq_ignore_ascii_case("content-length") {
+//!             content_length = value.parse()?;
+//!         }
+//!         headers.push((name.to_owned(), value.to_owned()));
+//!     }
+//! }
+//! let mut body = vec![0; content_length];
+//! reader.read_exact(&mut body)?;
+//!
+//! let receiver = WebhookReceiver::new("secret");
+//! let payload = receiver.receive_request(headers, &body)?;
+//! reader.get_mut().write_all(b"HTTP/1.1 204 No Content\r\n\r\n")?;
+//!
+//! match payload.event {
+//!     WebhookEvent::Followed { user } => assert_eq!(user.username, "alice"),
+//!     _ => unreachable!(),
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::fmt::{self, Display};
+
+pub use misskey_api::model::webhook::{WebhookEvent, WebhookPayload};
+
+/// Name of the header that contains the secret of the webhook.
+pub const SECRET_HEADER: &str = "X-Misskey-Hook-Secret";
+
+/// Possible errors from [`WebhookReceiver`].
+#[derive(Debug)]
+pub enum ReceiveError {
+    /// The request does not have the [`X-Misskey-Hook-Secret`][SECRET_HEADER] header.
+    MissingSecret,
+    /// The secret in the request does not match the expected one.
+    InvalidSecret,
+    /// The request body could not be parsed as a payload.
+    Payload(serde_json::Error),
+}
+
+impl std::error::Error for ReceiveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReceiveError::Payload(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ReceiveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReceiveError::MissingSecret => write!(f, "webhook secret is missing"),
+            ReceiveError::InvalidSecret => write!(f, "webhook secret does not match"),
+            ReceiveError::Payload(err) => write!(f, "invalid webhook payload: {}", err),
+        }
+    }
+}
+
+/// Verifier and parser of the requests sent from a webhook.
+///
+/// See the [module-level documentation][self] for an example.
+#[derive(Clone)]
+pub struct WebhookReceiver {
+    secret: String,
+}
+
+impl fmt::Debug for WebhookReceiver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WebhookReceiver").finish_non_exhaustive()
+    }
+}
+
+impl WebhookReceiver {
+    /// Creates a receiver that accepts requests with the given secret.
+    pub fn new(secret: impl Into<String>) -> Self {
+        WebhookReceiver {
+            secret: secret.into(),
+        }
+    }
+
+    /// Checks the value of the [`X-Misskey-Hook-Secret`][SECRET_HEADER] header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use misskey_util::webhook::{ReceiveError, WebhookReceiver};
+    ///
+    /// let receiver = WebhookReceiver::new("secret");
+    /// assert!(receiver.verify(Some("secret")).is_ok());
+    /// assert!(matches!(receiver.verify(Some("wrong")), Err(ReceiveError::InvalidSecret)));
+    /// assert!(matches!(receiver.verify(None), Err(ReceiveError::MissingSecret)));
+    /// ```
+    pub fn verify(&self, secret: Option<&str>) -> Result<(), ReceiveError> {
+        let secret = secret.ok_or(ReceiveError::MissingSecret)?;
+        if constant_time_eq(secret.as_bytes(), self.secret.as_bytes()) {
+            Ok(())
+        } else {
+            Err(ReceiveError::InvalidSecret)
+        }
+    }
+
+    /// Checks the secret and parses the request body.
+    ///
+    /// `secret` is the value of the [`X-Misskey-Hook-Secret`][SECRET_HEADER] header.
+    pub fn receive(
+        &self,
+        secret: Option<&str>,
+        body: &[u8],
+    ) -> Result<WebhookPayload, ReceiveError> {
+        self.verify(secret)?;
+        serde_json::from_slice(body).map_err(ReceiveError::Payload)
+    }
+
+    /// Checks the secret in the request headers and parses the request body.
+    ///
+    /// Header names are compared case-insensitively.
+    pub fn receive_request<K, V>(
+        &self,
+        headers: impl IntoIterator<Item = (K, V)>,
+        body: &[u8],
+    ) -> Result<WebhookPayload, ReceiveError>
+    where
+        K: AsRef<str>,
+        V: AsRef<[u8]>,
+    {
+        let secret = headers
+            .into_iter()
+            .find(|(name, _)| name.as_ref().eq_ignore_ascii_case(SECRET_HEADER))
+            .map(|(_, value)| value);
+        let secret = match &secret {
+            Some(value) => {
+                Some(std::str::from_utf8(value.as_ref()).map_err(|_| ReceiveError::InvalidSecret)?)
+            }
+            None => None,
+        };
+        self.receive(secret, body)
+    }
+}
+
+/// Compares the byte strings in time independent of where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
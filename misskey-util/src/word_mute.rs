@@ -0,0 +1,390 @@
+use misskey_api::model::{id::Id, note::Note, query::Query, user::User};
+use regex::{Regex, RegexBuilder};
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Keyword(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    /// Creates a matcher for the word that is alone in its conjunction, which can be a regex.
+    fn single(word: String) -> Option<Matcher> {
+        if let Some(regex) = parse_regex(&word) {
+            return regex.map(Matcher::Regex);
+        }
+        Some(Matcher::Keyword(word))
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Matcher::Keyword(keyword) => text.contains(keyword.as_str()),
+            Matcher::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// Parses the word in the form of `/pattern/flags`.
+///
+/// Returns `None` if `word` is not in that form, and `Some(None)` if the pattern is invalid.
+fn parse_regex(word: &str) -> Option<Option<Regex>> {
+    let rest = word.strip_prefix('/')?;
+    let (pattern, flags) = rest.rsplit_once('/')?;
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let mut builder = RegexBuilder::new(pattern);
+    for flag in flags.chars() {
+        match flag {
+            'i' => builder.case_insensitive(true),
+            'm' => builder.multi_line(true),
+            's' => builder.dot_matches_new_line(true),
+            // `g`, `y` and `u` do not affect whether the text matches
+            _ => continue,
+        };
+    }
+    Some(builder.build().ok())
+}
+
+/// Client-side filter of notes by muted words.
+///
+/// Misskey hides notes that contain the words muted by the user only in the web client,
+/// so notes obtained through the API (e.g. streaming timelines) are not filtered.
+/// This applies the same rules to the notes you received.
+///
+/// `muted_words` is a [`Query`], namely disjunction of conjunctions of the words.
+/// A note is muted if its text or content warning contains all the words in any of the conjunctions.
+/// Empty words are ignored. As Misskey does for the words not in conjunctions, a word alone in its
+/// conjunction is treated as a regular expression if it is in the form of `/pattern/flags`,
+/// and the invalid ones never match.
+///
+/// # Examples
+///
+/// ```
+/// use misskey_api::model::query::Query;
+/// use misskey_util::WordMuteFilter;
+///
+/// let filter = WordMuteFilter::new(Query::atom("spoiler").or(Query::atom("/^ad:/i")));
+/// assert!(filter.is_match("no spoilers please"));
+/// assert!(filter.is_match("AD: buy now"));
+/// assert!(!filter.is_match("hello"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WordMuteFilter {
+    words: Vec<Vec<Matcher>>,
+    owner_id: Option<Id<User>>,
+}
+
+impl WordMuteFilter {
+    /// Creates a filter from the muted words.
+    pub fn new(muted_words: Query<String>) -> Self {
+        let words = muted_words
+            .into_vec()
+            .into_iter()
+            .filter_map(|mut and| {
+                if and.len() == 1 {
+                    let word = and.pop().unwrap();
+                    if word.is_empty() {
+                        return None;
+                    }
+                    return Matcher::single(word).map(|matcher| vec![matcher]);
+                }
+                let and: Vec<_> = and
+                    .into_iter()
+                    .filter(|word| !word.is_empty())
+                    .map(Matcher::Keyword)
+                    .collect();
+                if and.is_empty() {
+                    None
+                } else {
+                    Some(and)
+                }
+            })
+            .collect();
+        WordMuteFilter {
+            words,
+            owner_id: None,
+        }
+    }
+
+    /// Creates a filter from the muted words of the user.
+    ///
+    /// `user` is expected to be obtained from [`me`][`crate::ClientExt::me`]
+    /// because muted words are not included in other users. Notes by `user` are never muted.
+    pub fn for_user(user: &User) -> Self {
+        let muted_words = user.muted_words.clone().unwrap_or_default();
+        WordMuteFilter {
+            owner_id: Some(user.id),
+            ..WordMuteFilter::new(muted_words)
+        }
+    }
+
+    /// Returns `true` if no words are muted.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns `true` if the text contains the muted words.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.words
+            .iter()
+            .any(|and| and.iter().all(|matcher| matcher.is_match(text)))
+    }
+
+    /// Returns `true` if the note should be hidden.
+    ///
+    /// For renotes without text, the renoted note is checked instead.
+    pub fn is_muted(&self, note: &Note) -> bool {
+        let note = match &note.renote {
+            Some(renote) if note.text.is_none() => renote,
+            _ => note,
+        };
+        if self.owner_id == Some(note.user_id) {
+            return false;
+        }
+
+        let text = match (&note.cw, &note.text) {
+            (Some(cw), Some(text)) => format!("{}\n{}", cw, text),
+            (Some(cw), None) => cw.clone(),
+            (None, Some(text)) => text.clone(),
+            (None, None) => return false,
+        };
+        self.is_match(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_regex, WordMuteFilter};
+
+    use misskey_api::model::query::Query;
+
+    fn muting(words: Vec<Vec<&str>>) -> WordMuteFilter {
+        let words = words
+            .into_iter()
+            .map(|and| and.into_iter().map(ToOwned::to_owned).collect())
+            .collect();
+        WordMuteFilter::new(Query::from_vec(words))
+    }
+
+    #[test]
+    fn keyword() {
+        let filter = muting(vec![vec!["spoiler"]]);
+        assert!(filter.is_match("no spoilers please"));
+        assert!(!filter.is_match("no SPOILERS please"));
+        assert!(!filter.is_match("hello"));
+    }
+
+    #[test]
+    fn invalid_regex_never_matches() {
+        assert!(matches!(parse_regex("/(/"), Some(None)));
+
+        let filter = muting(vec![vec!["/(/"]]);
+        assert!(!filter.is_match("("));
+        assert!(!filter.is_match("/(/"));
+
+        // the other conjunctions still work
+        let filter = muting(vec![vec!["/(/"], vec!["world"]]);
+        assert!(!filter.is_match("("));
+        assert!(filter.is_match("world"));
+    }
+
+    #[test]
+    fn regex_flags() {
+        let filter = muting(vec![vec!["/^ad:/"]]);
+        assert!(filter.is_match("ad: buy now"));
+        assert!(!filter.is_match("AD: buy now"));
+        assert!(!filter.is_match("hello\nad: buy now"));
+
+        let filter = muting(vec![vec!["/^ad:/i"]]);
+        assert!(filter.is_match("AD: buy now"));
+
+        let filter = muting(vec![vec!["/^ad:/m"]]);
+        assert!(filter.is_match("hello\nad: buy now"));
+
+        let filter = muting(vec![vec!["/buy.now/"]]);
+        assert!(!filter.is_match("buy\nnow"));
+        let filter = muting(vec![vec!["/buy.now/s"]]);
+        assert!(filter.is_match("buy\nnow"));
+
+        // flags that do not affect matching are ignored
+        let filter = muting(vec![vec!["/^ad:/gimsuy"]]);
+        assert!(filter.is_match("hello\nAD: buy now"));
+    }
+
+    #[test]
+    fn slash_in_regex() {
+        let filter = muting(vec![vec!["/https?://example\\.com/"]]);
+        assert!(filter.is_match("see https://example.com/notes"));
+        assert!(!filter.is_match("see example.com"));
+
+        let filter = muting(vec![vec!["/a/b/i"]]);
+        assert!(filter.is_match("A/B"));
+    }
+
+    #[test]
+    fn empty_regex_is_keyword() {
+        assert!(parse_regex("//").is_none());
+        assert!(parse_regex("//i").is_none());
+        assert!(parse_regex("/unclosed").is_none());
+
+        let filter = muting(vec![vec!["//"]]);
+        assert!(filter.is_match("https://example.com"));
+        assert!(!filter.is_match("hello"));
+    }
+
+    #[test]
+    fn conjunction() {
+        let filter = muting(vec![vec!["foo", "bar"], vec!["qux"]]);
+        assert!(filter.is_match("foo bar"));
+        assert!(filter.is_match("barfoo"));
+        assert!(!filter.is_match("foo"));
+        assert!(!filter.is_match("bar"));
+        assert!(filter.is_match("qux"));
+    }
+
+    #[test]
+    fn regex_in_conjunction_is_keyword() {
+        let filter = muting(vec![vec!["foo", "/ba[rz]/"]]);
+        assert!(!filter.is_match("foo bar"));
+        assert!(filter.is_match("foo /ba[rz]/"));
+
+        // nor is a word left alone after removing empty words
+        let filter = muting(vec![vec!["", "/ba[rz]/"]]);
+        assert!(!filter.is_match("bar"));
+        assert!(filter.is_match("/ba[rz]/"));
+    }
+
+    #[test]
+    fn empty_words_are_ignored() {
+        let filter = muting(vec![vec![""], vec!["", ""]]);
+        assert!(filter.is_empty());
+        assert!(!filter.is_match("hello"));
+
+        let filter = muting(vec![vec!["", "foo"]]);
+        assert!(filter.is_match("foo"));
+        assert!(!filter.is_match("hello"));
+    }
+
+    #[test]
+    fn empty_filter() {
+        let filter = muting(vec![vec![]]);
+        assert!(filter.is_empty());
+        assert!(!filter.is_match("hello"));
+    }
+
+    // the IDs in the notes are in the aid format
+    #[cfg(feature = "aid")]
+    mod note {
+        use super::muting;
+        use crate::WordMuteFilter;
+
+        use misskey_api::model::{note::Note, user::User};
+        use serde_json::{json, Value};
+
+        const OWNER_ID: &str = "8dhemt9ubf";
+        const OTHER_ID: &str = "8dhe5zqidm";
+
+        fn user(id: &str) -> Value {
+            json!({
+                "id": id,
+                "username": "user",
+                "name": null,
+                "avatarUrl": null,
+                "avatarBlurhash": null,
+                "avatarColor": null,
+                "bannerColor": null,
+                "emojis": [],
+                "host": null,
+                "mutedWords": [["spoiler"]],
+            })
+        }
+
+        fn note_json(
+            user_id: &str,
+            cw: Option<&str>,
+            text: Option<&str>,
+            renote: Option<Value>,
+        ) -> Value {
+            json!({
+                "id": "8ejiidh50m",
+                "createdAt": "2020-01-01T00:00:00.000Z",
+                "text": text,
+                "cw": cw,
+                "userId": user_id,
+                "user": user(user_id),
+                "renote": renote,
+                "visibility": "public",
+                "fileIds": [],
+                "files": [],
+                "reactions": {},
+                "emojis": [],
+                "renoteCount": 0,
+                "repliesCount": 0,
+            })
+        }
+
+        fn parse(note: Value) -> Note {
+            serde_json::from_value(note).unwrap()
+        }
+
+        #[test]
+        fn cw_and_text() {
+            let filter = muting(vec![vec!["spoiler", "ending"]]);
+            let note = parse(note_json(
+                OTHER_ID,
+                Some("spoiler"),
+                Some("the ending"),
+                None,
+            ));
+            assert!(filter.is_muted(&note));
+
+            let note = parse(note_json(OTHER_ID, Some("spoiler"), None, None));
+            assert!(!filter.is_muted(&note));
+
+            // the CW and the text are joined with a newline
+            let filter = muting(vec![vec!["/spoiler$/m"]]);
+            let note = parse(note_json(
+                OTHER_ID,
+                Some("spoiler"),
+                Some("the ending"),
+                None,
+            ));
+            assert!(filter.is_muted(&note));
+            let filter = muting(vec![vec!["spoilerthe"]]);
+            assert!(!filter.is_muted(&note));
+        }
+
+        #[test]
+        fn renote_without_text() {
+            let filter = muting(vec![vec!["spoiler"]]);
+            let renoted = note_json(OTHER_ID, None, Some("spoiler"), None);
+
+            let renote = parse(note_json(OTHER_ID, None, None, Some(renoted.clone())));
+            assert!(filter.is_muted(&renote));
+
+            // quotes are checked by their own text
+            let quote = parse(note_json(OTHER_ID, None, Some("look"), Some(renoted)));
+            assert!(!filter.is_muted(&quote));
+        }
+
+        #[test]
+        fn own_notes_are_not_muted() {
+            let owner: User = serde_json::from_value(user(OWNER_ID)).unwrap();
+            let filter = WordMuteFilter::for_user(&owner);
+            assert!(!filter.is_empty());
+
+            let own = parse(note_json(OWNER_ID, None, Some("spoiler"), None));
+            assert!(!filter.is_muted(&own));
+
+            let other = parse(note_json(OTHER_ID, None, Some("spoiler"), None));
+            assert!(filter.is_muted(&other));
+
+            // renotes of own notes are not muted either
+            let renoted = note_json(OWNER_ID, None, Some("spoiler"), None);
+            let renote = parse(note_json(OTHER_ID, None, None, Some(renoted)));
+            assert!(!filter.is_muted(&renote));
+        }
+    }
+}
@@ -1,40 +1,62 @@
+use std::collections::VecDeque;
 use std::fmt::{self, Debug};
+use std::future::Future;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::channel::{connect_websocket, TrySendError, WebSocketReceiver};
+use crate::channel::{connect_websocket, TrySendError, WebSocketReceiver, WebSocketSender};
+use crate::deflate::DeflateConfig;
 use crate::error::{Error, Result};
 use crate::model::outgoing::OutgoingMessage;
+use crate::record::SharedRecorder;
+use crate::runtime::SharedRuntime;
+use crate::stats::Stats;
 
-#[cfg(feature = "async-std-runtime")]
-use async_std::task;
-#[cfg(feature = "async-std-runtime")]
-use async_std::task::sleep;
 use async_tungstenite::tungstenite::http::HeaderMap;
 use async_tungstenite::tungstenite::Error as WsError;
-use futures_util::stream::StreamExt;
+use futures_util::{
+    sink::SinkExt,
+    stream::{FusedStream, StreamExt},
+};
 use log::{info, warn};
-#[cfg(feature = "tokio-runtime")]
-use tokio::task;
-#[cfg(feature = "tokio-runtime")]
-use tokio::time::sleep;
 use url::Url;
 
 pub mod channel;
 pub mod handler;
 pub mod model;
 
-use channel::{control_channel, ControlReceiver, ControlSender};
+use channel::{control_channel, CloseSender, ControlReceiver, ControlSender};
 use handler::Handler;
-use model::SharedBrokerState;
+use model::{BrokerControl, SharedBrokerState};
+
+/// How long to wait for the server to reply to the close frame on closing an idle connection.
+const IDLE_CLOSE_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug)]
 pub(crate) struct Broker {
     broker_rx: ControlReceiver,
     handler: Handler,
     reconnect: ReconnectConfig,
+    idle_timeout: Option<Duration>,
+    deflate: Option<DeflateConfig>,
+    recorder: Option<SharedRecorder>,
+    runtime: SharedRuntime,
+    /// the number of times the connection has been established
+    connections: u64,
     url: Url,
     additional_headers: HeaderMap,
+    state: SharedBrokerState,
+    close: Option<CloseRequest>,
+    /// controls received while disconnected, which are processed after reconnecting
+    deferred: VecDeque<BrokerControl>,
+}
+
+#[derive(Debug)]
+struct CloseRequest {
+    /// computed when the close is requested, so that the time spent before closing counts
+    deadline: Option<Instant>,
+    // these are dropped after the broker exited, which notifies the completion of closing
+    senders: Vec<CloseSender>,
 }
 
 /// Specifies the condition for reconnecting.
@@ -179,24 +201,100 @@ impl Default for ReconnectConfig {
     }
 }
 
+/// Specifies what to do when the buffer of a stream is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Waits until the stream consumes a buffered message.
+    ///
+    /// Note that this blocks the whole connection, i.e. messages for the other streams and the
+    /// responses to API requests are not delivered until then.
+    Block,
+    /// Drops the oldest message in the buffer to make room for the new one.
+    DropOldest,
+    /// Drops the new message.
+    DropNewest,
+    /// Drops the new message, and yields [`Error::Lagged`] with the number of the dropped
+    /// messages from the stream at the point where they are lost.
+    Lag,
+}
+
+/// Buffer configuration of the streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BufferConfig {
+    /// The maximum number of messages buffered for each stream, or `None` for the unbounded buffer.
+    pub capacity: Option<usize>,
+    /// Specifies what to do when the buffer is full.
+    pub overflow: OverflowPolicy,
+}
+
+impl BufferConfig {
+    /// Creates a `BufferConfig` with the unbounded buffer.
+    pub fn unbounded() -> BufferConfig {
+        BufferConfig {
+            capacity: None,
+            overflow: OverflowPolicy::Block,
+        }
+    }
+
+    /// Creates a `BufferConfig` with the buffer of the given capacity and overflow policy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn bounded(capacity: usize, overflow: OverflowPolicy) -> BufferConfig {
+        assert!(capacity > 0, "buffer capacity must be greater than zero");
+        BufferConfig {
+            capacity: Some(capacity),
+            overflow,
+        }
+    }
+}
+
+impl Default for BufferConfig {
+    /// The buffer is unbounded by default.
+    fn default() -> BufferConfig {
+        BufferConfig::unbounded()
+    }
+}
+
+/// Configuration of the broker.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BrokerConfig {
+    pub reconnect: ReconnectConfig,
+    pub share_channels: bool,
+    pub idle_timeout: Option<Duration>,
+    pub deflate: Option<DeflateConfig>,
+    pub recorder: Option<SharedRecorder>,
+}
+
 impl Broker {
     pub async fn spawn(
         url: Url,
         additional_headers: HeaderMap,
-        reconnect: ReconnectConfig,
+        config: BrokerConfig,
+        runtime: SharedRuntime,
     ) -> Result<(ControlSender, SharedBrokerState)> {
         let state = SharedBrokerState::working();
         let shared_state = SharedBrokerState::clone(&state);
 
         let (broker_tx, broker_rx) = control_channel(SharedBrokerState::clone(&state));
 
-        task::spawn(async move {
+        let task_runtime = SharedRuntime::clone(&runtime);
+        task_runtime.spawn(Box::pin(async move {
             let mut broker = Broker {
                 url,
                 additional_headers,
                 broker_rx,
-                reconnect,
-                handler: Handler::new(),
+                reconnect: config.reconnect,
+                idle_timeout: config.idle_timeout,
+                deflate: config.deflate,
+                recorder: config.recorder,
+                connections: 0,
+                runtime,
+                handler: Handler::new(config.share_channels),
+                state: SharedBrokerState::clone(&state),
+                close: None,
+                deferred: VecDeque::new(),
             };
 
             if let Some(err) = broker.run().await {
@@ -209,7 +307,7 @@ impl Broker {
             // is dropped after `state` is surely set to `Dead` or `Exited`, thus asserts that the
             // state must be set to `Dead` or `Exited` when these channels are found out to be closed.
             std::mem::drop(broker);
-        });
+        }));
 
         Ok((broker_tx, shared_state))
     }
@@ -228,6 +326,11 @@ impl Broker {
 
             info!("broker: task exited with error: {:?}", err.error);
 
+            if self.close.is_some() {
+                warn!("broker: died with error while closing");
+                return Some(err.error);
+            }
+
             if !self.reconnect.condition.should_reconnect(&err.error) {
                 warn!("broker: died with error");
                 return Some(err.error);
@@ -241,7 +344,55 @@ impl Broker {
                 "broker: attempt to reconnect in {:?}",
                 self.reconnect.interval
             );
-            sleep(self.reconnect.interval).await;
+            if self.wait_reconnect().await {
+                info!("broker: close requested while disconnected, exiting");
+                return None;
+            }
+        }
+    }
+
+    /// Waits for the reconnection interval while receiving controls,
+    /// and returns `true` if closing is requested meanwhile.
+    async fn wait_reconnect(&mut self) -> bool {
+        use futures_util::future::{self, Either};
+
+        let mut sleep = self.runtime.sleep(self.reconnect.interval);
+        loop {
+            if self.broker_rx.is_terminated() {
+                sleep.await;
+                return false;
+            }
+
+            match future::select(&mut sleep, self.broker_rx.next()).await {
+                Either::Left(((), _)) => return false,
+                Either::Right((Some(BrokerControl::Close { timeout, sender }), _)) => {
+                    // there is nothing to clean up without the connection
+                    self.request_close(timeout, sender);
+                    return true;
+                }
+                Either::Right((Some(BrokerControl::Stats { sender }), _)) => {
+                    sender.send_if_alive(self.stats());
+                }
+                Either::Right((Some(ctrl), _)) => self.deferred.push_back(ctrl),
+                Either::Right((None, _)) => {}
+            }
+        }
+    }
+
+    fn request_close(&mut self, timeout: Option<Duration>, sender: CloseSender) {
+        self.close
+            .get_or_insert_with(|| CloseRequest {
+                deadline: timeout.map(|timeout| Instant::now() + timeout),
+                senders: Vec::new(),
+            })
+            .senders
+            .push(sender);
+    }
+
+    fn stats(&self) -> Stats {
+        Stats {
+            reconnects: self.connections.saturating_sub(1),
+            ..self.handler.stats()
         }
     }
 
@@ -259,25 +410,95 @@ impl Broker {
         Ok(())
     }
 
+    async fn close(
+        &mut self,
+        websocket_tx: &mut WebSocketSender,
+        websocket_rx: &mut WebSocketReceiver,
+    ) -> Result<()> {
+        let deadline = self.close.as_ref().and_then(|req| req.deadline);
+
+        // from now on, streams are terminated as soon as their senders are dropped
+        self.state.set_closing().await;
+
+        for message in self.handler.unsubscribe_all() {
+            websocket_tx.send(&message).await?;
+        }
+
+        let runtime = SharedRuntime::clone(&self.runtime);
+        if with_deadline(&runtime, deadline, self.clean_handler(websocket_rx))
+            .await
+            .transpose()?
+            .is_none()
+        {
+            warn!(
+                "broker: gave up waiting for {} pending API responses",
+                self.handler.pending_requests()
+            );
+        }
+
+        // send a close frame and wait for the server to reply to it
+        websocket_tx.close().await?;
+        with_deadline(&runtime, deadline, async {
+            while let Some(Ok(_)) = websocket_rx.next().await {}
+        })
+        .await;
+
+        Ok(())
+    }
+
+    async fn control(
+        &mut self,
+        ctrl: BrokerControl,
+        websocket_tx: &mut WebSocketSender,
+    ) -> std::result::Result<(), TrySendError> {
+        #[cfg(feature = "inspect-contents")]
+        log::debug!("broker: received control {:?}", ctrl);
+
+        if let BrokerControl::Close { timeout, sender } = ctrl {
+            self.request_close(timeout, sender);
+            return Ok(());
+        }
+
+        if let BrokerControl::Stats { sender } = ctrl {
+            // the caller may have stopped waiting for the stats
+            sender.send_if_alive(self.stats());
+            return Ok(());
+        }
+
+        if let Some(out) = self.handler.control(ctrl) {
+            websocket_tx.try_send(out).await?
+        }
+
+        Ok(())
+    }
+
     async fn task(
         &mut self,
         remaining_message: Option<OutgoingMessage>,
     ) -> std::result::Result<(), TaskError> {
-        use futures_util::future::{self, Either};
-
-        let (mut websocket_tx, mut websocket_rx) =
-            match connect_websocket(self.url.clone(), self.additional_headers.clone()).await {
-                Ok(x) => x,
-                Err(error) => {
-                    // retain `remaining_message` because we've not sent it yet
-                    return Err(TaskError {
-                        remaining_message,
-                        error,
-                    });
-                }
-            };
+        use futures_util::future::{self, BoxFuture, Either};
+
+        let (mut websocket_tx, mut websocket_rx) = match connect_websocket(
+            &self.runtime,
+            self.url.clone(),
+            self.additional_headers.clone(),
+            self.deflate,
+            self.recorder.clone(),
+        )
+        .await
+        {
+            Ok(x) => x,
+            Err(error) => {
+                // retain `remaining_message` because we've not sent it yet
+                return Err(TaskError {
+                    remaining_message,
+                    error,
+                });
+            }
+        };
 
         info!("broker: started");
+        self.connections += 1;
 
         if let Some(message) = remaining_message {
             websocket_tx.try_send(message).await?;
@@ -287,42 +508,86 @@ impl Broker {
             websocket_tx.try_send(message).await?;
         }
 
+        while let Some(ctrl) = self.deferred.pop_front() {
+            self.control(ctrl, &mut websocket_tx).await?;
+        }
+
+        // fires when no subscriptions are left for `idle_timeout`
+        let mut idle: Option<BoxFuture<'static, ()>> = None;
+
         loop {
+            match self.idle_timeout {
+                Some(timeout) if self.handler.is_empty() => {
+                    idle.get_or_insert_with(|| self.runtime.sleep(timeout));
+                }
+                _ => idle = None,
+            }
+
             let t1 = websocket_rx.recv();
             let t2 = self.broker_rx.next();
+            let t3 = async {
+                match &mut idle {
+                    Some(idle) => idle.await,
+                    None => future::pending().await,
+                }
+            };
 
-            futures_util::pin_mut!(t1, t2);
+            futures_util::pin_mut!(t1, t2, t3);
 
-            match future::select(t1, t2).await {
-                Either::Left((msg, _)) => {
+            match future::select(future::select(t1, t2), t3).await {
+                Either::Right(((), _)) => {
+                    info!("broker: no subscriptions for a while, closing connection");
+                    self.close.get_or_insert_with(|| CloseRequest {
+                        deadline: Some(Instant::now() + IDLE_CLOSE_TIMEOUT),
+                        senders: Vec::new(),
+                    });
+                }
+                Either::Left((Either::Left((msg, _)), _)) => {
                     while let Some(ctrl) = self.broker_rx.try_recv() {
-                        #[cfg(feature = "inspect-contents")]
-                        log::debug!("broker: received control {:?}", ctrl);
-
-                        if let Some(out) = self.handler.control(ctrl) {
-                            websocket_tx.try_send(out).await?
-                        }
+                        self.control(ctrl, &mut websocket_tx).await?;
                     }
 
                     self.handler.handle(msg?).await?;
                 }
-                Either::Right((Some(ctrl), _)) => {
-                    #[cfg(feature = "inspect-contents")]
-                    log::debug!("broker: received control {:?}", ctrl);
-
-                    if let Some(out) = self.handler.control(ctrl) {
-                        websocket_tx.try_send(out).await?
-                    }
+                Either::Left((Either::Right((Some(ctrl), _)), _)) => {
+                    self.control(ctrl, &mut websocket_tx).await?;
                 }
-                Either::Right((None, _)) => {
+                Either::Left((Either::Right((None, _)), _)) => {
                     info!("broker: all controls terminated, exiting gracefully");
                     return Ok(self.clean_handler(&mut websocket_rx).await?);
                 }
             }
+
+            if self.close.is_some() {
+                info!("broker: close requested, closing connection");
+                return Ok(self.close(&mut websocket_tx, &mut websocket_rx).await?);
+            }
         }
     }
 }
 
+/// Waits for `future` until `deadline`, and returns `None` if it's elapsed.
+async fn with_deadline<F: Future>(
+    runtime: &SharedRuntime,
+    deadline: Option<Instant>,
+    future: F,
+) -> Option<F::Output> {
+    use futures_util::future::{self, Either};
+
+    let deadline = match deadline {
+        Some(deadline) => deadline,
+        None => return Some(future.await),
+    };
+
+    let timeout = runtime.sleep(deadline.saturating_duration_since(Instant::now()));
+    futures_util::pin_mut!(future, timeout);
+
+    match future::select(future, timeout).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(((), _)) => None,
+    }
+}
+
 #[derive(Debug, Clone)]
 struct TaskError {
     remaining_message: Option<OutgoingMessage>,
@@ -347,3 +612,78 @@ impl From<TrySendError> for TaskError {
         }
     }
 }
+
+#[cfg(all(test, feature = "tokio-runtime"))]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Broker, BrokerConfig, ReconnectCondition, ReconnectConfig};
+    use crate::broker::channel::{close_channel, response_channel};
+    use crate::broker::model::{BrokerControl, SharedBrokerState};
+    use crate::runtime::{Runtime, SharedRuntime, WebSocketConnection};
+
+    use async_tungstenite::tungstenite::{
+        handshake::client::Request, http::HeaderMap, Error as WsError,
+    };
+    use futures_util::future::BoxFuture;
+
+    /// Runtime that fails to connect forever.
+    struct UnreachableRuntime;
+
+    impl Runtime for UnreachableRuntime {
+        fn spawn(&self, future: BoxFuture<'static, ()>) {
+            tokio::task::spawn(future);
+        }
+
+        fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+            Box::pin(tokio::time::sleep(duration))
+        }
+
+        fn connect(&self, _: Request) -> BoxFuture<'static, Result<WebSocketConnection, WsError>> {
+            Box::pin(async { Err(WsError::ConnectionClosed) })
+        }
+    }
+
+    fn config() -> BrokerConfig {
+        BrokerConfig {
+            reconnect: ReconnectConfig {
+                interval: Duration::from_secs(3600),
+                condition: ReconnectCondition::always(),
+                retry_send: true,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn close_while_reconnecting() {
+        let url = "wss://example.com/streaming".parse().unwrap();
+        let runtime = SharedRuntime::new(UnreachableRuntime);
+        let (broker_tx, state) = Broker::spawn(url, HeaderMap::new(), config(), runtime)
+            .await
+            .unwrap();
+
+        let (stats_tx, stats_rx) = response_channel(SharedBrokerState::clone(&state));
+        broker_tx
+            .unbounded_send(BrokerControl::Stats { sender: stats_tx })
+            .unwrap();
+        let stats = tokio::time::timeout(Duration::from_secs(5), stats_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.reconnects, 0);
+
+        let (close_tx, close_rx) = close_channel(SharedBrokerState::clone(&state));
+        broker_tx
+            .unbounded_send(BrokerControl::Close {
+                timeout: None,
+                sender: close_tx,
+            })
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(5), close_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(state.read().await.is_closed());
+    }
+}
@@ -1,9 +1,11 @@
 mod channel_pong;
+mod close;
 mod control;
 mod response_oneshot;
 mod response_stream;
 
 pub(crate) use channel_pong::{channel_pong_channel, ChannelPongSender};
+pub(crate) use close::{close_channel, CloseSender};
 pub(crate) use control::{control_channel, ControlReceiver, ControlSender};
 pub(crate) use response_oneshot::{response_channel, ResponseSender};
 pub(crate) use response_stream::{
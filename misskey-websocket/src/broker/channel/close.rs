@@ -0,0 +1,39 @@
+use crate::broker::model::SharedBrokerState;
+use crate::error::Result;
+
+use futures_channel::oneshot::{self, Receiver, Sender};
+
+/// Sender that notifies the completion of closing by being dropped after the broker exited
+#[derive(Debug)]
+pub(crate) struct CloseSender(#[allow(dead_code)] Sender<()>);
+
+#[derive(Debug)]
+pub(crate) struct CloseReceiver {
+    inner: Receiver<()>,
+    state: SharedBrokerState,
+}
+
+impl CloseReceiver {
+    pub async fn recv(self) -> Result<()> {
+        // nothing is sent through this channel; the sender is just dropped after the broker exited
+        let _ = self.inner.await;
+        let state = self.state.read().await;
+        if state.is_closed() {
+            Ok(())
+        } else {
+            let err = state.dead().expect("close channel unexpectedly closed");
+            Err(err)
+        }
+    }
+}
+
+pub(crate) fn close_channel(state: SharedBrokerState) -> (CloseSender, CloseReceiver) {
+    let (sender, receiver) = oneshot::channel();
+    (
+        CloseSender(sender),
+        CloseReceiver {
+            inner: receiver,
+            state,
+        },
+    )
+}
@@ -12,6 +12,11 @@ impl<T> ResponseSender<T> {
             panic!("oneshot broker response channel unexpectedly closed");
         }
     }
+
+    /// Sends `t`, ignoring the receiver that has been dropped.
+    pub fn send_if_alive(self, t: T) {
+        let _ = self.0.send(t);
+    }
 }
 
 #[derive(Debug)]
@@ -1,28 +1,149 @@
+use std::collections::VecDeque;
 use std::fmt::{self, Debug};
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
 
 use crate::broker::model::{ReadBrokerState, SharedBrokerState};
-use crate::error::Result;
+use crate::broker::{BufferConfig, OverflowPolicy};
+use crate::error::{Error, Result};
 
-use futures_channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
-use futures_util::future::FutureExt;
-use futures_util::stream::{FusedStream, Stream, StreamExt};
+use futures_util::future::{self, FutureExt};
+use futures_util::stream::{FusedStream, Stream};
+
+enum Slot<T> {
+    Message(T),
+    /// number of messages dropped at this point
+    Lagged(u64),
+}
+
+struct Shared<T> {
+    buffer: VecDeque<Slot<T>>,
+    /// number of `Slot::Message` in `buffer`
+    len: usize,
+    config: BufferConfig,
+    is_sender_closed: bool,
+    is_receiver_closed: bool,
+    sender_waker: Option<Waker>,
+    receiver_waker: Option<Waker>,
+}
+
+impl<T> Shared<T> {
+    fn is_full(&self) -> bool {
+        match self.config.capacity {
+            Some(capacity) => self.len >= capacity,
+            None => false,
+        }
+    }
+
+    fn push(&mut self, t: T) {
+        self.buffer.push_back(Slot::Message(t));
+        self.len += 1;
+        if let Some(waker) = self.receiver_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn push_lagged(&mut self) {
+        if let Some(Slot::Lagged(n)) = self.buffer.back_mut() {
+            *n += 1;
+        } else {
+            self.buffer.push_back(Slot::Lagged(1));
+        }
+        if let Some(waker) = self.receiver_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn pop_oldest(&mut self) {
+        let mut lagged = 0;
+        while let Some(slot) = self.buffer.pop_front() {
+            match slot {
+                Slot::Lagged(n) => lagged += n,
+                Slot::Message(_) => {
+                    self.len -= 1;
+                    break;
+                }
+            }
+        }
+        // keep the lag count accrued before the dropped message
+        if lagged > 0 {
+            if let Some(Slot::Lagged(n)) = self.buffer.front_mut() {
+                *n += lagged;
+            } else {
+                self.buffer.push_front(Slot::Lagged(lagged));
+            }
+        }
+    }
+}
 
 /// Sender channel that broker uses to respond to the client
-#[derive(Debug, Clone)]
-pub(crate) struct ResponseStreamSender<T>(UnboundedSender<T>);
+pub(crate) struct ResponseStreamSender<T>(Arc<Mutex<Shared<T>>>);
+
+impl<T> Debug for ResponseStreamSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let shared = self.0.lock().unwrap();
+        f.debug_struct("ResponseStreamSender")
+            .field("len", &shared.len)
+            .field("config", &shared.config)
+            .finish()
+    }
+}
 
 impl<T> ResponseStreamSender<T> {
-    /// `Ok(())` when successfully sent, `Err(t)` when the channel is closed
-    pub fn try_send(&mut self, t: T) -> std::result::Result<(), T> {
-        self.0.unbounded_send(t).map_err(|e| e.into_inner())
+    /// `Ok(())` when successfully sent (or dropped as per the overflow policy),
+    /// `Err(t)` when the channel is closed
+    pub async fn send(&mut self, t: T) -> std::result::Result<(), T> {
+        let mut item = Some(t);
+        future::poll_fn(|cx| self.poll_send(cx, &mut item)).await
+    }
+
+    fn poll_send(
+        &self,
+        cx: &mut Context<'_>,
+        item: &mut Option<T>,
+    ) -> Poll<std::result::Result<(), T>> {
+        let mut shared = self.0.lock().unwrap();
+        let t = item.take().expect("polled after completion");
+
+        if shared.is_receiver_closed {
+            return Poll::Ready(Err(t));
+        }
+
+        if shared.is_full() {
+            match shared.config.overflow {
+                OverflowPolicy::Block => {
+                    shared.sender_waker = Some(cx.waker().clone());
+                    item.replace(t);
+                    return Poll::Pending;
+                }
+                OverflowPolicy::DropOldest => shared.pop_oldest(),
+                OverflowPolicy::DropNewest => return Poll::Ready(Ok(())),
+                OverflowPolicy::Lag => {
+                    shared.push_lagged();
+                    return Poll::Ready(Ok(()));
+                }
+            }
+        }
+
+        shared.push(t);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> Drop for ResponseStreamSender<T> {
+    fn drop(&mut self) {
+        let mut shared = self.0.lock().unwrap();
+        shared.is_sender_closed = true;
+        if let Some(waker) = shared.receiver_waker.take() {
+            waker.wake();
+        }
     }
 }
 
 /// Receiver channel that the client uses to receive the response from broker
 pub(crate) struct ResponseStreamReceiver<T> {
-    inner: UnboundedReceiver<T>,
+    inner: Arc<Mutex<Shared<T>>>,
     state: SharedBrokerState,
     is_terminated: bool,
     /// when `state_read_fut` is `Some(_)`, this stream is in terminating phase
@@ -38,6 +159,29 @@ impl<T> Debug for ResponseStreamReceiver<T> {
     }
 }
 
+impl<T> ResponseStreamReceiver<T> {
+    /// `None` when the sender is closed and there are no more messages
+    fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<Result<T>>> {
+        let mut shared = self.inner.lock().unwrap();
+
+        match shared.buffer.pop_front() {
+            Some(Slot::Message(t)) => {
+                shared.len -= 1;
+                if let Some(waker) = shared.sender_waker.take() {
+                    waker.wake();
+                }
+                Poll::Ready(Some(Ok(t)))
+            }
+            Some(Slot::Lagged(n)) => Poll::Ready(Some(Err(Error::Lagged(n)))),
+            None if shared.is_sender_closed => Poll::Ready(None),
+            None => {
+                shared.receiver_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
 impl<T> Stream for ResponseStreamReceiver<T> {
     type Item = Result<T>;
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<T>>> {
@@ -46,8 +190,8 @@ impl<T> Stream for ResponseStreamReceiver<T> {
         }
 
         if self.state_read_fut.is_none() {
-            if let Some(x) = futures_util::ready!(self.inner.poll_next_unpin(cx)) {
-                return Poll::Ready(Some(Ok(x)));
+            if let Some(x) = futures_util::ready!(self.poll_recv(cx)) {
+                return Poll::Ready(Some(x));
             }
         }
 
@@ -62,11 +206,16 @@ impl<T> Stream for ResponseStreamReceiver<T> {
         };
 
         let state = futures_util::ready!(fut.poll_unpin(cx));
+        self.is_terminated = true;
+
+        // streams are just terminated when the client is closed explicitly
+        if state.is_closed() {
+            return Poll::Ready(None);
+        }
+
         let err = state
             .dead()
             .expect("broker must be dead after poll_next returned None (ResponseStreamReceiver)");
-
-        self.is_terminated = true;
         Poll::Ready(Some(Err(err)))
     }
 }
@@ -77,17 +226,154 @@ impl<T> FusedStream for ResponseStreamReceiver<T> {
     }
 }
 
+impl<T> Drop for ResponseStreamReceiver<T> {
+    fn drop(&mut self) {
+        let mut shared = self.inner.lock().unwrap();
+        shared.is_receiver_closed = true;
+        shared.buffer.clear();
+        if let Some(waker) = shared.sender_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
 pub(crate) fn response_stream_channel<T>(
     state: SharedBrokerState,
+    config: BufferConfig,
 ) -> (ResponseStreamSender<T>, ResponseStreamReceiver<T>) {
-    let (sender, receiver) = mpsc::unbounded();
+    let shared = Arc::new(Mutex::new(Shared {
+        buffer: VecDeque::new(),
+        len: 0,
+        config,
+        is_sender_closed: false,
+        is_receiver_closed: false,
+        sender_waker: None,
+        receiver_waker: None,
+    }));
     (
-        ResponseStreamSender(sender),
+        ResponseStreamSender(Arc::clone(&shared)),
         ResponseStreamReceiver {
-            inner: receiver,
+            inner: shared,
             is_terminated: false,
             state_read_fut: None,
             state,
         },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{response_stream_channel, Slot};
+    use crate::broker::{model::SharedBrokerState, BufferConfig, OverflowPolicy};
+    use crate::error::Error;
+
+    use futures_util::{future::FutureExt, stream::StreamExt};
+
+    fn received<S: StreamExt + Unpin>(rx: &mut S) -> Vec<S::Item> {
+        std::iter::from_fn(|| rx.next().now_or_never().flatten()).collect()
+    }
+
+    #[test]
+    fn test_drop_oldest() {
+        let config = BufferConfig::bounded(2, OverflowPolicy::DropOldest);
+        let (mut tx, mut rx) = response_stream_channel(SharedBrokerState::working(), config);
+        for i in 0..4 {
+            tx.send(i).now_or_never().unwrap().unwrap();
+        }
+        let received: Vec<_> = received(&mut rx).into_iter().map(Result::unwrap).collect();
+        assert_eq!(received, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_drop_newest() {
+        let config = BufferConfig::bounded(2, OverflowPolicy::DropNewest);
+        let (mut tx, mut rx) = response_stream_channel(SharedBrokerState::working(), config);
+        for i in 0..4 {
+            tx.send(i).now_or_never().unwrap().unwrap();
+        }
+        let received: Vec<_> = received(&mut rx).into_iter().map(Result::unwrap).collect();
+        assert_eq!(received, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_lag() {
+        let config = BufferConfig::bounded(2, OverflowPolicy::Lag);
+        let (mut tx, mut rx) = response_stream_channel(SharedBrokerState::working(), config);
+        for i in 0..5 {
+            tx.send(i).now_or_never().unwrap().unwrap();
+        }
+        assert_eq!(rx.next().now_or_never().unwrap().unwrap().unwrap(), 0);
+        tx.send(5).now_or_never().unwrap().unwrap();
+
+        let received = received(&mut rx);
+        assert_eq!(received.len(), 3);
+        assert_eq!(*received[0].as_ref().unwrap(), 1);
+        assert!(matches!(received[1], Err(Error::Lagged(3))));
+        assert_eq!(*received[2].as_ref().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_lag_twice() {
+        let config = BufferConfig::bounded(2, OverflowPolicy::Lag);
+        let (mut tx, mut rx) = response_stream_channel(SharedBrokerState::working(), config);
+        for i in 0..4 {
+            tx.send(i).now_or_never().unwrap().unwrap();
+        }
+        assert_eq!(rx.next().now_or_never().unwrap().unwrap().unwrap(), 0);
+        for i in 4..6 {
+            tx.send(i).now_or_never().unwrap().unwrap();
+        }
+
+        let received = received(&mut rx);
+        assert_eq!(received.len(), 4);
+        assert_eq!(*received[0].as_ref().unwrap(), 1);
+        assert!(matches!(received[1], Err(Error::Lagged(2))));
+        assert_eq!(*received[2].as_ref().unwrap(), 4);
+        assert!(matches!(received[3], Err(Error::Lagged(1))));
+    }
+
+    #[test]
+    fn test_pop_oldest_keeps_lagged() {
+        let config = BufferConfig::bounded(2, OverflowPolicy::DropOldest);
+        let (tx, _rx) = response_stream_channel(SharedBrokerState::working(), config);
+        let mut shared = tx.0.lock().unwrap();
+        shared.buffer.extend(vec![
+            Slot::Lagged(2),
+            Slot::Message(0),
+            Slot::Lagged(1),
+            Slot::Message(1),
+        ]);
+        shared.len = 2;
+
+        shared.pop_oldest();
+        assert_eq!(shared.len, 1);
+        assert!(matches!(shared.buffer[0], Slot::Lagged(3)));
+        assert!(matches!(shared.buffer[1], Slot::Message(1)));
+
+        shared.pop_oldest();
+        assert_eq!(shared.len, 0);
+        assert_eq!(shared.buffer.len(), 1);
+        assert!(matches!(shared.buffer[0], Slot::Lagged(3)));
+    }
+
+    #[test]
+    fn test_block() {
+        let config = BufferConfig::bounded(1, OverflowPolicy::Block);
+        let (mut tx, mut rx) = response_stream_channel(SharedBrokerState::working(), config);
+        tx.send(0).now_or_never().unwrap().unwrap();
+
+        let mut send = Box::pin(tx.send(1));
+        assert!((&mut send).now_or_never().is_none());
+        assert_eq!(rx.next().now_or_never().unwrap().unwrap().unwrap(), 0);
+        assert!(send.now_or_never().unwrap().is_ok());
+        assert_eq!(rx.next().now_or_never().unwrap().unwrap().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_closed() {
+        let (mut tx, rx) =
+            response_stream_channel(SharedBrokerState::working(), BufferConfig::default());
+        std::mem::drop(rx);
+        assert_eq!(tx.send(0).now_or_never().unwrap(), Err(0));
+    }
+}
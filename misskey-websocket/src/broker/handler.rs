@@ -13,6 +13,7 @@ use crate::model::{
     outgoing::OutgoingMessage,
     ApiRequestId, ChannelId, SubNoteId,
 };
+use crate::stats::{BroadcastStats, ChannelStats, Stats, SubNoteStats};
 
 use log::{info, warn};
 use misskey_core::model::ApiResult;
@@ -28,30 +29,54 @@ struct ApiHandler {
 struct SubNoteHandler {
     message: OutgoingMessage,
     sender: ResponseStreamSender<Value>,
+    received: u64,
 }
 
 #[derive(Debug)]
-struct ChannelHandler {
-    message: OutgoingMessage,
+struct ChannelSubscriber {
     pong: Option<ChannelPongSender>,
     sender: ResponseStreamSender<Value>,
 }
 
+/// Handler for a connection to the channel, which is shared among `subscribers` when
+/// channel sharing is enabled.
+#[derive(Debug)]
+struct ChannelHandler {
+    message: OutgoingMessage,
+    connected: bool,
+    subscribers: HashMap<ChannelId, ChannelSubscriber>,
+    received: u64,
+}
+
+/// Channel name and serialized parameters, which identifies the connection to be shared.
+type ChannelKey = (&'static str, String);
+
 #[derive(Debug)]
 pub(crate) struct Handler {
     api: HashMap<ApiRequestId, ApiHandler>,
     sub_note: HashMap<SubNoteId, SubNoteHandler>,
+    /// keyed by the ID of the connection to the channel
     channel: HashMap<ChannelId, ChannelHandler>,
+    /// maps the ID of each subscriber to the ID of the connection it uses
+    channel_connection: HashMap<ChannelId, ChannelId>,
+    /// `Some` when channel sharing is enabled
+    shared_channel: Option<HashMap<ChannelKey, ChannelId>>,
     broadcast: HashMap<&'static str, HashMap<BroadcastId, ResponseStreamSender<Value>>>,
+    broadcast_received: HashMap<&'static str, u64>,
+    received: u64,
 }
 
 impl Handler {
-    pub fn new() -> Handler {
+    pub fn new(share_channels: bool) -> Handler {
         Handler {
             api: HashMap::new(),
             sub_note: HashMap::new(),
             channel: HashMap::new(),
+            channel_connection: HashMap::new(),
+            shared_channel: share_channels.then(HashMap::new),
             broadcast: HashMap::new(),
+            broadcast_received: HashMap::new(),
+            received: 0,
         }
     }
 
@@ -66,8 +91,13 @@ impl Handler {
             messages.push(message.clone());
         }
 
-        for ChannelHandler { message, .. } in self.channel.values() {
-            // TODO: Handle ping/pong in reconnect
+        for ChannelHandler {
+            message, connected, ..
+        } in self.channel.values_mut()
+        {
+            // subscribers joining before the server acknowledges the reconnection
+            // wait for the new `connected` message
+            *connected = false;
             messages.push(message.clone());
         }
 
@@ -97,32 +127,82 @@ impl Handler {
                 name,
                 pong,
             } => {
+                if let Some(shared) = &mut self.shared_channel {
+                    let key = (name, params.to_string());
+                    if let Some(&connection_id) = shared.get(&key) {
+                        let handler = self
+                            .channel
+                            .get_mut(&connection_id)
+                            .expect("shared channel must have its handler");
+                        // no `connected` message will come for the connection already connected
+                        let pong = if handler.connected {
+                            pong.send();
+                            None
+                        } else {
+                            Some(pong)
+                        };
+                        handler
+                            .subscribers
+                            .insert(id, ChannelSubscriber { pong, sender });
+                        self.channel_connection.insert(id, connection_id);
+                        return None;
+                    }
+                    shared.insert(key, id);
+                }
+
                 let message = OutgoingMessage::Connect {
                     channel: name,
                     id,
                     params,
                     pong: true,
                 };
+                let subscriber = ChannelSubscriber {
+                    pong: Some(pong),
+                    sender,
+                };
                 let handler = ChannelHandler {
                     message: message.clone(),
-                    sender,
-                    pong: Some(pong),
+                    connected: false,
+                    subscribers: std::iter::once((id, subscriber)).collect(),
+                    received: 0,
                 };
                 self.channel.insert(id, handler);
+                self.channel_connection.insert(id, id);
                 Some(message)
             }
             BrokerControl::Channel { id, message } => {
+                let id = self.channel_connection.get(&id).copied().unwrap_or(id);
                 Some(OutgoingMessage::Channel { id, message })
             }
             BrokerControl::Disconnect { id } => {
-                self.channel.remove(&id);
-                Some(OutgoingMessage::Disconnect { id })
+                let connection_id = match self.channel_connection.remove(&id) {
+                    Some(x) => x,
+                    None => {
+                        info!("disconnecting unknown channel {:?}, skipping", id);
+                        return None;
+                    }
+                };
+
+                if let Some(handler) = self.channel.get_mut(&connection_id) {
+                    handler.subscribers.remove(&id);
+                    if !handler.subscribers.is_empty() {
+                        return None;
+                    }
+                }
+
+                // disconnect when the last subscriber has gone
+                self.channel.remove(&connection_id);
+                if let Some(shared) = &mut self.shared_channel {
+                    shared.retain(|_, shared_id| *shared_id != connection_id);
+                }
+                Some(OutgoingMessage::Disconnect { id: connection_id })
             }
             BrokerControl::SubNote { id, sender } => {
                 let message = OutgoingMessage::SubNote { id: id.clone() };
                 let handler = SubNoteHandler {
                     message: message.clone(),
                     sender,
+                    received: 0,
                 };
                 self.sub_note.insert(id, handler);
                 Some(message)
@@ -136,6 +216,7 @@ impl Handler {
                     .entry(type_)
                     .or_insert_with(HashMap::new)
                     .insert(id, sender);
+                self.broadcast_received.entry(type_).or_insert(0);
                 None
             }
             BrokerControl::StopBroadcast { id } => {
@@ -146,6 +227,83 @@ impl Handler {
                 }
                 None
             }
+            BrokerControl::Close { .. } | BrokerControl::Stats { .. } => {
+                unreachable!("close and stats controls are handled by broker")
+            }
+        }
+    }
+
+    /// Removes all channels, subnotes and broadcasts, and returns messages to unsubscribe them.
+    pub fn unsubscribe_all(&mut self) -> Vec<OutgoingMessage> {
+        let mut messages = Vec::new();
+
+        for id in self.channel.drain().map(|(id, _)| id) {
+            messages.push(OutgoingMessage::Disconnect { id });
+        }
+        self.channel_connection.clear();
+        if let Some(shared) = &mut self.shared_channel {
+            shared.clear();
+        }
+
+        for id in self.sub_note.drain().map(|(id, _)| id) {
+            messages.push(OutgoingMessage::UnsubNote { id });
+        }
+
+        self.broadcast.clear();
+
+        messages
+    }
+
+    pub fn pending_requests(&self) -> usize {
+        self.api.len()
+    }
+
+    /// Returns the statistics of the subscriptions. `reconnects` is left to be filled by the broker.
+    pub fn stats(&self) -> Stats {
+        let channels = self
+            .channel
+            .values()
+            .filter_map(|handler| match &handler.message {
+                OutgoingMessage::Connect {
+                    channel, params, ..
+                } => Some(ChannelStats {
+                    name: channel,
+                    params: params.clone(),
+                    connected: handler.connected,
+                    subscribers: handler.subscribers.len(),
+                    received: handler.received,
+                }),
+                _ => None,
+            })
+            .collect();
+
+        let sub_notes = self
+            .sub_note
+            .iter()
+            .map(|(id, handler)| SubNoteStats {
+                note_id: id.0.clone(),
+                received: handler.received,
+            })
+            .collect();
+
+        let broadcasts = self
+            .broadcast
+            .iter()
+            .filter(|(_, senders)| !senders.is_empty())
+            .map(|(type_, senders)| BroadcastStats {
+                type_,
+                subscribers: senders.len(),
+                received: self.broadcast_received.get(type_).copied().unwrap_or(0),
+            })
+            .collect();
+
+        Stats {
+            channels,
+            sub_notes,
+            broadcasts,
+            pending_requests: self.api.len(),
+            received: self.received,
+            reconnects: 0,
         }
     }
 
@@ -157,6 +315,8 @@ impl Handler {
     }
 
     pub async fn handle(&mut self, msg: IncomingMessage) -> Result<()> {
+        self.received += 1;
+
         match msg.type_ {
             IncomingMessageType::Api(id) => {
                 if let Some(ApiHandler { sender, .. }) = self.api.remove(&id) {
@@ -170,7 +330,11 @@ impl Handler {
             IncomingMessageType::Channel => {
                 let ChannelMessage { id, message } = value::from_value(msg.body)?;
 
-                let ChannelHandler { sender, .. } = match self.channel.get_mut(&id) {
+                let ChannelHandler {
+                    subscribers,
+                    received,
+                    ..
+                } = match self.channel.get_mut(&id) {
                     Some(x) => x,
                     None => {
                         warn!("unhandled channel message with {:?}, skipping", id);
@@ -178,15 +342,29 @@ impl Handler {
                     }
                 };
 
-                if sender.try_send(message).is_err() {
-                    warn!("stale channel handler for {:?}, deleted", id);
-                    self.channel.remove(&id);
+                *received += 1;
+
+                let mut stale = Vec::new();
+                for (subscriber_id, ChannelSubscriber { sender, .. }) in subscribers.iter_mut() {
+                    if sender.send(message.clone()).await.is_err() {
+                        warn!("stale channel handler for {:?}, deleted", subscriber_id);
+                        stale.push(*subscriber_id);
+                    }
+                }
+
+                // the connection itself is removed on `Disconnect` control from the subscribers
+                for subscriber_id in stale {
+                    subscribers.remove(&subscriber_id);
                 }
             }
             IncomingMessageType::Connected => {
                 let ConnectedMessage { id } = value::from_value(msg.body)?;
 
-                let ChannelHandler { pong, .. } = match self.channel.get_mut(&id) {
+                let ChannelHandler {
+                    connected,
+                    subscribers,
+                    ..
+                } = match self.channel.get_mut(&id) {
                     Some(x) => x,
                     None => {
                         warn!("unhandled connected message with {:?}, skipping", id);
@@ -194,16 +372,21 @@ impl Handler {
                     }
                 };
 
-                if let Some(pong) = pong.take() {
-                    pong.send();
-                } else {
+                if *connected {
                     info!("duplicated connected message with {:?}, skipping", id);
                 }
+
+                *connected = true;
+                for pong in subscribers.values_mut().filter_map(|s| s.pong.take()) {
+                    pong.send();
+                }
             }
             IncomingMessageType::NoteUpdated => {
                 let NoteUpdatedMessage { id, message } = value::from_value(msg.body)?;
 
-                let SubNoteHandler { sender, .. } = match self.sub_note.get_mut(&id) {
+                let SubNoteHandler {
+                    sender, received, ..
+                } = match self.sub_note.get_mut(&id) {
                     Some(x) => x,
                     None => {
                         warn!("unhandled subnote message with {:?}, skipping", id);
@@ -211,7 +394,9 @@ impl Handler {
                     }
                 };
 
-                if sender.try_send(message).is_err() {
+                *received += 1;
+
+                if sender.send(message).await.is_err() {
                     warn!("stale subnote handler for {:?}, deleted", id);
                     self.sub_note.remove(&id);
                 }
@@ -227,15 +412,23 @@ impl Handler {
                     }
                 };
 
-                let body = msg.body;
-                senders.retain(|id, sender| {
-                    if sender.try_send(body.clone()).is_err() {
+                if !senders.is_empty() {
+                    if let Some(received) = self.broadcast_received.get_mut(type_.as_str()) {
+                        *received += 1;
+                    }
+                }
+
+                let mut stale = Vec::new();
+                for (id, sender) in senders.iter_mut() {
+                    if sender.send(msg.body.clone()).await.is_err() {
                         warn!("stale broadcast handler {}:{:?}, deleted", type_, id);
-                        false
-                    } else {
-                        true
+                        stale.push(*id);
                     }
-                });
+                }
+
+                for id in stale {
+                    senders.remove(&id);
+                }
             }
         }
 
@@ -2,10 +2,14 @@ use std::hash::Hash;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
-use crate::broker::channel::{ChannelPongSender, ResponseSender, ResponseStreamSender};
+use crate::broker::channel::{
+    ChannelPongSender, CloseSender, ResponseSender, ResponseStreamSender,
+};
 use crate::error::Error;
 use crate::model::{ApiRequestId, ChannelId, SubNoteId};
+use crate::stats::Stats;
 
 use async_rwlock::RwLock;
 use futures_util::future::{BoxFuture, Future, FutureExt};
@@ -59,12 +63,21 @@ pub(crate) enum BrokerControl {
     StopBroadcast {
         id: BroadcastId,
     },
+    Close {
+        timeout: Option<Duration>,
+        sender: CloseSender,
+    },
+    Stats {
+        sender: ResponseSender<Stats>,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum BrokerState {
     /// Broker is properly working and is available.
     Working,
+    /// Broker is closing the connection on request. (unavailable)
+    Closing,
     /// Broker is exited properly. (unavailable)
     Exited,
     /// Broker is exited with an error. (unavailable)
@@ -72,14 +85,20 @@ pub(crate) enum BrokerState {
 }
 
 impl BrokerState {
+    /// Returns the error that makes the broker unavailable, or `None` if it is working.
     pub fn dead(self) -> Option<Error> {
         match self {
             BrokerState::Working => None,
-            // TODO: clearify the guarantee that no one asks for `BrokerState` after broker is dead
-            BrokerState::Exited => panic!("asked if broker is dead while it is already exited"),
+            // the broker can only be exited while the client is alive when it is closed explicitly
+            // or due to the idle timeout
+            BrokerState::Closing | BrokerState::Exited => Some(Error::Closed),
             BrokerState::Dead(e) => Some(e),
         }
     }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(self, BrokerState::Closing | BrokerState::Exited)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -100,6 +119,11 @@ impl SharedBrokerState {
         SharedBrokerState(Arc::new(RwLock::new(BrokerState::Working)))
     }
 
+    pub async fn set_closing(&self) {
+        let mut lock = self.0.write().await;
+        *lock = BrokerState::Closing;
+    }
+
     pub async fn set_exited(&self) {
         let mut lock = self.0.write().await;
         *lock = BrokerState::Exited;
@@ -3,20 +3,18 @@ use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+use crate::deflate::DeflateConfig;
 use crate::error::{Error, Result};
 use crate::model::{incoming::IncomingMessage, outgoing::OutgoingMessage};
+use crate::record::{Direction, SharedRecorder};
+use crate::runtime::{SharedRuntime, WebSocketConnection};
 
-#[cfg(feature = "async-std-runtime")]
-use async_tungstenite::async_std::{connect_async, ConnectStream};
-#[cfg(feature = "tokio-runtime")]
-use async_tungstenite::tokio::{connect_async, ConnectStream};
 use async_tungstenite::tungstenite::{
     client::IntoClientRequest,
     error::{Error as WsError, Result as WsResult},
     http::header::HeaderMap,
     Message as WsMessage,
 };
-use async_tungstenite::WebSocketStream;
 use futures_util::{
     sink::{Sink, SinkExt},
     stream::{SplitSink, SplitStream, Stream, StreamExt, TryStreamExt},
@@ -26,7 +24,10 @@ use log::debug;
 use url::Url;
 
 /// Receiver channel that communicates with Misskey
-pub struct WebSocketReceiver(SplitStream<PingPongWebSocketStream<WebSocketStream<ConnectStream>>>);
+pub struct WebSocketReceiver {
+    stream: SplitStream<PingPongWebSocketStream<WebSocketConnection>>,
+    recorder: Option<SharedRecorder>,
+}
 
 impl fmt::Debug for WebSocketReceiver {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -38,7 +39,7 @@ impl Stream for WebSocketReceiver {
     type Item = Result<IncomingMessage>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
-        let text = match futures_util::ready!(self.0.poll_next_unpin(cx)?) {
+        let text = match futures_util::ready!(self.stream.poll_next_unpin(cx)?) {
             Some(WsMessage::Text(t)) => t,
             Some(WsMessage::Ping(_)) | Some(WsMessage::Pong(_)) => return self.poll_next(cx),
             None | Some(WsMessage::Close(_)) => return Poll::Ready(None),
@@ -48,6 +49,10 @@ impl Stream for WebSocketReceiver {
         #[cfg(feature = "inspect-contents")]
         debug!("received message: {}", text);
 
+        if let Some(recorder) = &self.recorder {
+            recorder.record(Direction::Incoming, &text);
+        }
+
         Poll::Ready(Some(Ok(serde_json::from_str(&text)?)))
     }
 }
@@ -75,9 +80,10 @@ impl WebSocketReceiver {
 }
 
 /// Sender channel that communicates with Misskey
-pub struct WebSocketSender(
-    SplitSink<PingPongWebSocketStream<WebSocketStream<ConnectStream>>, WsMessage>,
-);
+pub struct WebSocketSender {
+    sink: SplitSink<PingPongWebSocketStream<WebSocketConnection>, WsMessage>,
+    recorder: Option<SharedRecorder>,
+}
 
 #[derive(Debug, Clone)]
 pub struct TrySendError {
@@ -108,24 +114,30 @@ impl Sink<&'_ OutgoingMessage> for WebSocketSender {
     type Error = Error;
 
     fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
-        self.0.poll_ready_unpin(cx).map_err(Into::into)
+        self.sink.poll_ready_unpin(cx).map_err(Into::into)
     }
 
     fn start_send(mut self: Pin<&mut Self>, item: &OutgoingMessage) -> Result<()> {
-        let msg = WsMessage::Text(serde_json::to_string(item)?);
+        let text = serde_json::to_string(item)?;
+
+        if let Some(recorder) = &self.recorder {
+            recorder.record(Direction::Outgoing, &text);
+        }
+
+        let msg = WsMessage::Text(text);
 
         #[cfg(feature = "inspect-contents")]
         debug!("send message: {:?}", msg);
 
-        self.0.start_send_unpin(msg).map_err(Into::into)
+        self.sink.start_send_unpin(msg).map_err(Into::into)
     }
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
-        self.0.poll_flush_unpin(cx).map_err(Into::into)
+        self.sink.poll_flush_unpin(cx).map_err(Into::into)
     }
 
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
-        self.0.poll_close_unpin(cx).map_err(Into::into)
+        self.sink.poll_close_unpin(cx).map_err(Into::into)
     }
 }
 
@@ -214,12 +226,23 @@ where
 }
 
 pub async fn connect_websocket(
+    runtime: &SharedRuntime,
     url: Url,
     additional_headers: HeaderMap,
+    deflate: Option<DeflateConfig>,
+    recorder: Option<SharedRecorder>,
 ) -> Result<(WebSocketSender, WebSocketReceiver)> {
     let mut request = url.into_client_request()?;
     request.headers_mut().extend(additional_headers);
-    let (ws, _) = connect_async(request).await?;
+    let ws = match deflate {
+        Some(config) => runtime.connect_deflate(request, config).await?,
+        None => runtime.connect(request).await?,
+    };
     let (sink, stream) = PingPongWebSocketStream::new(ws).split();
-    Ok((WebSocketSender(sink), WebSocketReceiver(stream)))
+    let sender = WebSocketSender {
+        sink,
+        recorder: recorder.clone(),
+    };
+    let receiver = WebSocketReceiver { stream, recorder };
+    Ok((sender, receiver))
 }
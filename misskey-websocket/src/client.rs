@@ -1,12 +1,15 @@
 use std::fmt::{self, Debug};
+use std::time::Duration;
 
 use crate::broker::{
-    channel::{response_channel, ControlSender},
+    channel::{close_channel, response_channel, ControlSender},
     model::{BrokerControl, SharedBrokerState},
-    Broker, ReconnectConfig,
+    Broker, BrokerConfig, BufferConfig, ReconnectConfig,
 };
 use crate::error::{Error, Result};
 use crate::model::{ApiRequestId, SubNoteId};
+use crate::runtime::SharedRuntime;
+use crate::stats::Stats;
 
 use async_tungstenite::tungstenite::http::HeaderMap;
 use futures_util::{
@@ -40,6 +43,7 @@ use stream::{Broadcast, Channel, SubNote};
 pub struct WebSocketClient {
     broker_tx: ControlSender,
     state: SharedBrokerState,
+    pub(crate) buffer: BufferConfig,
 }
 
 impl Debug for WebSocketClient {
@@ -94,8 +98,26 @@ impl WebSocketClient {
         additional_headers: HeaderMap,
         reconnect_config: ReconnectConfig,
     ) -> Result<WebSocketClient> {
-        let (broker_tx, state) = Broker::spawn(url, additional_headers, reconnect_config).await?;
-        Ok(WebSocketClient { broker_tx, state })
+        let config = BrokerConfig {
+            reconnect: reconnect_config,
+            ..Default::default()
+        };
+        let runtime = SharedRuntime::from_features().ok_or(Error::NoRuntime)?;
+        WebSocketClient::connect_with_broker_config(url, additional_headers, config, runtime).await
+    }
+
+    pub(crate) async fn connect_with_broker_config(
+        url: Url,
+        additional_headers: HeaderMap,
+        config: BrokerConfig,
+        runtime: SharedRuntime,
+    ) -> Result<WebSocketClient> {
+        let (broker_tx, state) = Broker::spawn(url, additional_headers, config, runtime).await?;
+        Ok(WebSocketClient {
+            broker_tx,
+            state,
+            buffer: BufferConfig::default(),
+        })
     }
 
     /// Creates a new builder instance with `url`.
@@ -117,6 +139,22 @@ impl WebSocketClient {
     ///
     /// [stream]: futures_util::stream::Stream
     pub fn subnote<E, Id>(&self, note_id: Id) -> BoxFuture<'static, Result<SubNote<E>>>
+    where
+        E: misskey_core::streaming::SubNoteEvent,
+        Id: Into<String>,
+    {
+        self.subnote_with_buffer(note_id, self.buffer)
+    }
+
+    /// Captures the note specified by `id`, with the given buffer configuration.
+    ///
+    /// This is the same as [`subnote`][`WebSocketClient::subnote`] except that the returned
+    /// stream uses `buffer` instead of the one configured on the client.
+    pub fn subnote_with_buffer<E, Id>(
+        &self,
+        note_id: Id,
+        buffer: BufferConfig,
+    ) -> BoxFuture<'static, Result<SubNote<E>>>
     where
         E: misskey_core::streaming::SubNoteEvent,
         Id: Into<String>,
@@ -125,6 +163,7 @@ impl WebSocketClient {
             SubNoteId(note_id.into()),
             self.broker_tx.clone(),
             SharedBrokerState::clone(&self.state),
+            buffer,
         )
         .boxed()
     }
@@ -140,6 +179,21 @@ impl WebSocketClient {
         &self,
         request: R,
     ) -> BoxFuture<'static, Result<Channel<R::Incoming, R::Outgoing>>>
+    where
+        R: misskey_core::streaming::ConnectChannelRequest,
+    {
+        self.channel_with_buffer(request, self.buffer)
+    }
+
+    /// Connects to the channel using `request`, with the given buffer configuration.
+    ///
+    /// This is the same as [`channel`][`WebSocketClient::channel`] except that the returned
+    /// stream uses `buffer` instead of the one configured on the client.
+    pub fn channel_with_buffer<R>(
+        &self,
+        request: R,
+        buffer: BufferConfig,
+    ) -> BoxFuture<'static, Result<Channel<R::Incoming, R::Outgoing>>>
     where
         R: misskey_core::streaming::ConnectChannelRequest,
     {
@@ -147,6 +201,7 @@ impl WebSocketClient {
             request,
             self.broker_tx.clone(),
             SharedBrokerState::clone(&self.state),
+            buffer,
         )
     }
 
@@ -157,15 +212,93 @@ impl WebSocketClient {
     ///
     /// [stream]: futures_util::stream::Stream
     pub fn broadcast<E>(&self) -> BoxFuture<'static, Result<Broadcast<E>>>
+    where
+        E: misskey_core::streaming::BroadcastEvent,
+    {
+        self.broadcast_with_buffer(self.buffer)
+    }
+
+    /// Receive messages from the broadcast stream, with the given buffer configuration.
+    ///
+    /// This is the same as [`broadcast`][`WebSocketClient::broadcast`] except that the returned
+    /// stream uses `buffer` instead of the one configured on the client.
+    pub fn broadcast_with_buffer<E>(
+        &self,
+        buffer: BufferConfig,
+    ) -> BoxFuture<'static, Result<Broadcast<E>>>
     where
         E: misskey_core::streaming::BroadcastEvent,
     {
         Broadcast::start(
             self.broker_tx.clone(),
             SharedBrokerState::clone(&self.state),
+            buffer,
         )
         .boxed()
     }
+
+    /// Closes the connection gracefully.
+    ///
+    /// This disconnects from all channels and subnotes, waits for the responses to the pending API
+    /// requests, and then sends a close frame to the server. The returned future resolves once the
+    /// connection is closed and the background task has exited.
+    ///
+    /// After this call, streams obtained from this client are terminated, and any other
+    /// operation on this client (including its clones) fails with [`Error::Closed`].
+    /// Closing an already closed client simply succeeds.
+    pub async fn close(&self) -> Result<()> {
+        self.close_inner(None).await
+    }
+
+    /// Closes the connection gracefully, waiting for pending API requests up to `timeout`.
+    ///
+    /// This is the same as [`close`][`WebSocketClient::close`] except that it gives up waiting for
+    /// the server after `timeout`. The API requests that are not responded until then fail with
+    /// [`Error::Closed`].
+    pub async fn close_with_timeout(&self, timeout: Duration) -> Result<()> {
+        self.close_inner(Some(timeout)).await
+    }
+
+    /// Returns `true` if this client is no longer available, i.e. it has been closed or the
+    /// connection has been lost.
+    pub fn is_closed(&self) -> bool {
+        // the state is locked only while the broker is updating it to be unavailable
+        match self.state.try_read() {
+            Some(state) => state.dead().is_some(),
+            None => true,
+        }
+    }
+
+    /// Returns the statistics of the active subscriptions and the connection.
+    ///
+    /// This is useful for monitoring, e.g. to find the streams that are not dropped.
+    /// See [`Stats`] for the available information.
+    pub async fn stats(&self) -> Result<Stats> {
+        let (tx, rx) = response_channel(SharedBrokerState::clone(&self.state));
+        self.broker_tx
+            .clone()
+            .send(BrokerControl::Stats { sender: tx })
+            .await?;
+        rx.recv().await
+    }
+
+    async fn close_inner(&self, timeout: Option<Duration>) -> Result<()> {
+        let (tx, rx) = close_channel(SharedBrokerState::clone(&self.state));
+        let result = self
+            .broker_tx
+            .clone()
+            .send(BrokerControl::Close {
+                timeout,
+                sender: tx,
+            })
+            .await;
+
+        match result {
+            Ok(()) => rx.recv().await,
+            Err(Error::Closed) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 impl Client for WebSocketClient {
@@ -220,6 +353,7 @@ impl StreamingClient for WebSocketClient {
                 SubNoteId(note_id),
                 self.broker_tx.clone(),
                 SharedBrokerState::clone(&self.state),
+                self.buffer,
             )
             .await?
             .boxed())
@@ -237,6 +371,7 @@ impl StreamingClient for WebSocketClient {
             request,
             self.broker_tx.clone(),
             SharedBrokerState::clone(&self.state),
+            self.buffer,
         )
         .map_ok(boxed_stream_sink)
         .boxed()
@@ -250,6 +385,7 @@ impl StreamingClient for WebSocketClient {
             Ok(Broadcast::start(
                 self.broker_tx.clone(),
                 SharedBrokerState::clone(&self.state),
+                self.buffer,
             )
             .await?
             .boxed())
@@ -342,5 +478,137 @@ mod tests {
         .await;
     }
 
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "tokio02-runtime", tokio02::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn share_channels() {
+        use misskey_api::streaming::channel::home_timeline;
+
+        misskey_test::init_logger();
+        let client = WebSocketClientBuilder::new(env::websocket_url())
+            .token(env::token())
+            .share_channels(true)
+            .connect()
+            .await
+            .unwrap();
+
+        let mut stream1 = client
+            .channel(home_timeline::Request::default())
+            .await
+            .unwrap();
+        let mut stream2 = client
+            .channel(home_timeline::Request::default())
+            .await
+            .unwrap();
+
+        futures_util::future::join3(
+            async {
+                client
+                    .request(
+                        misskey_api::endpoint::notes::create::Request::builder()
+                            .text("hi")
+                            .build(),
+                    )
+                    .await
+                    .unwrap()
+                    .unwrap()
+            },
+            async { stream1.next().await.unwrap().unwrap() },
+            async { stream2.next().await.unwrap().unwrap() },
+        )
+        .await;
+
+        stream1.disconnect().await.unwrap();
+
+        futures_util::future::join(
+            async {
+                client
+                    .request(
+                        misskey_api::endpoint::notes::create::Request::builder()
+                            .text("hi")
+                            .build(),
+                    )
+                    .await
+                    .unwrap()
+                    .unwrap()
+            },
+            async { stream2.next().await.unwrap().unwrap() },
+        )
+        .await;
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn stats() {
+        use misskey_api::streaming::channel::home_timeline;
+
+        let client = test_client().await;
+        let mut stream = client
+            .channel(home_timeline::Request::default())
+            .await
+            .unwrap();
+
+        futures_util::future::join(
+            async {
+                client
+                    .request(
+                        misskey_api::endpoint::notes::create::Request::builder()
+                            .text("hi")
+                            .build(),
+                    )
+                    .await
+                    .unwrap()
+                    .unwrap()
+            },
+            async { stream.next().await.unwrap().unwrap() },
+        )
+        .await;
+
+        let stats = client.stats().await.unwrap();
+        assert_eq!(stats.channels.len(), 1);
+        assert_eq!(stats.channels[0].name, "homeTimeline");
+        assert_eq!(stats.channels[0].subscribers, 1);
+        assert!(stats.channels[0].received >= 1);
+        assert_eq!(stats.pending_requests, 0);
+
+        drop(stream);
+        let stats = client.stats().await.unwrap();
+        assert!(stats.channels.is_empty());
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "tokio02-runtime", tokio02::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn close() {
+        let client = test_client().await;
+        let note = client
+            .request(
+                misskey_api::endpoint::notes::create::Request::builder()
+                    .text("hi")
+                    .build(),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .created_note;
+
+        let mut stream = client
+            .subnote::<misskey_api::streaming::note::NoteUpdateEvent, _>(note.id.to_string())
+            .await
+            .unwrap();
+
+        client.close().await.unwrap();
+        assert!(stream.next().await.is_none());
+        assert!(matches!(
+            client
+                .request(misskey_api::endpoint::notes::show::Request { note_id: note.id })
+                .await,
+            Err(crate::Error::Closed)
+        ));
+
+        // closing twice is fine
+        client.close().await.unwrap();
+    }
+
     // TODO: test of `Broadcast`
 }
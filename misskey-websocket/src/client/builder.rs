@@ -2,9 +2,12 @@ use std::result::Result as StdResult;
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::broker::{ReconnectCondition, ReconnectConfig};
+use crate::broker::{BrokerConfig, BufferConfig, OverflowPolicy, ReconnectCondition};
 use crate::client::WebSocketClient;
+use crate::deflate::DeflateConfig;
 use crate::error::{Error, Result};
+use crate::record::SharedRecorder;
+use crate::runtime::{Runtime, SharedRuntime};
 
 use async_tungstenite::tungstenite::http::{
     self,
@@ -16,7 +19,9 @@ use url::Url;
 struct WebSocketClientBuilderInner {
     url: Url,
     additional_headers: HeaderMap,
-    reconnect: ReconnectConfig,
+    config: BrokerConfig,
+    buffer: BufferConfig,
+    runtime: Option<SharedRuntime>,
 }
 
 /// Builder for [`WebSocketClient`].
@@ -69,7 +74,9 @@ impl WebSocketClientBuilder {
             .map(|url| WebSocketClientBuilderInner {
                 url,
                 additional_headers: HeaderMap::new(),
-                reconnect: ReconnectConfig::default(),
+                config: BrokerConfig::default(),
+                buffer: BufferConfig::default(),
+                runtime: None,
             });
 
         WebSocketClientBuilder { inner }
@@ -140,7 +147,7 @@ impl WebSocketClientBuilder {
     /// Sets whether or not to enable automatic reconnection.
     ///
     /// Automatic reconnection is enabled by default (as per [`Default`][default] implementation for
-    /// [`ReconnectConfig`]), and you can disable it with `.auto_reconnect(false)`.
+    /// [`ReconnectConfig`][`crate::ReconnectConfig`]), and you can disable it with `.auto_reconnect(false)`.
     ///
     /// [default]: std::default::Default
     pub fn auto_reconnect(&mut self, enable: bool) -> &mut Self {
@@ -154,7 +161,7 @@ impl WebSocketClientBuilder {
     /// Sets an interval duration of automatic reconnection in seconds.
     pub fn reconnect_secs(&mut self, secs: u64) -> &mut Self {
         self.inner.and_then_mut(|inner| {
-            inner.reconnect.interval = Duration::from_secs(secs);
+            inner.config.reconnect.interval = Duration::from_secs(secs);
             Ok(())
         });
         self
@@ -163,7 +170,7 @@ impl WebSocketClientBuilder {
     /// Sets an interval duration of automatic reconnection.
     pub fn reconnect_interval(&mut self, interval: Duration) -> &mut Self {
         self.inner.and_then_mut(|inner| {
-            inner.reconnect.interval = interval;
+            inner.config.reconnect.interval = interval;
             Ok(())
         });
         self
@@ -172,7 +179,7 @@ impl WebSocketClientBuilder {
     /// Specifies the condition for reconnecting.
     pub fn reconnect_condition(&mut self, condition: ReconnectCondition) -> &mut Self {
         self.inner.and_then_mut(|inner| {
-            inner.reconnect.condition = condition;
+            inner.config.reconnect.condition = condition;
             Ok(())
         });
         self
@@ -181,7 +188,131 @@ impl WebSocketClientBuilder {
     /// Specifies whether to re-send messages that may have failed to be sent when reconnecting.
     pub fn reconnect_retry_send(&mut self, enable: bool) -> &mut Self {
         self.inner.and_then_mut(|inner| {
-            inner.reconnect.retry_send = enable;
+            inner.config.reconnect.retry_send = enable;
+            Ok(())
+        });
+        self
+    }
+
+    /// Sets whether or not to share a connection to the channel among the identical requests.
+    ///
+    /// When enabled, [`channel`][`WebSocketClient::channel`] calls with the same channel and
+    /// parameters are multiplexed onto a single connection, and the received messages are
+    /// delivered to all of the returned streams. The connection is disconnected when the last
+    /// stream is disconnected or dropped. Note that messages sent to one of these streams are
+    /// sent to the shared connection.
+    ///
+    /// Channel sharing is disabled by default.
+    pub fn share_channels(&mut self, enable: bool) -> &mut Self {
+        self.inner.and_then_mut(|inner| {
+            inner.config.share_channels = enable;
+            Ok(())
+        });
+        self
+    }
+
+    /// Sets the duration after which the connection is closed when there are no subscriptions.
+    ///
+    /// Subscriptions include channels, note subscriptions, broadcast streams and pending API
+    /// requests. Once closed, the client behaves as if [`close`][`WebSocketClient::close`] is
+    /// called, which can be checked with [`is_closed`][`WebSocketClient::is_closed`].
+    ///
+    /// The connection is kept open regardless of the subscriptions by default.
+    pub fn idle_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.inner.and_then_mut(|inner| {
+            inner.config.idle_timeout = Some(timeout);
+            Ok(())
+        });
+        self
+    }
+
+    /// Negotiates the permessage-deflate extension to receive compressed messages.
+    ///
+    /// The extension is offered to the server with the parameters in `config`, and the connection
+    /// falls back to uncompressed messages when the server does not accept it. See the
+    /// [`deflate`][`crate::deflate`] module for details.
+    ///
+    /// The extension is supported by [`TokioRuntime`][`crate::runtime::TokioRuntime`]. Other
+    /// runtimes open uncompressed connections unless they implement
+    /// [`Runtime::connect_deflate`][`crate::runtime::Runtime::connect_deflate`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config.server_max_window_bits` is not in the range from 8 to 15.
+    pub fn permessage_deflate(&mut self, config: DeflateConfig) -> &mut Self {
+        if let Some(bits) = config.server_max_window_bits {
+            assert!(
+                (8..=15).contains(&bits),
+                "server_max_window_bits must be in the range from 8 to 15"
+            );
+        }
+        self.inner.and_then_mut(|inner| {
+            inner.config.deflate = Some(config);
+            Ok(())
+        });
+        self
+    }
+
+    /// Records the messages exchanged with the server to `writer`.
+    ///
+    /// Each message is written as a line of JSON with the time at which it is sent or received.
+    /// See the [`record`][`crate::record`] module for the format, and
+    /// [`ReplayClient`][`crate::replay::ReplayClient`] to replay the recorded traffic.
+    ///
+    /// The messages are written on a background thread through a buffer, which is flushed whenever
+    /// the pending messages are written out. Failures in writing are logged and ignored.
+    pub fn record<W>(&mut self, writer: W) -> &mut Self
+    where
+        W: std::io::Write + Send + 'static,
+    {
+        self.inner.and_then_mut(|inner| {
+            inner.config.recorder = Some(SharedRecorder::new(writer));
+            Ok(())
+        });
+        self
+    }
+
+    /// Sets the capacity of the buffer of each stream.
+    ///
+    /// Streams have the unbounded buffer by default, which grows without limit when the messages
+    /// are not consumed. Use [`overflow_policy`][`WebSocketClientBuilder::overflow_policy`] to
+    /// specify what to do when the buffer is full. You can also override this per stream using
+    /// methods such as [`WebSocketClient::channel_with_buffer`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn buffer_capacity(&mut self, capacity: usize) -> &mut Self {
+        assert!(capacity > 0, "buffer capacity must be greater than zero");
+        self.inner.and_then_mut(|inner| {
+            inner.buffer.capacity = Some(capacity);
+            Ok(())
+        });
+        self
+    }
+
+    /// Specifies what to do when the buffer of a stream is full.
+    ///
+    /// This is meaningful only when the capacity is set with
+    /// [`buffer_capacity`][`WebSocketClientBuilder::buffer_capacity`].
+    /// [`OverflowPolicy::Block`] is used by default.
+    pub fn overflow_policy(&mut self, policy: OverflowPolicy) -> &mut Self {
+        self.inner.and_then_mut(|inner| {
+            inner.buffer.overflow = policy;
+            Ok(())
+        });
+        self
+    }
+
+    /// Specifies the async runtime that the client runs on.
+    ///
+    /// By default, [`TokioRuntime`][`crate::runtime::TokioRuntime`] or
+    /// [`AsyncStdRuntime`][`crate::runtime::AsyncStdRuntime`] is used depending on the enabled
+    /// feature, and [`connect`][`WebSocketClientBuilder::connect`] fails with
+    /// [`Error::NoRuntime`] when none of them is enabled.
+    pub fn runtime<R: Runtime>(&mut self, runtime: R) -> &mut Self {
+        self.inner.and_then_mut(|inner| {
+            inner.runtime = Some(SharedRuntime::new(runtime));
             Ok(())
         });
         self
@@ -192,12 +323,21 @@ impl WebSocketClientBuilder {
         let WebSocketClientBuilderInner {
             url,
             additional_headers,
-            reconnect,
+            config,
+            buffer,
+            runtime,
         } = match self.inner.clone() {
             Err(e) => return Err(e),
             Ok(inner) => inner,
         };
 
-        WebSocketClient::connect_with_headers_and_config(url, additional_headers, reconnect).await
+        let runtime = runtime
+            .or_else(SharedRuntime::from_features)
+            .ok_or(Error::NoRuntime)?;
+        let mut client =
+            WebSocketClient::connect_with_broker_config(url, additional_headers, config, runtime)
+                .await?;
+        client.buffer = buffer;
+        Ok(client)
     }
 }
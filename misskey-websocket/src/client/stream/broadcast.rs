@@ -6,11 +6,10 @@ use std::task::{Context, Poll};
 use crate::broker::{
     channel::{response_stream_channel, ControlSender, ResponseStreamReceiver},
     model::{BroadcastId, BrokerControl, SharedBrokerState},
+    BufferConfig,
 };
 use crate::error::Result;
 
-#[cfg(feature = "async-std-runtime")]
-use async_std::task;
 use futures_util::{
     sink::SinkExt,
     stream::{FusedStream, Stream, StreamExt},
@@ -18,8 +17,6 @@ use futures_util::{
 use log::{info, warn};
 use misskey_core::streaming::BroadcastEvent;
 use serde_json::Value;
-#[cfg(feature = "tokio-runtime")]
-use tokio::task;
 
 struct BroadcastInner {
     id: BroadcastId,
@@ -57,19 +54,20 @@ impl FusedStream for BroadcastInner {
 
 impl Drop for BroadcastInner {
     fn drop(&mut self) {
-        if self.is_terminated {
+        // the broker has already released the stream if `response_rx` is terminated
+        if self.is_terminated || self.response_rx.is_terminated() {
             return;
         }
 
-        let mut broker_tx = self.broker_tx.clone();
+        // If the broker connection is dead, we don't need to stop this anyway
+        // because the client can't be used anymore.
         let id = self.id;
-        task::spawn(async move {
-            // If the broker connection is dead, we don't need to stop this anyway
-            // because the client can't be used anymore.
-            if let Err(e) = broker_tx.send(BrokerControl::StopBroadcast { id }).await {
-                warn!("Broadcast::stop failed in Drop::drop (ignored): {:?}", e);
-            }
-        });
+        if let Err(e) = self
+            .broker_tx
+            .unbounded_send(BrokerControl::StopBroadcast { id })
+        {
+            warn!("Broadcast::stop failed in Drop::drop (ignored): {:?}", e);
+        }
     }
 }
 
@@ -77,11 +75,12 @@ impl BroadcastInner {
     async fn start(
         mut broker_tx: ControlSender,
         state: SharedBrokerState,
+        buffer: BufferConfig,
         type_: &'static str,
     ) -> Result<BroadcastInner> {
         let id = BroadcastId::new();
 
-        let (response_tx, response_rx) = response_stream_channel(state);
+        let (response_tx, response_rx) = response_stream_channel(state, buffer);
         broker_tx
             .send(BrokerControl::StartBroadcast {
                 id,
@@ -129,8 +128,9 @@ where
     pub(crate) async fn start(
         broker_tx: ControlSender,
         state: SharedBrokerState,
+        buffer: BufferConfig,
     ) -> Result<Broadcast<E>> {
-        BroadcastInner::start(broker_tx, state, E::TYPE)
+        BroadcastInner::start(broker_tx, state, buffer, E::TYPE)
             .await
             .map(|inner| Broadcast {
                 inner,
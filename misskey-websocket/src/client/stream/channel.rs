@@ -8,12 +8,11 @@ use crate::broker::{
         channel_pong_channel, response_stream_channel, ControlSender, ResponseStreamReceiver,
     },
     model::{BrokerControl, SharedBrokerState},
+    BufferConfig,
 };
 use crate::error::{Error, Result};
 use crate::model::ChannelId;
 
-#[cfg(feature = "async-std-runtime")]
-use async_std::task;
 use futures_util::{
     future::BoxFuture,
     sink::{Sink, SinkExt},
@@ -23,8 +22,6 @@ use log::{info, warn};
 use misskey_core::streaming::ConnectChannelRequest;
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
-#[cfg(feature = "tokio-runtime")]
-use tokio::task;
 
 pub struct ChannelInner {
     id: ChannelId,
@@ -86,22 +83,23 @@ impl FusedStream for ChannelInner {
 
 impl Drop for ChannelInner {
     fn drop(&mut self) {
-        if self.is_terminated {
+        // the broker has already released the stream if `response_rx` is terminated
+        if self.is_terminated || self.response_rx.is_terminated() {
             return;
         }
 
-        let mut broker_tx = self.broker_tx.clone();
+        // If the broker or websocket connection is dead, we don't need to unsubscribe anyway
+        // because the client can't be used anymore.
         let id = self.id;
-        task::spawn(async move {
-            // If the broker or websocket connection is dead, we don't need to unsubscribe anyway
-            // because the client can't be used anymore.
-            if let Err(e) = broker_tx.send(BrokerControl::Disconnect { id }).await {
-                warn!(
-                    "Channel::disconnect failed in Drop::drop (ignored): {:?}",
-                    e
-                );
-            }
-        });
+        if let Err(e) = self
+            .broker_tx
+            .unbounded_send(BrokerControl::Disconnect { id })
+        {
+            warn!(
+                "Channel::disconnect failed in Drop::drop (ignored): {:?}",
+                e
+            );
+        }
     }
 }
 
@@ -111,10 +109,12 @@ impl ChannelInner {
         serialized_req: Value,
         mut broker_tx: ControlSender,
         state: SharedBrokerState,
+        buffer: BufferConfig,
     ) -> Result<ChannelInner> {
         let id = ChannelId::uuid();
 
-        let (response_tx, response_rx) = response_stream_channel(SharedBrokerState::clone(&state));
+        let (response_tx, response_rx) =
+            response_stream_channel(SharedBrokerState::clone(&state), buffer);
         let (pong_tx, pong_rx) = channel_pong_channel(state);
 
         broker_tx
@@ -175,13 +175,14 @@ where
         req: R,
         broker_tx: ControlSender,
         state: SharedBrokerState,
+        buffer: BufferConfig,
     ) -> BoxFuture<'static, Result<Channel<I, O>>>
     where
         R: ConnectChannelRequest<Incoming = I, Outgoing = O>,
     {
         let req = serde_json::to_value(req);
         Box::pin(async move {
-            ChannelInner::connect(R::NAME, req?, broker_tx, state)
+            ChannelInner::connect(R::NAME, req?, broker_tx, state, buffer)
                 .await
                 .map(|inner| Channel {
                     inner,
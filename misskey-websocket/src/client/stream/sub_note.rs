@@ -6,12 +6,11 @@ use std::task::{Context, Poll};
 use crate::broker::{
     channel::{response_stream_channel, ControlSender, ResponseStreamReceiver},
     model::{BrokerControl, SharedBrokerState},
+    BufferConfig,
 };
 use crate::error::Result;
 use crate::model::SubNoteId;
 
-#[cfg(feature = "async-std-runtime")]
-use async_std::task;
 use futures_util::{
     sink::SinkExt,
     stream::{FusedStream, Stream, StreamExt},
@@ -19,8 +18,6 @@ use futures_util::{
 use log::{info, warn};
 use misskey_core::streaming::SubNoteEvent;
 use serde_json::Value;
-#[cfg(feature = "tokio-runtime")]
-use tokio::task;
 
 pub struct SubNoteInner {
     id: SubNoteId,
@@ -58,22 +55,23 @@ impl FusedStream for SubNoteInner {
 
 impl Drop for SubNoteInner {
     fn drop(&mut self) {
-        if self.is_terminated {
+        // the broker has already released the stream if `response_rx` is terminated
+        if self.is_terminated || self.response_rx.is_terminated() {
             return;
         }
 
-        let mut broker_tx = self.broker_tx.clone();
+        // If the broker connection is dead, we don't need to unsubscribe anyway
+        // because the client can't be used anymore.
         let id = self.id.clone();
-        task::spawn(async move {
-            // If the broker connection is dead, we don't need to unsubscribe anyway
-            // because the client can't be used anymore.
-            if let Err(e) = broker_tx.send(BrokerControl::UnsubNote { id }).await {
-                warn!(
-                    "SubNote::unsubscribe failed in Drop::drop (ignored): {:?}",
-                    e
-                );
-            }
-        });
+        if let Err(e) = self
+            .broker_tx
+            .unbounded_send(BrokerControl::UnsubNote { id })
+        {
+            warn!(
+                "SubNote::unsubscribe failed in Drop::drop (ignored): {:?}",
+                e
+            );
+        }
     }
 }
 
@@ -82,8 +80,9 @@ impl SubNoteInner {
         id: SubNoteId,
         mut broker_tx: ControlSender,
         state: SharedBrokerState,
+        buffer: BufferConfig,
     ) -> Result<SubNoteInner> {
-        let (response_tx, response_rx) = response_stream_channel(state);
+        let (response_tx, response_rx) = response_stream_channel(state, buffer);
         broker_tx
             .send(BrokerControl::SubNote {
                 id: id.clone(),
@@ -130,8 +129,9 @@ impl<E> SubNote<E> {
         id: SubNoteId,
         broker_tx: ControlSender,
         state: SharedBrokerState,
+        buffer: BufferConfig,
     ) -> Result<SubNote<E>> {
-        SubNoteInner::subscribe(id, broker_tx, state)
+        SubNoteInner::subscribe(id, broker_tx, state, buffer)
             .await
             .map(|inner| SubNote {
                 inner,
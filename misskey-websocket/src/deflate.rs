@@ -0,0 +1,64 @@
+//! Compression of the streaming connection.
+//!
+//! The client can negotiate the permessage-deflate extension ([RFC 7692]) to receive compressed
+//! messages from the server, which significantly cuts the bandwidth of busy timelines since the
+//! messages consist of highly redundant JSON. Enable it with
+//! [`WebSocketClientBuilder::permessage_deflate`][`crate::WebSocketClientBuilder::permessage_deflate`].
+//!
+//! Only the messages from the server are compressed; the client sends uncompressed messages, which
+//! is allowed by the extension. When the server does not accept the extension, the connection
+//! falls back to uncompressed messages.
+//!
+//! [RFC 7692]: https://datatracker.ietf.org/doc/html/rfc7692
+
+mod frame;
+mod inflate;
+#[cfg(feature = "tokio-runtime")]
+pub(crate) mod transport;
+
+/// Parameters of the permessage-deflate extension offered to the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DeflateConfig {
+    /// The base-2 logarithm of the LZ77 window size that the server may use to compress
+    /// messages, from 8 to 15.
+    ///
+    /// A smaller window reduces the memory used on the server per connection at the cost of
+    /// compression ratio. The server chooses the size when `None`, which is the default.
+    pub server_max_window_bits: Option<u8>,
+    /// Whether to ask the server to compress each message independently.
+    ///
+    /// This saves the memory of the server at the cost of compression ratio. Disabled by default.
+    pub server_no_context_takeover: bool,
+}
+
+impl DeflateConfig {
+    /// Returns the value of `Sec-WebSocket-Extensions` header to offer the extension.
+    pub(crate) fn offer(&self) -> String {
+        let mut offer = String::from("permessage-deflate");
+        if let Some(bits) = self.server_max_window_bits {
+            offer.push_str(&format!("; server_max_window_bits={}", bits));
+        }
+        if self.server_no_context_takeover {
+            offer.push_str("; server_no_context_takeover");
+        }
+        offer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeflateConfig;
+
+    #[test]
+    fn test_offer() {
+        assert_eq!(DeflateConfig::default().offer(), "permessage-deflate");
+        let config = DeflateConfig {
+            server_max_window_bits: Some(10),
+            server_no_context_takeover: true,
+        };
+        assert_eq!(
+            config.offer(),
+            "permessage-deflate; server_max_window_bits=10; server_no_context_takeover"
+        );
+    }
+}
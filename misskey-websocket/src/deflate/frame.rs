@@ -0,0 +1,348 @@
+//! Decompression of the byte stream received from the server.
+//!
+//! tungstenite rejects frames with the RSV1 bit set, which marks compressed messages. Instead of
+//! handling the extension within tungstenite, the bytes read from the socket are rewritten before
+//! tungstenite sees them: the handshake response is inspected to find out whether the extension
+//! is accepted, and then each compressed message is replaced with an uncompressed one.
+
+use std::io;
+
+use super::inflate::Inflater;
+
+/// Limit on the size of decompressed messages, which is the default of tungstenite.
+const MAX_MESSAGE_SIZE: usize = 64 << 20;
+/// Limit on the size of the handshake response.
+const MAX_HANDSHAKE_SIZE: usize = 64 * 1024;
+/// Size of the frames that the decompressed messages are split into.
+const MAX_FRAME_SIZE: usize = 1 << 20;
+/// Trailer removed from the compressed messages by the server.
+const TRAILER: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+const FIN: u8 = 0x80;
+const RSV1: u8 = 0x40;
+const OPCODE_CONTINUATION: u8 = 0x0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Reading the handshake response.
+    Handshake,
+    /// The extension is accepted and the frames are being decompressed.
+    Frames,
+    /// The extension is not in use, so the bytes are passed through.
+    Passthrough,
+}
+
+/// Compressed message being received.
+#[derive(Debug)]
+struct Message {
+    opcode: u8,
+    payload: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub(crate) struct FrameDecoder {
+    state: State,
+    inflater: Inflater,
+    no_context_takeover: bool,
+    message: Option<Message>,
+    /// bytes received but not processed yet
+    input: Vec<u8>,
+    /// bytes processed but not read yet
+    output: Vec<u8>,
+    /// the number of bytes already read from `output`
+    consumed: usize,
+}
+
+impl FrameDecoder {
+    pub fn new() -> FrameDecoder {
+        FrameDecoder {
+            state: State::Handshake,
+            inflater: Inflater::default(),
+            no_context_takeover: false,
+            message: None,
+            input: Vec::new(),
+            output: Vec::new(),
+            consumed: 0,
+        }
+    }
+
+    /// Returns whether the server accepted the extension, or `None` during the handshake.
+    pub fn is_accepted(&self) -> Option<bool> {
+        match self.state {
+            State::Handshake => None,
+            State::Frames => Some(true),
+            State::Passthrough => Some(false),
+        }
+    }
+
+    /// Returns the processed bytes.
+    pub fn output(&self) -> &[u8] {
+        &self.output[self.consumed..]
+    }
+
+    /// Marks the first `n` bytes of [`output`][`FrameDecoder::output`] as read.
+    pub fn consume(&mut self, n: usize) {
+        self.consumed += n;
+        if self.consumed == self.output.len() {
+            self.output.clear();
+            self.consumed = 0;
+        }
+    }
+
+    /// Processes the bytes received from the server.
+    pub fn feed(&mut self, data: &[u8]) -> io::Result<()> {
+        if self.state == State::Passthrough {
+            self.output.extend_from_slice(data);
+            return Ok(());
+        }
+
+        self.input.extend_from_slice(data);
+        if self.state == State::Handshake {
+            self.handshake();
+        }
+        if self.state == State::Frames {
+            while self.frame()? {}
+        }
+        if self.state == State::Passthrough {
+            self.output.append(&mut self.input);
+        }
+        Ok(())
+    }
+
+    /// Passes the unprocessed bytes through at the end of the stream, which leaves tungstenite to
+    /// report the incomplete frame or response.
+    pub fn finish(&mut self) {
+        self.output.append(&mut self.input);
+    }
+
+    fn handshake(&mut self) {
+        let end = match self.input.windows(4).position(|w| w == b"\r\n\r\n") {
+            Some(position) => position + 4,
+            None if self.input.len() > MAX_HANDSHAKE_SIZE => {
+                self.state = State::Passthrough;
+                return;
+            }
+            None => return,
+        };
+
+        let response = String::from_utf8_lossy(&self.input[..end]);
+        let mut accepted = false;
+        for line in response.split("\r\n").skip(1) {
+            let (name, value) = match line.split_once(':') {
+                Some(x) => x,
+                None => continue,
+            };
+            if !name.trim().eq_ignore_ascii_case("sec-websocket-extensions") {
+                continue;
+            }
+            for extension in value.split(',') {
+                let mut params = extension.split(';').map(str::trim);
+                if params.next() != Some("permessage-deflate") {
+                    continue;
+                }
+                accepted = true;
+                if params.any(|param| param == "server_no_context_takeover") {
+                    self.no_context_takeover = true;
+                }
+            }
+        }
+
+        self.output.extend(self.input.drain(..end));
+        self.state = if accepted {
+            State::Frames
+        } else {
+            State::Passthrough
+        };
+    }
+
+    /// Processes a frame in `input`, and returns whether a frame is processed.
+    fn frame(&mut self) -> io::Result<bool> {
+        let header = match parse_header(&self.input) {
+            Some(header) => header,
+            None => return Ok(false),
+        };
+        if header.payload_len > MAX_MESSAGE_SIZE {
+            return Err(invalid("frame is too large"));
+        }
+        let end = header.len + header.payload_len;
+        if self.input.len() < end {
+            return Ok(false);
+        }
+
+        let first = self.input[0];
+        let opcode = first & 0x0f;
+        let is_control = opcode & 0x08 != 0;
+        let is_compressed = first & RSV1 != 0;
+        let fin = first & FIN != 0;
+
+        if is_control || header.masked || (self.message.is_none() && !is_compressed) {
+            // pass through the uncompressed frames of uncompressed messages and control frames,
+            // which may be interleaved with the fragments of a compressed message. masked frames
+            // are not allowed from the server and left to be rejected by tungstenite
+            self.output.extend(self.input.drain(..end));
+            return Ok(true);
+        }
+
+        let payload = &self.input[header.len..end];
+        match &mut self.message {
+            Some(message) if opcode == OPCODE_CONTINUATION && !is_compressed => {
+                if message.payload.len() + payload.len() > MAX_MESSAGE_SIZE {
+                    return Err(invalid("compressed message is too large"));
+                }
+                message.payload.extend_from_slice(payload);
+            }
+            None if opcode != OPCODE_CONTINUATION => {
+                self.message = Some(Message {
+                    opcode,
+                    payload: payload.to_vec(),
+                });
+            }
+            _ => return Err(invalid("unexpected frame in compressed message")),
+        }
+        self.input.drain(..end);
+
+        if fin {
+            let mut message = self.message.take().unwrap();
+            message.payload.extend_from_slice(&TRAILER);
+            if self.no_context_takeover {
+                self.inflater.reset();
+            }
+            let data = self.inflater.inflate(&message.payload, MAX_MESSAGE_SIZE)?;
+            write_message(&mut self.output, message.opcode, &data);
+        }
+
+        Ok(true)
+    }
+}
+
+fn invalid(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+struct Header {
+    /// the length of the header
+    len: usize,
+    payload_len: usize,
+    masked: bool,
+}
+
+fn parse_header(input: &[u8]) -> Option<Header> {
+    let second = *input.get(1)?;
+    let masked = second & 0x80 != 0;
+    let (len, payload_len) = match second & 0x7f {
+        126 => (
+            4,
+            u16::from_be_bytes(input.get(2..4)?.try_into().unwrap()) as u64,
+        ),
+        127 => (
+            10,
+            u64::from_be_bytes(input.get(2..10)?.try_into().unwrap()),
+        ),
+        n => (2, n as u64),
+    };
+    let len = if masked { len + 4 } else { len };
+    if input.len() < len {
+        return None;
+    }
+    let payload_len = usize::try_from(payload_len).unwrap_or(usize::MAX);
+    Some(Header {
+        len,
+        payload_len,
+        masked,
+    })
+}
+
+/// Writes `data` as a message split into unmasked frames.
+fn write_message(output: &mut Vec<u8>, opcode: u8, data: &[u8]) {
+    let mut chunks = data.chunks(MAX_FRAME_SIZE).peekable();
+    let mut opcode = opcode;
+    if chunks.peek().is_none() {
+        write_frame(output, true, opcode, &[]);
+    }
+    while let Some(chunk) = chunks.next() {
+        write_frame(output, chunks.peek().is_none(), opcode, chunk);
+        opcode = OPCODE_CONTINUATION;
+    }
+}
+
+fn write_frame(output: &mut Vec<u8>, fin: bool, opcode: u8, payload: &[u8]) {
+    output.push(if fin { FIN | opcode } else { opcode });
+    match payload.len() {
+        len @ 0..=125 => output.push(len as u8),
+        len @ 126..=0xffff => {
+            output.push(126);
+            output.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            output.push(127);
+            output.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    output.extend_from_slice(payload);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameDecoder;
+
+    const ACCEPTED: &[u8] = b"HTTP/1.1 101 Switching Protocols\r\n\
+        Upgrade: websocket\r\n\
+        Sec-WebSocket-Extensions: permessage-deflate; server_max_window_bits=15\r\n\r\n";
+    const NOT_ACCEPTED: &[u8] = b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\r\n";
+    /// "Hello, Hello, Hello!" compressed with the trailer removed.
+    const COMPRESSED: [u8; 13] = [
+        0xf2, 0x48, 0xcd, 0xc9, 0xc9, 0xd7, 0x51, 0xf0, 0x40, 0xa2, 0x14, 0x01, 0x00,
+    ];
+
+    fn feed_bytewise(decoder: &mut FrameDecoder, input: &[u8]) -> Vec<u8> {
+        for byte in input {
+            decoder.feed(std::slice::from_ref(byte)).unwrap();
+        }
+        let output = decoder.output().to_vec();
+        decoder.consume(output.len());
+        output
+    }
+
+    #[test]
+    fn test_compressed_message() {
+        let mut decoder = FrameDecoder::new();
+        assert_eq!(feed_bytewise(&mut decoder, ACCEPTED), ACCEPTED);
+        assert_eq!(decoder.is_accepted(), Some(true));
+
+        let mut input = vec![0xc1, COMPRESSED.len() as u8];
+        input.extend_from_slice(&COMPRESSED);
+        let mut expected = vec![0x81, 20];
+        expected.extend_from_slice(b"Hello, Hello, Hello!");
+        assert_eq!(feed_bytewise(&mut decoder, &input), expected);
+    }
+
+    #[test]
+    fn test_fragmented_message() {
+        let mut decoder = FrameDecoder::new();
+        feed_bytewise(&mut decoder, ACCEPTED);
+
+        let (first, second) = COMPRESSED.split_at(5);
+        let mut input = vec![0x41, first.len() as u8];
+        input.extend_from_slice(first);
+        // ping interleaved in the fragments
+        input.extend_from_slice(&[0x89, 0x00]);
+        input.extend_from_slice(&[0x80, second.len() as u8]);
+        input.extend_from_slice(second);
+        // uncompressed message
+        input.extend_from_slice(&[0x81, 0x02, b'h', b'i']);
+
+        let mut expected = vec![0x89, 0x00, 0x81, 20];
+        expected.extend_from_slice(b"Hello, Hello, Hello!");
+        expected.extend_from_slice(&[0x81, 0x02, b'h', b'i']);
+        assert_eq!(feed_bytewise(&mut decoder, &input), expected);
+    }
+
+    #[test]
+    fn test_not_accepted() {
+        let mut decoder = FrameDecoder::new();
+        let mut input = NOT_ACCEPTED.to_vec();
+        input.extend_from_slice(&[0xc1, 0x01, 0x00]);
+        assert_eq!(feed_bytewise(&mut decoder, &input), input);
+        assert_eq!(decoder.is_accepted(), Some(false));
+    }
+}
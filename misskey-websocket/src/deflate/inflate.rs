@@ -0,0 +1,370 @@
+//! Decoder of the raw DEFLATE format ([RFC 1951]).
+//!
+//! [RFC 1951]: https://datatracker.ietf.org/doc/html/rfc1951
+
+use std::io;
+
+/// The largest window allowed in the format.
+const WINDOW_SIZE: usize = 32 * 1024;
+const MAX_BITS: usize = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// The order in which the code lengths of the code length alphabet are stored.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+fn invalid(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Canonical Huffman code, decoded bit by bit.
+struct Huffman {
+    /// the number of symbols for each code length
+    counts: [u16; MAX_BITS + 1],
+    /// symbols ordered by their codes
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> io::Result<Huffman> {
+        let mut counts = [0; MAX_BITS + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(invalid("over-subscribed Huffman code"));
+            }
+        }
+
+        let mut offsets = [0; MAX_BITS + 1];
+        for length in 1..MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+
+        Ok(Huffman { counts, symbols })
+    }
+
+    fn fixed() -> (Huffman, Huffman) {
+        let mut lengths = [0; 288];
+        lengths[..144].fill(8);
+        lengths[144..256].fill(9);
+        lengths[256..280].fill(7);
+        lengths[280..].fill(8);
+        let lengths = Huffman::new(&lengths).expect("fixed code must be valid");
+        let distances = Huffman::new(&[5; 30]).expect("fixed code must be valid");
+        (lengths, distances)
+    }
+}
+
+/// Reader of the input from the least significant bit.
+struct Bits<'a> {
+    input: &'a [u8],
+    position: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl<'a> Bits<'a> {
+    fn new(input: &'a [u8]) -> Bits<'a> {
+        Bits {
+            input,
+            position: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position == self.input.len() && self.count == 0
+    }
+
+    fn read(&mut self, bits: u32) -> io::Result<u32> {
+        while self.count < bits {
+            let byte = *self
+                .input
+                .get(self.position)
+                .ok_or_else(|| invalid("unexpected end of compressed data"))?;
+            self.position += 1;
+            self.buffer |= (byte as u32) << self.count;
+            self.count += 8;
+        }
+        let value = self.buffer & ((1 << bits) - 1);
+        self.buffer >>= bits;
+        self.count -= bits;
+        Ok(value)
+    }
+
+    /// Discards the remaining bits in the current byte.
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+
+    fn read_bytes(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        debug_assert_eq!(self.count, 0);
+        let bytes = self
+            .input
+            .get(self.position..self.position + len)
+            .ok_or_else(|| invalid("unexpected end of compressed data"))?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn decode(&mut self, huffman: &Huffman) -> io::Result<u16> {
+        let (mut code, mut first, mut index) = (0, 0, 0);
+        for &count in &huffman.counts[1..] {
+            code |= self.read(1)? as i32;
+            let count = count as i32;
+            if code - count < first {
+                return Ok(huffman.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(invalid("invalid Huffman code"))
+    }
+}
+
+/// Decoder that keeps the window across the messages.
+#[derive(Debug, Default)]
+pub(crate) struct Inflater {
+    window: Vec<u8>,
+}
+
+impl Inflater {
+    /// Forgets the previous messages.
+    pub fn reset(&mut self) {
+        self.window.clear();
+    }
+
+    /// Decompresses `input`, which ends at a block boundary.
+    ///
+    /// Fails when the decompressed data exceeds `max_size` bytes.
+    pub fn inflate(&mut self, input: &[u8], max_size: usize) -> io::Result<Vec<u8>> {
+        let start = self.window.len();
+        let mut buffer = std::mem::take(&mut self.window);
+        let result = inflate_blocks(&mut Bits::new(input), &mut buffer, start + max_size);
+        let output = buffer.split_off(start);
+        if result.is_ok() {
+            buffer.extend_from_slice(&output);
+            let excess = buffer.len().saturating_sub(WINDOW_SIZE);
+            buffer.drain(..excess);
+            self.window = buffer;
+        }
+        result.map(|()| output)
+    }
+}
+
+fn inflate_blocks(bits: &mut Bits, output: &mut Vec<u8>, limit: usize) -> io::Result<()> {
+    while !bits.is_empty() {
+        let last = bits.read(1)? == 1;
+        match bits.read(2)? {
+            0 => stored(bits, output)?,
+            1 => {
+                let (lengths, distances) = Huffman::fixed();
+                codes(bits, output, &lengths, &distances, limit)?;
+            }
+            2 => {
+                let (lengths, distances) = dynamic(bits)?;
+                codes(bits, output, &lengths, &distances, limit)?;
+            }
+            _ => return Err(invalid("invalid block type")),
+        }
+        if output.len() > limit {
+            return Err(invalid("decompressed message is too large"));
+        }
+        if last {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn stored(bits: &mut Bits, output: &mut Vec<u8>) -> io::Result<()> {
+    bits.align();
+    let len = bits.read(16)?;
+    if bits.read(16)? != !len & 0xffff {
+        return Err(invalid("stored block length did not match its complement"));
+    }
+    output.extend_from_slice(bits.read_bytes(len as usize)?);
+    Ok(())
+}
+
+fn dynamic(bits: &mut Bits) -> io::Result<(Huffman, Huffman)> {
+    let nlen = bits.read(5)? as usize + 257;
+    let ndist = bits.read(5)? as usize + 1;
+    let ncode = bits.read(4)? as usize + 4;
+    if nlen > 286 || ndist > 30 {
+        return Err(invalid("too many length or distance codes"));
+    }
+
+    let mut lengths = [0; 286 + 30];
+    for &index in &CODE_LENGTH_ORDER[..ncode] {
+        lengths[index] = bits.read(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&lengths[..19])?;
+
+    let mut index = 0;
+    while index < nlen + ndist {
+        let symbol = bits.decode(&code_lengths)?;
+        let (length, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *index
+                    .checked_sub(1)
+                    .and_then(|i| lengths.get(i))
+                    .ok_or_else(|| invalid("repeated length with no first length"))?;
+                (previous, 3 + bits.read(2)? as usize)
+            }
+            17 => (0, 3 + bits.read(3)? as usize),
+            _ => (0, 11 + bits.read(7)? as usize),
+        };
+        if index + repeat > nlen + ndist {
+            return Err(invalid("too many code lengths"));
+        }
+        lengths[index..index + repeat].fill(length);
+        index += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(invalid("missing end-of-block code"));
+    }
+
+    let lengths_code = Huffman::new(&lengths[..nlen])?;
+    let distances_code = Huffman::new(&lengths[nlen..nlen + ndist])?;
+    Ok((lengths_code, distances_code))
+}
+
+fn codes(
+    bits: &mut Bits,
+    output: &mut Vec<u8>,
+    lengths: &Huffman,
+    distances: &Huffman,
+    limit: usize,
+) -> io::Result<()> {
+    loop {
+        let symbol = bits.decode(lengths)? as usize;
+        match symbol {
+            0..=255 => output.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let symbol = symbol - 257;
+                if symbol >= LENGTH_BASE.len() {
+                    return Err(invalid("invalid length symbol"));
+                }
+                let len =
+                    LENGTH_BASE[symbol] as usize + bits.read(LENGTH_EXTRA[symbol] as u32)? as usize;
+                let symbol = bits.decode(distances)? as usize;
+                if symbol >= DISTANCE_BASE.len() {
+                    return Err(invalid("invalid distance symbol"));
+                }
+                let distance = DISTANCE_BASE[symbol] as usize
+                    + bits.read(DISTANCE_EXTRA[symbol] as u32)? as usize;
+                if distance > output.len() {
+                    return Err(invalid("distance too far back"));
+                }
+                let from = output.len() - distance;
+                for i in 0..len {
+                    output.push(output[from + i]);
+                }
+            }
+        }
+        if output.len() > limit {
+            return Err(invalid("decompressed message is too large"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Inflater;
+
+    // generated with zlib, using raw deflate and flushing with `Z_SYNC_FLUSH`
+
+    const MESSAGE: &str = r#"{"type":"channel","body":{"id":"foobar","type":"note","body":{"id":"8dhemt9ubf","text":"hello","user":{"id":"8dhe5zqidm","username":"alice","name":null,"emojis":[]}}}}"#;
+    const MESSAGE_COMPRESSED: [u8; 116] = [
+        0x5c, 0x8c, 0x31, 0x0e, 0xc2, 0x30, 0x10, 0x04, 0xff, 0xb2, 0x75, 0x5a, 0x24, 0xf0, 0x57,
+        0x10, 0x85, 0x1d, 0x5f, 0x64, 0xa3, 0xf3, 0x1d, 0x24, 0xb6, 0x44, 0x88, 0xf2, 0x77, 0xce,
+        0x22, 0x0d, 0x6c, 0xb7, 0xb3, 0xa3, 0xdd, 0x50, 0xd7, 0x07, 0xc1, 0x61, 0x4c, 0x5e, 0x84,
+        0x18, 0x03, 0x82, 0xc6, 0x15, 0x6e, 0x43, 0x8e, 0x86, 0x27, 0xd5, 0xe0, 0x67, 0xa3, 0x87,
+        0x26, 0x5a, 0xe9, 0xdf, 0x39, 0xc7, 0x44, 0xa5, 0x5e, 0x5a, 0x98, 0xba, 0x47, 0xaf, 0x6a,
+        0x2c, 0x11, 0xb3, 0x5a, 0x6d, 0x0b, 0xcd, 0x3f, 0xe2, 0xe9, 0xfd, 0xcc, 0xb1, 0x1c, 0x8b,
+        0xf8, 0xd2, 0x4f, 0x3d, 0xe7, 0xb1, 0xbf, 0x7e, 0xab, 0x34, 0xe6, 0x01, 0x54, 0xf4, 0x9e,
+        0x17, 0xb8, 0xeb, 0x6d, 0xb7, 0x7c, 0x00, 0x00, 0x00, 0xff, 0xff,
+    ];
+    /// `MESSAGE` compressed again with the same compressor, which refers to the first one.
+    const MESSAGE_COMPRESSED_AGAIN: [u8; 10] =
+        [0xaa, 0x1e, 0x1a, 0xce, 0x04, 0x00, 0x00, 0x00, 0xff, 0xff];
+
+    #[test]
+    fn test_fixed() {
+        let input = [
+            0xf2, 0x48, 0xcd, 0xc9, 0xc9, 0xd7, 0x51, 0xf0, 0x40, 0xa2, 0x14, 0x01, 0x00, 0x00,
+            0x00, 0xff, 0xff,
+        ];
+        let output = Inflater::default().inflate(&input, 1024).unwrap();
+        assert_eq!(output, b"Hello, Hello, Hello!");
+    }
+
+    #[test]
+    fn test_stored() {
+        let input = [
+            0x00, 0x07, 0x00, 0xf8, 0xff, 0x6d, 0x69, 0x73, 0x73, 0x6b, 0x65, 0x79, 0x00, 0x00,
+            0x00, 0xff, 0xff,
+        ];
+        let output = Inflater::default().inflate(&input, 1024).unwrap();
+        assert_eq!(output, b"misskey");
+    }
+
+    #[test]
+    fn test_dynamic() {
+        let output = Inflater::default()
+            .inflate(&MESSAGE_COMPRESSED, 1024)
+            .unwrap();
+        assert_eq!(output, MESSAGE.as_bytes());
+    }
+
+    #[test]
+    fn test_context_takeover() {
+        let mut inflater = Inflater::default();
+        inflater.inflate(&MESSAGE_COMPRESSED, 1024).unwrap();
+        let output = inflater.inflate(&MESSAGE_COMPRESSED_AGAIN, 1024).unwrap();
+        assert_eq!(output, MESSAGE.as_bytes());
+
+        inflater.reset();
+        assert!(inflater.inflate(&MESSAGE_COMPRESSED_AGAIN, 1024).is_err());
+    }
+
+    #[test]
+    fn test_too_large() {
+        let mut inflater = Inflater::default();
+        assert!(inflater.inflate(&MESSAGE_COMPRESSED, 100).is_err());
+    }
+}
@@ -0,0 +1,240 @@
+//! Connection that negotiates the extension on tokio.
+
+use std::convert::TryFrom;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use async_tungstenite::tungstenite::{
+    client::uri_mode,
+    error::{TlsError, UrlError},
+    handshake::client::Request,
+    http::header::{HeaderValue, SEC_WEBSOCKET_EXTENSIONS},
+    stream::Mode,
+    Error as WsError,
+};
+use futures_util::ready;
+use log::{debug, info};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::{ClientConfig, OwnedTrustAnchor, RootCertStore, ServerName};
+use tokio_rustls::TlsConnector;
+
+use super::frame::FrameDecoder;
+use super::DeflateConfig;
+use crate::runtime::WebSocketConnection;
+
+trait Io: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Io for T {}
+
+/// Stream that decompresses the messages read from `S`.
+struct InflateStream<S> {
+    inner: S,
+    decoder: FrameDecoder,
+    buffer: Box<[u8]>,
+    eof: bool,
+}
+
+impl<S> InflateStream<S> {
+    fn new(inner: S) -> InflateStream<S> {
+        InflateStream {
+            inner,
+            decoder: FrameDecoder::new(),
+            buffer: vec![0; 8 * 1024].into_boxed_slice(),
+            eof: false,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for InflateStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            let output = this.decoder.output();
+            if !output.is_empty() || this.eof {
+                let n = output.len().min(buf.remaining());
+                buf.put_slice(&output[..n]);
+                this.decoder.consume(n);
+                return Poll::Ready(Ok(()));
+            }
+
+            let mut read_buf = ReadBuf::new(&mut this.buffer);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut read_buf))?;
+            if read_buf.filled().is_empty() {
+                this.eof = true;
+                this.decoder.finish();
+            } else {
+                this.decoder.feed(read_buf.filled())?;
+            }
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for InflateStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+fn tls_connector() -> TlsConnector {
+    let mut root_store = RootCertStore::empty();
+    root_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|ta| {
+        OwnedTrustAnchor::from_subject_spki_name_constraints(
+            ta.subject,
+            ta.spki,
+            ta.name_constraints,
+        )
+    }));
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+    TlsConnector::from(Arc::new(config))
+}
+
+/// Opens a WebSocket connection offering the extension with `config`.
+pub(crate) async fn connect(
+    mut request: Request,
+    config: DeflateConfig,
+) -> Result<WebSocketConnection, WsError> {
+    let offer = HeaderValue::try_from(config.offer()).expect("offer must be a valid header value");
+    request
+        .headers_mut()
+        .insert(SEC_WEBSOCKET_EXTENSIONS, offer);
+
+    let mode = uri_mode(request.uri())?;
+    let host = request
+        .uri()
+        .host()
+        .ok_or(WsError::Url(UrlError::NoHostName))?;
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_owned();
+    let port = request.uri().port_u16().unwrap_or(match mode {
+        Mode::Plain => 80,
+        Mode::Tls => 443,
+    });
+
+    let socket = TcpStream::connect((host.as_str(), port)).await?;
+    let socket: Box<dyn Io> = match mode {
+        Mode::Plain => Box::new(socket),
+        Mode::Tls => {
+            let domain = ServerName::try_from(host.as_str())
+                .map_err(|_| WsError::Tls(TlsError::InvalidDnsName))?;
+            Box::new(tls_connector().connect(domain, socket).await?)
+        }
+    };
+
+    let (ws, _) =
+        async_tungstenite::tokio::client_async(request, InflateStream::new(socket)).await?;
+    match ws.get_ref().get_ref().decoder.is_accepted() {
+        Some(true) => debug!("permessage-deflate is negotiated"),
+        _ => info!("permessage-deflate is not accepted, falling back to uncompressed messages"),
+    }
+    Ok(Box::pin(ws))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::connect;
+    use crate::deflate::DeflateConfig;
+
+    use async_tungstenite::tungstenite::{
+        client::IntoClientRequest, handshake::derive_accept_key, Message as WsMessage,
+    };
+    use futures_util::stream::StreamExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// "Hello, Hello, Hello!" compressed with the trailer removed.
+    const COMPRESSED: [u8; 13] = [
+        0xf2, 0x48, 0xcd, 0xc9, 0xc9, 0xd7, 0x51, 0xf0, 0x40, 0xa2, 0x14, 0x01, 0x00,
+    ];
+
+    /// Accepts a connection, and sends a text message, compressed if `accept` is true.
+    ///
+    /// Returns the request headers received.
+    async fn serve(listener: TcpListener, accept: bool) -> String {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        while !request.ends_with(b"\r\n\r\n") {
+            request.push(socket.read_u8().await.unwrap());
+        }
+        let request = String::from_utf8(request).unwrap();
+        let key = request
+            .lines()
+            .find_map(|line| line.strip_prefix("Sec-WebSocket-Key: "))
+            .unwrap();
+
+        let mut response = format!(
+            "HTTP/1.1 101 Switching Protocols\r\n\
+             Connection: Upgrade\r\n\
+             Upgrade: websocket\r\n\
+             Sec-WebSocket-Accept: {}\r\n",
+            derive_accept_key(key.as_bytes())
+        )
+        .into_bytes();
+        if accept {
+            response.extend_from_slice(b"Sec-WebSocket-Extensions: permessage-deflate\r\n\r\n");
+            response.extend_from_slice(&[0xc1, COMPRESSED.len() as u8]);
+            response.extend_from_slice(&COMPRESSED);
+        } else {
+            response.extend_from_slice(b"\r\n");
+            response.extend_from_slice(&[0x81, 20]);
+            response.extend_from_slice(b"Hello, Hello, Hello!");
+        }
+        socket.write_all(&response).await.unwrap();
+        request
+    }
+
+    async fn receive_hello(accept: bool) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/streaming", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve(listener, accept));
+
+        let config = DeflateConfig {
+            server_max_window_bits: Some(12),
+            server_no_context_takeover: false,
+        };
+        let mut ws = connect(url.into_client_request().unwrap(), config)
+            .await
+            .unwrap();
+        assert_eq!(
+            ws.next().await.unwrap().unwrap(),
+            WsMessage::Text("Hello, Hello, Hello!".to_string())
+        );
+
+        let request = server.await.unwrap();
+        assert!(request.contains(
+            "sec-websocket-extensions: permessage-deflate; server_max_window_bits=12\r\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_compressed() {
+        receive_hello(true).await;
+    }
+
+    #[tokio::test]
+    async fn test_fallback() {
+        receive_hello(false).await;
+    }
+}
@@ -22,6 +22,21 @@ pub enum Error {
     /// Invalid header.
     #[error("Invalid header: {0}")]
     InvalidHeader(#[source] Arc<tungstenite::http::Error>),
+    /// The client is already closed.
+    #[error("websocket client is closed")]
+    Closed,
+    /// The stream lagged behind and the given number of messages are dropped.
+    ///
+    /// This is yielded from streams with [`OverflowPolicy::Lag`][`crate::OverflowPolicy::Lag`],
+    /// and they continue to be available after this.
+    #[error("stream lagged behind, {0} messages dropped")]
+    Lagged(u64),
+    /// No async runtime is available to run the client.
+    ///
+    /// Enable either `tokio-runtime` or `async-std-runtime` feature, or specify the runtime with
+    /// [`WebSocketClientBuilder::runtime`][`crate::WebSocketClientBuilder::runtime`].
+    #[error("no async runtime is available")]
+    NoRuntime,
 }
 
 impl From<Infallible> for Error {
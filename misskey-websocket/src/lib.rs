@@ -4,14 +4,27 @@
 //!
 //! - `tokio-runtime`: Use the [tokio](https://tokio.rs) runtime. Enabled by default.
 //! - `async-std-runtime`: Use the [async-std](https://async.rs) runtime.
+//!
+//! These features only provide the default [`Runtime`][`runtime::Runtime`]. Library crates can
+//! depend on this crate with `default-features = false` to leave the choice to the application,
+//! and you can run the client on other executors by specifying your own runtime with
+//! [`WebSocketClientBuilder::runtime`]. See the [`runtime`] module for details.
 #![warn(missing_docs)]
 
 mod broker;
 mod channel;
 mod client;
+pub mod deflate;
 mod error;
 mod model;
+pub mod pool;
+pub mod record;
+pub mod replay;
+pub mod runtime;
+mod serialized;
+pub mod stats;
 
-pub use broker::{ReconnectCondition, ReconnectConfig};
+pub use broker::{BufferConfig, OverflowPolicy, ReconnectCondition, ReconnectConfig};
 pub use client::{builder::WebSocketClientBuilder, stream, WebSocketClient};
 pub use error::Error;
+pub use serialized::SerializedRequest;
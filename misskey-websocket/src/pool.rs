@@ -0,0 +1,676 @@
+//! Pool of WebSocket connections.
+//!
+//! [`WebSocketPool`] spreads the subscriptions across multiple connections, each of which is
+//! served by its own [`WebSocketClient`]. This is useful when you have a lot of subscriptions,
+//! since every message on a connection is processed by a single background task.
+
+use std::fmt::{self, Debug};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::client::{builder::WebSocketClientBuilder, WebSocketClient};
+use crate::error::{Error, Result};
+use crate::serialized::SerializedRequest;
+
+use futures_util::{
+    future::{BoxFuture, FutureExt},
+    lock::Mutex,
+    sink::{Sink, SinkExt},
+    stream::{Stream, StreamExt},
+};
+use log::info;
+use misskey_core::streaming::{
+    BroadcastEvent, BroadcastStream, ChannelStream, ConnectChannelRequest, StreamingClient,
+    SubNoteEvent, SubNoteStream,
+};
+use serde_json::Value;
+
+/// Subscription to be placed on one of the connections.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Subscription<'a> {
+    /// Connection to the channel.
+    Channel {
+        /// The name of the channel.
+        name: &'static str,
+        /// The parameters used to connect to the channel.
+        params: &'a Value,
+    },
+    /// Subscription to the note.
+    SubNote {
+        /// The ID of the note.
+        note_id: &'a str,
+    },
+    /// Broadcast stream.
+    Broadcast {
+        /// The type of the broadcast event.
+        type_: &'static str,
+    },
+}
+
+/// Strategy to choose the connection for each subscription.
+pub trait Placement: Send + Sync + 'static {
+    /// Returns the index of the connection to place `subscription` on.
+    ///
+    /// `loads[i]` is the number of active subscriptions on the `i`-th connection. The index is
+    /// wrapped around if it is out of range.
+    fn place(&self, subscription: Subscription<'_>, loads: &[usize]) -> usize;
+}
+
+/// [`Placement`] that chooses the connection with the fewest subscriptions.
+///
+/// This is used by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LeastLoaded;
+
+impl Placement for LeastLoaded {
+    fn place(&self, _subscription: Subscription<'_>, loads: &[usize]) -> usize {
+        loads
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, load)| **load)
+            .map_or(0, |(index, _)| index)
+    }
+}
+
+/// [`Placement`] that chooses the connections in turn.
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    next: AtomicUsize,
+}
+
+impl Placement for RoundRobin {
+    fn place(&self, _subscription: Subscription<'_>, loads: &[usize]) -> usize {
+        self.next.fetch_add(1, Ordering::Relaxed) % loads.len()
+    }
+}
+
+/// Builder for [`WebSocketPool`].
+#[derive(Clone)]
+pub struct WebSocketPoolBuilder {
+    client: WebSocketClientBuilder,
+    size: usize,
+    placement: Arc<dyn Placement>,
+}
+
+impl Debug for WebSocketPoolBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WebSocketPoolBuilder")
+            .field("client", &self.client)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl WebSocketPoolBuilder {
+    /// Creates a new builder instance that opens the connections with `client`.
+    ///
+    /// The pool has 2 connections and uses [`LeastLoaded`] by default.
+    pub fn new(client: WebSocketClientBuilder) -> Self {
+        WebSocketPoolBuilder {
+            client,
+            size: 2,
+            placement: Arc::new(LeastLoaded),
+        }
+    }
+
+    /// Sets the number of the connections.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn size(&mut self, size: usize) -> &mut Self {
+        assert!(size > 0, "pool size must be greater than zero");
+        self.size = size;
+        self
+    }
+
+    /// Sets the strategy to choose the connection for each subscription.
+    pub fn placement<P: Placement>(&mut self, placement: P) -> &mut Self {
+        self.placement = Arc::new(placement);
+        self
+    }
+
+    /// Finish this builder instance and open the connections.
+    pub async fn connect(&self) -> Result<WebSocketPool> {
+        let mut slots = Vec::with_capacity(self.size);
+        for _ in 0..self.size {
+            slots.push(Slot {
+                client: Mutex::new(self.client.connect().await?),
+                load: Arc::new(AtomicUsize::new(0)),
+            });
+        }
+
+        Ok(WebSocketPool {
+            inner: Arc::new(PoolInner {
+                builder: self.client.clone(),
+                slots,
+                placement: Arc::clone(&self.placement),
+                closed: AtomicBool::new(false),
+            }),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Slot {
+    client: Mutex<WebSocketClient>,
+    load: Arc<AtomicUsize>,
+}
+
+struct PoolInner {
+    builder: WebSocketClientBuilder,
+    slots: Vec<Slot>,
+    placement: Arc<dyn Placement>,
+    closed: AtomicBool,
+}
+
+/// Decrements the load of the connection on drop.
+#[derive(Debug)]
+struct LoadGuard(Arc<AtomicUsize>);
+
+impl LoadGuard {
+    fn new(load: &Arc<AtomicUsize>) -> LoadGuard {
+        load.fetch_add(1, Ordering::Relaxed);
+        LoadGuard(Arc::clone(load))
+    }
+}
+
+impl Drop for LoadGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl PoolInner {
+    /// Chooses the connection for `subscription`, reconnecting it if it is no longer available.
+    async fn acquire(
+        &self,
+        subscription: Subscription<'_>,
+    ) -> Result<(WebSocketClient, LoadGuard)> {
+        if self.closed.load(Ordering::Acquire) {
+            return Err(Error::Closed);
+        }
+
+        let loads: Vec<_> = self
+            .slots
+            .iter()
+            .map(|slot| slot.load.load(Ordering::Relaxed))
+            .collect();
+        let index = self.placement.place(subscription, &loads) % self.slots.len();
+        let slot = &self.slots[index];
+
+        let mut client = slot.client.lock().await;
+        if client.is_closed() {
+            info!("pool: connection #{} is closed, reconnecting", index);
+            *client = self.builder.connect().await?;
+        }
+
+        Ok((client.clone(), LoadGuard::new(&slot.load)))
+    }
+}
+
+/// Owned version of [`Subscription`].
+#[derive(Debug, Clone)]
+enum Target {
+    Channel { name: &'static str, params: Value },
+    SubNote { note_id: String },
+    Broadcast { type_: &'static str },
+}
+
+impl Target {
+    fn as_subscription(&self) -> Subscription<'_> {
+        match self {
+            Target::Channel { name, params } => Subscription::Channel { name, params },
+            Target::SubNote { note_id } => Subscription::SubNote { note_id },
+            Target::Broadcast { type_ } => Subscription::Broadcast { type_ },
+        }
+    }
+}
+
+type Subscribe<S> = Arc<dyn Fn(&WebSocketClient) -> BoxFuture<'static, Result<S>> + Send + Sync>;
+
+struct Active<S> {
+    stream: S,
+    client: WebSocketClient,
+    _load: LoadGuard,
+}
+
+enum State<S> {
+    Active(Active<S>),
+    Subscribing(BoxFuture<'static, Result<Active<S>>>),
+    Terminated,
+}
+
+/// Stream that moves to another connection when the connection it is on is lost.
+struct PoolStream<S> {
+    pool: Arc<PoolInner>,
+    target: Target,
+    subscribe: Subscribe<S>,
+    state: State<S>,
+}
+
+impl<S: Send + 'static> PoolStream<S> {
+    async fn subscribe(
+        pool: Arc<PoolInner>,
+        target: Target,
+        subscribe: Subscribe<S>,
+    ) -> Result<PoolStream<S>> {
+        let active =
+            PoolStream::subscribe_active(Arc::clone(&pool), target.clone(), Arc::clone(&subscribe))
+                .await?;
+        Ok(PoolStream {
+            pool,
+            target,
+            subscribe,
+            state: State::Active(active),
+        })
+    }
+
+    fn subscribe_active(
+        pool: Arc<PoolInner>,
+        target: Target,
+        subscribe: Subscribe<S>,
+    ) -> BoxFuture<'static, Result<Active<S>>> {
+        Box::pin(async move {
+            let (client, load) = pool.acquire(target.as_subscription()).await?;
+            let stream = subscribe(&client).await?;
+            Ok(Active {
+                stream,
+                client,
+                _load: load,
+            })
+        })
+    }
+
+    fn resubscribe(&mut self) {
+        info!("pool: connection lost, moving {:?}", self.target);
+        self.state = State::Subscribing(PoolStream::subscribe_active(
+            Arc::clone(&self.pool),
+            self.target.clone(),
+            Arc::clone(&self.subscribe),
+        ));
+    }
+
+    /// Drives the pending subscription, and returns the error if it failed.
+    fn poll_subscribing(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if let State::Subscribing(future) = &mut self.state {
+            match futures_util::ready!(future.poll_unpin(cx)) {
+                Ok(active) => self.state = State::Active(active),
+                Err(e) => {
+                    self.state = State::Terminated;
+                    return Poll::Ready(Err(e));
+                }
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S, T> Stream for PoolStream<S>
+where
+    S: Stream<Item = Result<T>> + Send + Unpin + 'static,
+{
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<T>>> {
+        loop {
+            if let Err(e) = futures_util::ready!(self.poll_subscribing(cx)) {
+                return Poll::Ready(Some(Err(e)));
+            }
+
+            let active = match &mut self.state {
+                State::Active(active) => active,
+                State::Subscribing(_) => continue,
+                State::Terminated => return Poll::Ready(None),
+            };
+
+            match futures_util::ready!(active.stream.poll_next_unpin(cx)) {
+                Some(Err(_))
+                    if active.client.is_closed() && !self.pool.closed.load(Ordering::Acquire) =>
+                {
+                    self.resubscribe()
+                }
+                None => {
+                    self.state = State::Terminated;
+                    return Poll::Ready(None);
+                }
+                item => return Poll::Ready(item),
+            }
+        }
+    }
+}
+
+impl<S, O> Sink<O> for PoolStream<S>
+where
+    S: Sink<O, Error = Error> + Send + Unpin + 'static,
+{
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        futures_util::ready!(self.poll_subscribing(cx))?;
+        match &mut self.state {
+            State::Active(active) => active.stream.poll_ready_unpin(cx),
+            State::Subscribing(_) => Poll::Pending,
+            State::Terminated => Poll::Ready(Err(Error::Closed)),
+        }
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: O) -> Result<()> {
+        match &mut self.state {
+            State::Active(active) => active.stream.start_send_unpin(item),
+            _ => Err(Error::Closed),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        match &mut self.state {
+            State::Active(active) => active.stream.poll_flush_unpin(cx),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        match &mut self.state {
+            State::Active(active) => active.stream.poll_close_unpin(cx),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+/// Streaming client that spreads the subscriptions across multiple connections.
+///
+/// Each subscription is placed on one of the connections according to the [`Placement`]
+/// strategy. When a connection is lost (i.e. the [`WebSocketClient`] gives up reconnecting),
+/// the subscriptions on it are placed again and the connection is re-established as needed,
+/// so that the streams returned from the pool continue to work.
+///
+/// # Example
+///
+/// ```no_run
+/// use misskey_websocket::{pool::WebSocketPoolBuilder, WebSocketClientBuilder};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut client = WebSocketClientBuilder::with_host("your.instance.example");
+/// client.token("API_TOKEN");
+///
+/// let mut pool = WebSocketPoolBuilder::new(client);
+/// pool.size(4);
+/// let pool = pool.connect().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct WebSocketPool {
+    inner: Arc<PoolInner>,
+}
+
+impl Debug for WebSocketPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WebSocketPool")
+            .field("slots", &self.inner.slots)
+            .finish()
+    }
+}
+
+impl WebSocketPool {
+    /// Creates a new builder instance that opens the connections with `client`.
+    ///
+    /// This function is identical to [`WebSocketPoolBuilder::new`].
+    pub fn builder(client: WebSocketClientBuilder) -> WebSocketPoolBuilder {
+        WebSocketPoolBuilder::new(client)
+    }
+
+    /// Returns the number of the connections.
+    pub fn size(&self) -> usize {
+        self.inner.slots.len()
+    }
+
+    /// Returns the number of active subscriptions on each connection.
+    pub fn loads(&self) -> Vec<usize> {
+        self.inner
+            .slots
+            .iter()
+            .map(|slot| slot.load.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    /// Closes all connections gracefully.
+    ///
+    /// See [`WebSocketClient::close`] for details. The streams obtained from the pool are
+    /// terminated, and any other operation on the pool fails with [`Error::Closed`].
+    pub async fn close(&self) -> Result<()> {
+        self.inner.closed.store(true, Ordering::Release);
+        for slot in &self.inner.slots {
+            let client = slot.client.lock().await.clone();
+            client.close().await?;
+        }
+        Ok(())
+    }
+}
+
+impl StreamingClient for WebSocketPool {
+    type Error = Error;
+
+    fn subnote<E: SubNoteEvent>(
+        &self,
+        note_id: String,
+    ) -> BoxFuture<'_, Result<SubNoteStream<'_, E, Error>>> {
+        let target = Target::SubNote {
+            note_id: note_id.clone(),
+        };
+        let subscribe: Subscribe<_> =
+            Arc::new(move |client: &WebSocketClient| client.subnote::<E, _>(note_id.clone()));
+        PoolStream::subscribe(Arc::clone(&self.inner), target, subscribe)
+            .map(|result| result.map(StreamExt::boxed))
+            .boxed()
+    }
+
+    fn channel<R: ConnectChannelRequest>(
+        &self,
+        request: R,
+    ) -> BoxFuture<'_, Result<ChannelStream<'_, R, Error>>> {
+        let params = match SerializedRequest::new(request) {
+            Ok(request) => request.params().clone(),
+            Err(e) => return Box::pin(futures_util::future::ready(Err(e.into()))),
+        };
+        let target = Target::Channel {
+            name: R::NAME,
+            params: params.clone(),
+        };
+        // capturing only the parameters, since `R` may not be `'static`
+        let subscribe: Subscribe<_> = Arc::new(move |client: &WebSocketClient| {
+            client.channel(SerializedRequest::<R>::from_params(params.clone()))
+        });
+        PoolStream::subscribe(Arc::clone(&self.inner), target, subscribe)
+            .map(|result| result.map(|stream| Box::pin(stream) as ChannelStream<'_, R, Error>))
+            .boxed()
+    }
+
+    fn broadcast<E: BroadcastEvent>(&self) -> BoxFuture<'_, Result<BroadcastStream<'_, E, Error>>> {
+        let target = Target::Broadcast { type_: E::TYPE };
+        let subscribe: Subscribe<_> = Arc::new(|client: &WebSocketClient| client.broadcast::<E>());
+        PoolStream::subscribe(Arc::clone(&self.inner), target, subscribe)
+            .map(|result| result.map(StreamExt::boxed))
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LeastLoaded, Placement, RoundRobin, Subscription};
+
+    const SUBSCRIPTION: Subscription<'static> = Subscription::Broadcast {
+        type_: "emojiAdded",
+    };
+
+    #[test]
+    fn test_least_loaded() {
+        assert_eq!(LeastLoaded.place(SUBSCRIPTION, &[2, 0, 1]), 1);
+        assert_eq!(LeastLoaded.place(SUBSCRIPTION, &[1, 1, 1]), 0);
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let placement = RoundRobin::default();
+        let indices: Vec<_> = (0..4)
+            .map(|_| placement.place(SUBSCRIPTION, &[0, 0, 0]))
+            .collect();
+        assert_eq!(indices, vec![0, 1, 2, 0]);
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    mod reconnect {
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+        use std::time::Duration;
+
+        use crate::pool::{RoundRobin, WebSocketPool, WebSocketPoolBuilder};
+        use crate::runtime::{Runtime, WebSocketConnection};
+        use crate::WebSocketClientBuilder;
+
+        use async_tungstenite::tungstenite::{
+            error::Result as WsResult, handshake::client::Request, Error as WsError,
+            Message as WsMessage,
+        };
+        use futures_channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+        use futures_util::{
+            future::{self, BoxFuture},
+            sink::Sink,
+            stream::{Stream, StreamExt},
+        };
+        use misskey_core::streaming::{BroadcastEvent, StreamingClient};
+        use serde::Deserialize;
+
+        type Server = UnboundedSender<WsResult<WsMessage>>;
+
+        /// Connection that receives the messages from the server and discards the sent ones.
+        struct MockConnection(UnboundedReceiver<WsResult<WsMessage>>);
+
+        impl Stream for MockConnection {
+            type Item = WsResult<WsMessage>;
+
+            fn poll_next(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Option<Self::Item>> {
+                self.0.poll_next_unpin(cx)
+            }
+        }
+
+        impl Sink<WsMessage> for MockConnection {
+            type Error = WsError;
+
+            fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<WsResult<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn start_send(self: Pin<&mut Self>, _: WsMessage) -> WsResult<()> {
+                Ok(())
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<WsResult<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<WsResult<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        /// Runtime that connects to in-memory servers, whose ends are passed to `servers`.
+        struct MockRuntime {
+            servers: UnboundedSender<Server>,
+        }
+
+        impl Runtime for MockRuntime {
+            fn spawn(&self, future: BoxFuture<'static, ()>) {
+                tokio::task::spawn(future);
+            }
+
+            fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+                Box::pin(tokio::time::sleep(duration))
+            }
+
+            fn connect(
+                &self,
+                _: Request,
+            ) -> BoxFuture<'static, Result<WebSocketConnection, WsError>> {
+                let (server, rx) = mpsc::unbounded();
+                self.servers.unbounded_send(server).unwrap();
+                Box::pin(future::ready(Ok(
+                    Box::pin(MockConnection(rx)) as WebSocketConnection
+                )))
+            }
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Event {
+            name: String,
+        }
+
+        impl BroadcastEvent for Event {
+            const TYPE: &'static str = "emojiAdded";
+        }
+
+        /// Sends the event from `server` once the stream is subscribed on the `index`-th connection.
+        async fn emit(pool: &WebSocketPool, index: usize, server: &Server, name: &str) {
+            let client = pool.inner.slots[index].client.lock().await.clone();
+            // the subscription is processed by the time the stats are returned
+            let stats = client.stats().await.unwrap();
+            assert_eq!(stats.broadcasts[0].subscribers, 1);
+
+            let message = format!(r#"{{"type":"emojiAdded","body":{{"name":"{}"}}}}"#, name);
+            server.unbounded_send(Ok(WsMessage::Text(message))).unwrap();
+        }
+
+        fn event(name: &str) -> Event {
+            Event {
+                name: name.to_string(),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_move_to_another_connection() {
+            let (servers_tx, mut servers) = mpsc::unbounded();
+            let mut client = WebSocketClientBuilder::new("wss://example.com/streaming");
+            client.auto_reconnect(false).runtime(MockRuntime {
+                servers: servers_tx,
+            });
+            let pool = WebSocketPoolBuilder::new(client)
+                .placement(RoundRobin::default())
+                .connect()
+                .await
+                .unwrap();
+            let server0 = servers.next().await.unwrap();
+            let server1 = servers.next().await.unwrap();
+
+            let mut stream = pool.broadcast::<Event>().await.unwrap();
+            assert_eq!(pool.loads(), vec![1, 0]);
+            emit(&pool, 0, &server0, "a").await;
+            assert_eq!(stream.next().await.unwrap().unwrap(), event("a"));
+
+            // the stream moves to the next connection when the connection is lost
+            drop(server0);
+            let (item, ()) = future::join(stream.next(), async {
+                while pool.loads() != vec![0, 1] {
+                    tokio::task::yield_now().await;
+                }
+                emit(&pool, 1, &server1, "b").await;
+            })
+            .await;
+            assert_eq!(item.unwrap().unwrap(), event("b"));
+            assert!(pool.inner.slots[0].client.lock().await.is_closed());
+
+            // the lost connection is re-established for the next subscription placed on it
+            let mut stream = pool.broadcast::<Event>().await.unwrap();
+            assert_eq!(pool.loads(), vec![1, 1]);
+            let server0 = servers.next().await.unwrap();
+            emit(&pool, 0, &server0, "c").await;
+            assert_eq!(stream.next().await.unwrap().unwrap(), event("c"));
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Recording of the streaming traffic.
+//!
+//! [`WebSocketClientBuilder::record`][`crate::WebSocketClientBuilder::record`] makes the client
+//! write the messages it exchanges with the server to the given writer, in the [JSON Lines]
+//! format. Each line is a [`Record`], which looks like this:
+//!
+//! ```json
+//! {"time":"2021-01-01T00:00:00.123Z","direction":"incoming","message":{"type":"channel","body":{...}}}
+//! ```
+//!
+//! The recorded file can be replayed with [`ReplayClient`][`crate::replay::ReplayClient`].
+//!
+//! [JSON Lines]: https://jsonlines.org
+
+use std::fmt::{self, Debug};
+use std::io::{BufWriter, Write};
+use std::iter;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Direction of the recorded message.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum Direction {
+    /// The message is received from the server.
+    Incoming,
+    /// The message is sent to the server.
+    Outgoing,
+}
+
+/// A line in the recorded traffic.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Record {
+    /// The time at which the message is sent or received.
+    pub time: DateTime<Utc>,
+    /// Whether the message is sent or received.
+    pub direction: Direction,
+    /// The raw message.
+    pub message: Value,
+}
+
+/// Shared handle to the writer that the traffic is recorded to.
+///
+/// The records are written on a dedicated thread not to block the executor with the writes.
+#[derive(Clone)]
+pub(crate) struct SharedRecorder(Sender<Record>);
+
+impl Debug for SharedRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SharedRecorder").finish()
+    }
+}
+
+impl SharedRecorder {
+    pub fn new<W: Write + Send + 'static>(writer: W) -> SharedRecorder {
+        let (tx, rx) = mpsc::channel();
+        thread::Builder::new()
+            .name("misskey-websocket-recorder".to_string())
+            .spawn(move || write_records(BufWriter::new(writer), rx))
+            .expect("failed to spawn the recorder thread");
+        SharedRecorder(tx)
+    }
+
+    /// Records the message in `text`, which is the raw text frame.
+    ///
+    /// Failures are only logged not to disturb the connection.
+    pub fn record(&self, direction: Direction, text: &str) {
+        let message = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("recorder: skipped malformed message: {}", e);
+                return;
+            }
+        };
+        let record = Record {
+            time: Utc::now(),
+            direction,
+            message,
+        };
+
+        if self.0.send(record).is_err() {
+            warn!("recorder: the writer thread has exited");
+        }
+    }
+}
+
+/// Writes the records until all the [`SharedRecorder`]s are dropped.
+///
+/// The writer is flushed each time the pending records are written out, so that bursts of
+/// messages are written at once while the file is kept up to date.
+fn write_records<W: Write>(mut writer: BufWriter<W>, rx: Receiver<Record>) {
+    while let Ok(record) = rx.recv() {
+        let result = iter::once(record)
+            .chain(rx.try_iter())
+            .try_for_each(|record| {
+                serde_json::to_writer(&mut writer, &record)?;
+                writer.write_all(b"\n")
+            })
+            .and_then(|()| writer.flush());
+        if let Err(e) = result {
+            warn!("recorder: failed to write a record: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+    use std::sync::mpsc::{self, Sender};
+
+    use super::{Direction, SharedRecorder};
+    use crate::replay::ReplayClient;
+
+    use futures_util::stream::TryStreamExt;
+    use misskey_core::streaming::{ConnectChannelRequest, StreamingClient};
+    use serde::Serialize;
+    use serde_json::{json, Value};
+
+    /// Writer that sends the written bytes, which are all received when the writer is dropped.
+    struct ChannelWriter(Sender<Vec<u8>>);
+
+    impl Write for ChannelWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.send(buf.to_vec()).unwrap();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Request {
+        list_id: &'static str,
+    }
+
+    impl ConnectChannelRequest for Request {
+        type Incoming = Value;
+        type Outgoing = Value;
+
+        const NAME: &'static str = "userList";
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn record_and_replay() {
+        let connect = json!({"type": "connect", "body": {"id": "a", "channel": "userList", "params": {"list_id": "x"}, "pong": true}});
+        let message =
+            json!({"type": "channel", "body": {"id": "a", "type": "note", "body": {"n": 1}}});
+
+        let (tx, rx) = mpsc::channel();
+        let recorder = SharedRecorder::new(ChannelWriter(tx));
+        recorder.record(Direction::Outgoing, &connect.to_string());
+        recorder.record(Direction::Incoming, "not a json");
+        recorder.record(Direction::Incoming, &message.to_string());
+        // the writer thread exits and drops the writer after all the recorders are dropped
+        drop(recorder);
+        let output: Vec<u8> = rx.iter().flatten().collect();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 2);
+        assert!(output.ends_with('\n'));
+
+        let client = ReplayClient::from_reader(output.as_bytes())
+            .unwrap()
+            .immediate();
+        let records = client.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].direction, Direction::Outgoing);
+        assert_eq!(records[0].message, connect);
+        assert_eq!(records[1].direction, Direction::Incoming);
+        assert_eq!(records[1].message, message);
+        assert!(records[0].time <= records[1].time);
+
+        let stream = client.channel(Request { list_id: "x" }).await.unwrap();
+        let messages: Vec<Value> = stream.try_collect().await.unwrap();
+        assert_eq!(messages, vec![json!({"type": "note", "body": {"n": 1}})]);
+    }
+}
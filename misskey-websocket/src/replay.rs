@@ -0,0 +1,463 @@
+//! Replay of the recorded streaming traffic.
+//!
+//! [`ReplayClient`] implements [`StreamingClient`] over the traffic recorded with
+//! [`WebSocketClientBuilder::record`][`crate::WebSocketClientBuilder::record`], so that code
+//! written against [`StreamingClient`] can be re-run against the exact messages it received.
+
+use std::fmt::{self, Debug};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::model::incoming::IncomingMessageType;
+use crate::record::{Direction, Record};
+use crate::runtime::{Runtime, SharedRuntime};
+
+use chrono::{DateTime, Utc};
+use futures_util::{
+    future::{self, BoxFuture},
+    sink::Sink,
+    stream::{self, BoxStream, Stream, StreamExt},
+};
+use misskey_core::streaming::{
+    BroadcastEvent, BroadcastStream, ChannelStream, ConnectChannelRequest, StreamingClient,
+    SubNoteEvent, SubNoteStream,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pace {
+    Scaled(f64),
+    Immediate,
+}
+
+/// Client that replays the recorded traffic.
+///
+/// Each stream returned from the client replays the messages addressed to it from the
+/// beginning of the recording, keeping the intervals between the messages as recorded
+/// (or scaled with [`speed`][`ReplayClient::speed`]). The streams end at the end of the
+/// recording, and messages sent to the channels are discarded.
+///
+/// Channels are matched with the recorded connections by their names and parameters, and
+/// note subscriptions are matched by the note IDs.
+///
+/// # Example
+///
+/// ```no_run
+/// # use misskey_websocket::replay::ReplayClient;
+/// use futures_util::stream::TryStreamExt;
+/// use misskey_api::streaming::channel::main;
+/// use misskey_core::streaming::StreamingClient;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ReplayClient::open("traffic.jsonl")?.speed(10.0);
+///
+/// let mut stream = client.channel(main::Request::default()).await?;
+/// while let Some(event) = stream.try_next().await? {
+///     println!("{:?}", event);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ReplayClient {
+    records: Arc<[Record]>,
+    pace: Pace,
+    runtime: Option<SharedRuntime>,
+}
+
+impl Debug for ReplayClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReplayClient")
+            .field("records", &self.records.len())
+            .field("pace", &self.pace)
+            .finish()
+    }
+}
+
+impl ReplayClient {
+    /// Creates a client that replays the given records.
+    ///
+    /// The messages are replayed in real time by default.
+    pub fn from_records<I>(records: I) -> ReplayClient
+    where
+        I: IntoIterator<Item = Record>,
+    {
+        ReplayClient {
+            records: records.into_iter().collect(),
+            pace: Pace::Scaled(1.0),
+            runtime: SharedRuntime::from_features(),
+        }
+    }
+
+    /// Creates a client that replays the records read from `reader`.
+    ///
+    /// Blank lines are skipped, and malformed lines result in [`io::ErrorKind::InvalidData`].
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<ReplayClient> {
+        let mut records = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record = serde_json::from_str(&line).map_err(|e| {
+                let message = format!("malformed record at line {}: {}", index + 1, e);
+                io::Error::new(io::ErrorKind::InvalidData, message)
+            })?;
+            records.push(record);
+        }
+
+        Ok(ReplayClient::from_records(records))
+    }
+
+    /// Creates a client that replays the records in the file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<ReplayClient> {
+        ReplayClient::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Replays the messages `factor` times faster than recorded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is not a positive finite number.
+    pub fn speed(mut self, factor: f64) -> Self {
+        assert!(
+            factor.is_finite() && factor > 0.0,
+            "replay speed must be a positive finite number"
+        );
+        self.pace = Pace::Scaled(factor);
+        self
+    }
+
+    /// Replays the messages as fast as possible, ignoring the recorded intervals.
+    pub fn immediate(mut self) -> Self {
+        self.pace = Pace::Immediate;
+        self
+    }
+
+    /// Specifies the runtime used to wait between the messages.
+    ///
+    /// The runtime enabled by the feature flags is used by default.
+    pub fn runtime<R: Runtime>(mut self, runtime: R) -> Self {
+        self.runtime = Some(SharedRuntime::new(runtime));
+        self
+    }
+
+    /// Returns the records to be replayed.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    fn incoming(&self) -> impl Iterator<Item = (DateTime<Utc>, IncomingMessageType, &Value)> {
+        self.records
+            .iter()
+            .filter(|record| record.direction == Direction::Incoming)
+            .filter_map(|record| {
+                let type_ = record.message.get("type")?;
+                let type_ = serde_json::from_value(type_.clone()).ok()?;
+                Some((record.time, type_, record.message.get("body")?))
+            })
+    }
+
+    /// Collects the recorded IDs of outgoing messages of the given type that satisfy `pred`.
+    fn outgoing_ids<F>(&self, type_: &str, pred: F) -> Vec<&Value>
+    where
+        F: Fn(&Value) -> bool,
+    {
+        self.records
+            .iter()
+            .filter(|record| record.direction == Direction::Outgoing)
+            .filter(|record| record.message.get("type").and_then(Value::as_str) == Some(type_))
+            .filter_map(|record| record.message.get("body"))
+            .filter(|body| pred(body))
+            .filter_map(|body| body.get("id"))
+            .collect()
+    }
+
+    /// Returns a stream that yields `items` at the recorded timing.
+    fn replay<T>(&self, items: Vec<(DateTime<Utc>, Value)>) -> Result<BoxStream<'static, Result<T>>>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        let values = match (self.pace, self.records.first()) {
+            (Pace::Scaled(scale), Some(origin)) => {
+                let runtime = self.runtime.clone().ok_or(Error::NoRuntime)?;
+                let origin = origin.time;
+                let start = Instant::now();
+                stream::iter(items)
+                    .then(move |(time, value)| {
+                        let offset = (time - origin).to_std().unwrap_or_default();
+                        let deadline =
+                            start + Duration::from_secs_f64(offset.as_secs_f64() / scale);
+                        let sleep =
+                            runtime.sleep(deadline.saturating_duration_since(Instant::now()));
+                        async move {
+                            sleep.await;
+                            value
+                        }
+                    })
+                    .boxed()
+            }
+            _ => stream::iter(items).map(|(_, value)| value).boxed(),
+        };
+        Ok(values
+            .map(|value| Ok(serde_json::from_value(value)?))
+            .boxed())
+    }
+}
+
+/// Removes the `id` field from the message body.
+fn without_id(body: &Value) -> Value {
+    let mut map: Map<String, Value> = body.as_object().cloned().unwrap_or_default();
+    map.remove("id");
+    Value::Object(map)
+}
+
+/// Stream for the [`channel`][`StreamingClient::channel`] method, which discards sent messages.
+struct ReplayChannel<I, O> {
+    stream: BoxStream<'static, Result<I>>,
+    _marker: PhantomData<fn(O)>,
+}
+
+impl<I, O> Stream for ReplayChannel<I, O> {
+    type Item = Result<I>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<I>>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+impl<I, O> Sink<O> for ReplayChannel<I, O> {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, _item: O) -> Result<()> {
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl StreamingClient for ReplayClient {
+    type Error = Error;
+
+    fn subnote<E: SubNoteEvent>(
+        &self,
+        note_id: String,
+    ) -> BoxFuture<'_, Result<SubNoteStream<'_, E, Error>>> {
+        let note_id = Value::String(note_id);
+        let items = self
+            .incoming()
+            .filter(|(_, type_, body)| {
+                *type_ == IncomingMessageType::NoteUpdated && body.get("id") == Some(&note_id)
+            })
+            .map(|(time, _, body)| (time, without_id(body)))
+            .collect();
+        Box::pin(future::ready(self.replay(items)))
+    }
+
+    fn channel<R: ConnectChannelRequest>(
+        &self,
+        request: R,
+    ) -> BoxFuture<'_, Result<ChannelStream<'_, R, Error>>> {
+        let params = match serde_json::to_value(request) {
+            Ok(params) => params,
+            Err(e) => return Box::pin(future::ready(Err(e.into()))),
+        };
+        let ids = self.outgoing_ids("connect", |body| {
+            body.get("channel").and_then(Value::as_str) == Some(R::NAME)
+                && body.get("params") == Some(&params)
+        });
+        let items = self
+            .incoming()
+            .filter(|(_, type_, body)| {
+                *type_ == IncomingMessageType::Channel
+                    && matches!(body.get("id"), Some(id) if ids.contains(&id))
+            })
+            .map(|(time, _, body)| (time, without_id(body)))
+            .collect();
+        let result = self.replay(items).map(|stream| {
+            Box::pin(ReplayChannel {
+                stream,
+                _marker: PhantomData,
+            }) as ChannelStream<'_, R, Error>
+        });
+        Box::pin(future::ready(result))
+    }
+
+    fn broadcast<E: BroadcastEvent>(&self) -> BoxFuture<'_, Result<BroadcastStream<'_, E, Error>>> {
+        let items = self
+            .incoming()
+            .filter(|(_, type_, _)| *type_ == IncomingMessageType::Other(E::TYPE.to_string()))
+            .map(|(time, _, body)| (time, body.clone()))
+            .collect();
+        Box::pin(future::ready(self.replay(items)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReplayClient;
+    use crate::record::{Direction, Record};
+
+    use chrono::{Duration, Utc};
+    use futures_util::stream::TryStreamExt;
+    use misskey_core::streaming::{
+        BroadcastEvent, ConnectChannelRequest, StreamingClient, SubNoteEvent,
+    };
+    use serde::{Deserialize, Serialize};
+    use serde_json::{json, Value};
+
+    #[derive(Serialize)]
+    struct Request {
+        list_id: &'static str,
+    }
+
+    impl ConnectChannelRequest for Request {
+        type Incoming = Value;
+        type Outgoing = Value;
+
+        const NAME: &'static str = "userList";
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Event {
+        name: String,
+    }
+
+    impl BroadcastEvent for Event {
+        const TYPE: &'static str = "emojiAdded";
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct NoteEvent {
+        #[serde(rename = "type")]
+        type_: String,
+    }
+
+    impl SubNoteEvent for NoteEvent {}
+
+    fn records() -> Vec<Record> {
+        let origin = Utc::now();
+        let record = |millis, direction, message| Record {
+            time: origin + Duration::milliseconds(millis),
+            direction,
+            message,
+        };
+        let channel = |id, n| json!({"type": "channel", "body": {"id": id, "type": "note", "body": {"n": n}}});
+
+        vec![
+            record(
+                0,
+                Direction::Outgoing,
+                json!({"type": "connect", "body": {"id": "a", "channel": "userList", "params": {"list_id": "x"}, "pong": true}}),
+            ),
+            record(
+                0,
+                Direction::Outgoing,
+                json!({"type": "connect", "body": {"id": "b", "channel": "userList", "params": {"list_id": "y"}, "pong": true}}),
+            ),
+            record(10, Direction::Incoming, channel("a", 1)),
+            record(20, Direction::Incoming, channel("b", 2)),
+            record(
+                30,
+                Direction::Incoming,
+                json!({"type": "noteUpdated", "body": {"id": "n", "type": "deleted", "body": {}}}),
+            ),
+            record(
+                40,
+                Direction::Incoming,
+                json!({"type": "emojiAdded", "body": {"name": "misskey"}}),
+            ),
+            record(50, Direction::Incoming, channel("a", 3)),
+        ]
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn channel() {
+        let client = ReplayClient::from_records(records()).immediate();
+        let stream = client.channel(Request { list_id: "x" }).await.unwrap();
+        let messages: Vec<Value> = stream.try_collect().await.unwrap();
+        assert_eq!(
+            messages,
+            vec![
+                json!({"type": "note", "body": {"n": 1}}),
+                json!({"type": "note", "body": {"n": 3}}),
+            ]
+        );
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn subnote() {
+        let client = ReplayClient::from_records(records()).immediate();
+        let stream = client.subnote("n".to_string()).await.unwrap();
+        let events: Vec<NoteEvent> = stream.try_collect().await.unwrap();
+        assert_eq!(
+            events,
+            vec![NoteEvent {
+                type_: "deleted".to_string()
+            }]
+        );
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn broadcast() {
+        let client = ReplayClient::from_records(records()).immediate();
+        let stream = client.broadcast::<Event>().await.unwrap();
+        let events: Vec<Event> = stream.try_collect().await.unwrap();
+        assert_eq!(
+            events,
+            vec![Event {
+                name: "misskey".to_string()
+            }]
+        );
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn speed() {
+        let client = ReplayClient::from_records(records()).speed(10.0);
+        let start = std::time::Instant::now();
+        let stream = client.channel(Request { list_id: "x" }).await.unwrap();
+        let messages: Vec<Value> = stream.try_collect().await.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(start.elapsed() >= std::time::Duration::from_millis(5));
+        assert!(start.elapsed() < std::time::Duration::from_millis(50));
+    }
+
+    #[test]
+    fn from_reader() {
+        let records = records();
+        let lines = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let client = ReplayClient::from_reader(lines.as_bytes()).unwrap();
+        assert_eq!(client.records(), records.as_slice());
+
+        let err = ReplayClient::from_reader("{}\n".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
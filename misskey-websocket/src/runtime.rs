@@ -0,0 +1,183 @@
+//! Async runtime abstraction.
+//!
+//! [`WebSocketClient`][`crate::WebSocketClient`] runs a background task that manages the
+//! connection. The task is spawned, put to sleep, and connected to the server through the
+//! [`Runtime`] trait, so you can run the client on any executor by implementing it and passing
+//! it to [`WebSocketClientBuilder::runtime`][`crate::WebSocketClientBuilder::runtime`].
+//!
+//! [`TokioRuntime`] and [`AsyncStdRuntime`] are provided with `tokio-runtime` and
+//! `async-std-runtime` features respectively, and one of them is used when no runtime is given.
+
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_tungstenite::tungstenite::{
+    handshake::client::Request, Error as WsError, Message as WsMessage,
+};
+use futures_util::future::BoxFuture;
+use misskey_core::streaming::BoxStreamSink;
+
+use crate::deflate::DeflateConfig;
+
+/// WebSocket connection returned from [`Runtime::connect`].
+pub type WebSocketConnection = BoxStreamSink<'static, WsMessage, WsMessage, WsError>;
+
+/// Trait for async runtimes that the client runs on.
+///
+/// # Example
+///
+/// Running the client on [smol](https://docs.rs/smol), using the transport from async-tungstenite:
+///
+/// ```ignore
+/// use std::time::Duration;
+///
+/// use async_tungstenite::tungstenite::{handshake::client::Request, Error as WsError};
+/// use futures_util::future::BoxFuture;
+/// use misskey_websocket::runtime::{Runtime, WebSocketConnection};
+///
+/// #[derive(Debug)]
+/// struct SmolRuntime;
+///
+/// impl Runtime for SmolRuntime {
+///     fn spawn(&self, future: BoxFuture<'static, ()>) {
+///         smol::spawn(future).detach();
+///     }
+///
+///     fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+///         Box::pin(async move {
+///             smol::Timer::after(duration).await;
+///         })
+///     }
+///
+///     fn connect(&self, request: Request) -> BoxFuture<'static, Result<WebSocketConnection, WsError>> {
+///         Box::pin(async move {
+///             let (ws, _) = async_tungstenite::async_std::connect_async(request).await?;
+///             Ok(Box::pin(ws) as WebSocketConnection)
+///         })
+///     }
+/// }
+/// ```
+pub trait Runtime: Send + Sync + 'static {
+    /// Spawns a future that runs in the background.
+    fn spawn(&self, future: BoxFuture<'static, ()>);
+
+    /// Returns a future that completes after `duration`.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Opens a WebSocket connection with the given request.
+    fn connect(&self, request: Request)
+        -> BoxFuture<'static, Result<WebSocketConnection, WsError>>;
+
+    /// Opens a WebSocket connection with the given request, offering the permessage-deflate
+    /// extension with `config`.
+    ///
+    /// This is used instead of [`connect`][`Runtime::connect`] when the extension is enabled
+    /// with [`WebSocketClientBuilder::permessage_deflate`][`crate::WebSocketClientBuilder::permessage_deflate`].
+    /// The connection is expected to yield the decompressed messages. The default implementation
+    /// does not offer the extension and opens an uncompressed connection with
+    /// [`connect`][`Runtime::connect`].
+    fn connect_deflate(
+        &self,
+        request: Request,
+        config: DeflateConfig,
+    ) -> BoxFuture<'static, Result<WebSocketConnection, WsError>> {
+        let _ = config;
+        self.connect(request)
+    }
+}
+
+/// [`Runtime`] backed by [tokio](https://tokio.rs).
+///
+/// The client must be used within the context of the tokio runtime.
+#[cfg(feature = "tokio-runtime")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioRuntime;
+
+#[cfg(feature = "tokio-runtime")]
+impl Runtime for TokioRuntime {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        tokio::task::spawn(future);
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    fn connect(
+        &self,
+        request: Request,
+    ) -> BoxFuture<'static, Result<WebSocketConnection, WsError>> {
+        Box::pin(async move {
+            let (ws, _) = async_tungstenite::tokio::connect_async(request).await?;
+            Ok(Box::pin(ws) as WebSocketConnection)
+        })
+    }
+
+    fn connect_deflate(
+        &self,
+        request: Request,
+        config: DeflateConfig,
+    ) -> BoxFuture<'static, Result<WebSocketConnection, WsError>> {
+        Box::pin(crate::deflate::transport::connect(request, config))
+    }
+}
+
+/// [`Runtime`] backed by [async-std](https://async.rs).
+#[cfg(feature = "async-std-runtime")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncStdRuntime;
+
+#[cfg(feature = "async-std-runtime")]
+impl Runtime for AsyncStdRuntime {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        async_std::task::spawn(future);
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(async_std::task::sleep(duration))
+    }
+
+    fn connect(
+        &self,
+        request: Request,
+    ) -> BoxFuture<'static, Result<WebSocketConnection, WsError>> {
+        Box::pin(async move {
+            let (ws, _) = async_tungstenite::async_std::connect_async(request).await?;
+            Ok(Box::pin(ws) as WebSocketConnection)
+        })
+    }
+}
+
+/// Shared handle to the [`Runtime`].
+#[derive(Clone)]
+pub(crate) struct SharedRuntime(Arc<dyn Runtime>);
+
+impl Debug for SharedRuntime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SharedRuntime").finish()
+    }
+}
+
+impl SharedRuntime {
+    pub fn new<R: Runtime>(runtime: R) -> SharedRuntime {
+        SharedRuntime(Arc::new(runtime))
+    }
+
+    /// Returns the runtime enabled by the feature flags, preferring tokio to async-std.
+    pub fn from_features() -> Option<SharedRuntime> {
+        #[cfg(feature = "tokio-runtime")]
+        return Some(SharedRuntime::new(TokioRuntime));
+        #[cfg(all(feature = "async-std-runtime", not(feature = "tokio-runtime")))]
+        return Some(SharedRuntime::new(AsyncStdRuntime));
+        #[cfg(not(any(feature = "tokio-runtime", feature = "async-std-runtime")))]
+        return None;
+    }
+}
+
+impl std::ops::Deref for SharedRuntime {
+    type Target = dyn Runtime;
+    fn deref(&self) -> &dyn Runtime {
+        &*self.0
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+use misskey_core::streaming::ConnectChannelRequest;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+/// [`ConnectChannelRequest`] serialized in advance.
+///
+/// This can be used to connect to the same channel again, and is [`Send`] and [`Sync`] even if
+/// the original request is not.
+pub struct SerializedRequest<R> {
+    params: Value,
+    _marker: PhantomData<fn() -> R>,
+}
+
+impl<R: ConnectChannelRequest> SerializedRequest<R> {
+    /// Serializes the request.
+    pub fn new(request: R) -> serde_json::Result<Self> {
+        Ok(SerializedRequest::from_params(serde_json::to_value(
+            request,
+        )?))
+    }
+
+    /// Creates the request from the parameters serialized beforehand.
+    ///
+    /// Keeping the parameters instead of `SerializedRequest<R>` itself is useful in the futures
+    /// and the closures which must outlive `R`.
+    pub fn from_params(params: Value) -> Self {
+        SerializedRequest {
+            params,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the serialized parameters of the request.
+    pub fn params(&self) -> &Value {
+        &self.params
+    }
+}
+
+impl<R> Clone for SerializedRequest<R> {
+    fn clone(&self) -> Self {
+        SerializedRequest {
+            params: self.params.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<R> Debug for SerializedRequest<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SerializedRequest")
+            .field(&self.params)
+            .finish()
+    }
+}
+
+impl<R> Serialize for SerializedRequest<R> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.params.serialize(serializer)
+    }
+}
+
+impl<R: ConnectChannelRequest> ConnectChannelRequest for SerializedRequest<R> {
+    type Incoming = R::Incoming;
+    type Outgoing = R::Outgoing;
+
+    const NAME: &'static str = R::NAME;
+}
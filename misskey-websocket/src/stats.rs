@@ -0,0 +1,61 @@
+//! Statistics of the connection.
+//!
+//! See [`WebSocketClient::stats`][`crate::WebSocketClient::stats`].
+
+use serde_json::Value;
+
+/// Snapshot of the subscriptions and the state of the connection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    /// Active connections to the channels.
+    pub channels: Vec<ChannelStats>,
+    /// Active note subscriptions.
+    pub sub_notes: Vec<SubNoteStats>,
+    /// Active broadcast streams, grouped by the event type.
+    pub broadcasts: Vec<BroadcastStats>,
+    /// The number of API requests waiting for the responses.
+    pub pending_requests: usize,
+    /// The number of messages received from the server.
+    pub received: u64,
+    /// The number of times the connection has been re-established.
+    pub reconnects: u64,
+}
+
+/// Statistics of a connection to the channel.
+///
+/// When channel sharing is enabled (see
+/// [`WebSocketClientBuilder::share_channels`][`crate::WebSocketClientBuilder::share_channels`]),
+/// a connection can be shared among multiple streams.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelStats {
+    /// The name of the channel.
+    pub name: &'static str,
+    /// The parameters used to connect to the channel.
+    pub params: Value,
+    /// Whether the server has acknowledged the connection.
+    pub connected: bool,
+    /// The number of streams using this connection.
+    pub subscribers: usize,
+    /// The number of messages received from the channel.
+    pub received: u64,
+}
+
+/// Statistics of a note subscription.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubNoteStats {
+    /// The ID of the subscribed note.
+    pub note_id: String,
+    /// The number of events received for the note.
+    pub received: u64,
+}
+
+/// Statistics of the broadcast streams of an event type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastStats {
+    /// The type of the broadcast event.
+    pub type_: &'static str,
+    /// The number of streams receiving the event.
+    pub subscribers: usize,
+    /// The number of events received while there are any streams.
+    pub received: u64,
+}
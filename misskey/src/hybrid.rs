@@ -0,0 +1,400 @@
+//! Client that combines HTTP requests with the streaming over WebSocket.
+
+use std::time::Duration;
+
+use crate::http::{self, HttpClient, HttpClientBuilder};
+use crate::websocket::{self, SerializedRequest, WebSocketClient, WebSocketClientBuilder};
+
+use futures_util::{future::BoxFuture, lock::Mutex, stream::StreamExt};
+use mime::Mime;
+use misskey_core::model::ApiResult;
+use misskey_core::streaming::{
+    BroadcastEvent, BroadcastStream, ChannelStream, ConnectChannelRequest, StreamingClient,
+    SubNoteEvent, SubNoteStream,
+};
+use misskey_core::{Client, Request, UploadFileClient, UploadFileRequest};
+
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Builder for [`HybridClient`].
+#[derive(Debug, Clone)]
+pub struct HybridClientBuilder {
+    host: String,
+    token: Option<String>,
+    idle_timeout: Duration,
+}
+
+impl HybridClientBuilder {
+    /// Creates a new builder instance with the given host name `host`.
+    ///
+    /// HTTP requests are sent to `https://{host}/api/`, and the WebSocket connection is made to
+    /// `wss://{host}/streaming`. All other configurations are set to default.
+    pub fn with_host<S>(host: S) -> Self
+    where
+        S: Into<String>,
+    {
+        HybridClientBuilder {
+            host: host.into(),
+            token: None,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+        }
+    }
+
+    /// Sets an API token.
+    pub fn token<S>(&mut self, token: S) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Sets the duration after which the WebSocket connection is closed when there are no
+    /// subscriptions.
+    ///
+    /// The connection is opened again on the next subscription. 60 secs is used by default.
+    pub fn idle_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Finish this builder instance and build [`HybridClient`].
+    ///
+    /// This does not connect to the WebSocket server yet.
+    pub fn build(&self) -> Result<HybridClient, http::Error> {
+        let mut http = HttpClientBuilder::with_host(&self.host);
+        let mut websocket = WebSocketClientBuilder::with_host(&self.host);
+        if let Some(token) = &self.token {
+            http = http.token(token);
+            websocket.token(token);
+        }
+        websocket.idle_timeout(self.idle_timeout);
+
+        Ok(HybridClient {
+            http: http.build()?,
+            websocket,
+            connection: Mutex::new(None),
+        })
+    }
+}
+
+/// Client that sends requests over HTTP and streams over WebSocket.
+///
+/// API requests and file uploads are sent with [`HttpClient`], and the streaming operations are
+/// served by [`WebSocketClient`]. The WebSocket connection is opened on the first call to
+/// [`channel`][`StreamingClient::channel`], [`subnote`][`StreamingClient::subnote`] or
+/// [`broadcast`][`StreamingClient::broadcast`], and is closed after it has no subscriptions
+/// for a while (see [`HybridClientBuilder::idle_timeout`]).
+///
+/// # Example
+///
+/// ```no_run
+/// use futures::stream::TryStreamExt;
+/// use misskey::prelude::*;
+/// use misskey::HybridClient;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let client = HybridClient::with_token("your.instance.example", "API_TOKEN")?;
+///
+/// // sent over HTTP
+/// client.create_note("Hello, Misskey").await?;
+///
+/// // connects to the WebSocket server here
+/// let mut stream = client.local_timeline().await?;
+/// while let Some(note) = stream.try_next().await? {
+///     println!("{:?}", note.text);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct HybridClient {
+    http: HttpClient,
+    websocket: WebSocketClientBuilder,
+    connection: Mutex<Option<WebSocketClient>>,
+}
+
+impl HybridClient {
+    /// Creates a new client for the given host name `host` with the API token.
+    pub fn with_token<S, T>(host: S, token: T) -> Result<HybridClient, http::Error>
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        HybridClientBuilder::with_host(host).token(token).build()
+    }
+
+    /// Creates a new builder instance with the given host name `host`.
+    ///
+    /// This function is identical to [`HybridClientBuilder::with_host`].
+    pub fn builder<S>(host: S) -> HybridClientBuilder
+    where
+        S: Into<String>,
+    {
+        HybridClientBuilder::with_host(host)
+    }
+
+    /// Returns a reference to the underlying HTTP client.
+    pub fn http(&self) -> &HttpClient {
+        &self.http
+    }
+
+    /// Returns the current WebSocket client, connecting to the server if not connected.
+    pub async fn websocket(&self) -> Result<WebSocketClient, websocket::Error> {
+        let mut connection = self.connection.lock().await;
+        match &*connection {
+            Some(client) if !client.is_closed() => return Ok(client.clone()),
+            _ => {}
+        }
+
+        let client = self.websocket.connect().await?;
+        *connection = Some(client.clone());
+        Ok(client)
+    }
+
+    /// Runs `f` with the WebSocket client, retrying once with a new connection if the current
+    /// one is closed meanwhile.
+    async fn with_websocket<T, F>(&self, f: F) -> Result<T, websocket::Error>
+    where
+        F: Fn(&WebSocketClient) -> BoxFuture<'static, Result<T, websocket::Error>>,
+    {
+        let client = self.websocket().await?;
+        match f(&client).await {
+            Err(websocket::Error::Closed) => {}
+            result => return result,
+        }
+
+        let client = self.websocket().await?;
+        f(&client).await
+    }
+
+    /// Closes the WebSocket connection if it is open.
+    ///
+    /// See [`WebSocketClient::close`] for details. The connection is opened again on the next
+    /// subscription.
+    pub async fn close(&self) -> Result<(), websocket::Error> {
+        let client = self.connection.lock().await.take();
+        match client {
+            Some(client) => client.close().await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Client for HybridClient {
+    type Error = http::Error;
+
+    fn request<R: Request>(
+        &self,
+        request: R,
+    ) -> BoxFuture<'_, Result<ApiResult<R::Response>, Self::Error>> {
+        self.http.request(request)
+    }
+}
+
+impl UploadFileClient for HybridClient {
+    fn request_with_file<R, T>(
+        &self,
+        request: R,
+        type_: Mime,
+        file_name: String,
+        content: T,
+    ) -> BoxFuture<'_, Result<ApiResult<R::Response>, Self::Error>>
+    where
+        R: UploadFileRequest,
+        T: std::io::Read + Send + Sync + 'static,
+    {
+        self.http
+            .request_with_file(request, type_, file_name, content)
+    }
+}
+
+impl StreamingClient for HybridClient {
+    type Error = websocket::Error;
+
+    fn subnote<E: SubNoteEvent>(
+        &self,
+        note_id: String,
+    ) -> BoxFuture<'_, Result<SubNoteStream<'_, E, Self::Error>, Self::Error>> {
+        Box::pin(async move {
+            let stream = self
+                .with_websocket(|client| client.subnote(note_id.clone()))
+                .await?;
+            Ok(stream.boxed())
+        })
+    }
+
+    fn channel<R: ConnectChannelRequest>(
+        &self,
+        request: R,
+    ) -> BoxFuture<'_, Result<ChannelStream<'_, R, Self::Error>, Self::Error>> {
+        // serialized here not to require `Send` on the request
+        let params = SerializedRequest::new(request).map(|request| request.params().clone());
+        Box::pin(async move {
+            let request = SerializedRequest::<R>::from_params(params?);
+            let stream = self
+                .with_websocket(|client| client.channel(&request))
+                .await?;
+            Ok(Box::pin(stream) as ChannelStream<'_, R, Self::Error>)
+        })
+    }
+
+    fn broadcast<E: BroadcastEvent>(
+        &self,
+    ) -> BoxFuture<'_, Result<BroadcastStream<'_, E, Self::Error>, Self::Error>> {
+        Box::pin(async move {
+            let stream = self.with_websocket(|client| client.broadcast()).await?;
+            Ok(stream.boxed())
+        })
+    }
+}
+
+#[cfg(all(test, feature = "tokio-runtime"))]
+mod tests {
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+
+    use super::HybridClient;
+    use crate::websocket::runtime::{Runtime, WebSocketConnection};
+
+    use async_tungstenite::tungstenite::{
+        handshake::client::Request, Error as WsError, Message as WsMessage,
+    };
+    use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+    use futures::future::BoxFuture;
+    use futures::sink::Sink;
+    use futures::stream::{Stream, StreamExt};
+    use misskey_api::streaming::channel::main;
+    use misskey_core::streaming::StreamingClient;
+    use serde_json::{json, Value};
+
+    /// In-memory connection to [`MockServerRuntime`].
+    struct MockConnection {
+        rx: UnboundedReceiver<WsMessage>,
+        tx: UnboundedSender<WsMessage>,
+    }
+
+    impl Stream for MockConnection {
+        type Item = Result<WsMessage, WsError>;
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.rx.poll_next_unpin(cx).map(|msg| msg.map(Ok))
+        }
+    }
+
+    impl Sink<WsMessage> for MockConnection {
+        type Error = WsError;
+
+        fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), WsError>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: WsMessage) -> Result<(), WsError> {
+            self.tx
+                .unbounded_send(item)
+                .map_err(|_| WsError::ConnectionClosed)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), WsError>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), WsError>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    /// Runtime that connects to an in-memory server, which acknowledges the channel connections
+    /// and replies to the close frames.
+    #[derive(Default, Clone)]
+    struct MockServerRuntime {
+        connections: Arc<AtomicUsize>,
+    }
+
+    impl Runtime for MockServerRuntime {
+        fn spawn(&self, future: BoxFuture<'static, ()>) {
+            tokio::task::spawn(future);
+        }
+
+        fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+            Box::pin(tokio::time::sleep(duration))
+        }
+
+        fn connect(&self, _: Request) -> BoxFuture<'static, Result<WebSocketConnection, WsError>> {
+            self.connections.fetch_add(1, Ordering::SeqCst);
+            let (client_tx, mut server_rx) = mpsc::unbounded();
+            let (server_tx, client_rx) = mpsc::unbounded();
+            tokio::task::spawn(async move {
+                while let Some(msg) = server_rx.next().await {
+                    match msg {
+                        WsMessage::Text(text) => {
+                            let msg: Value = serde_json::from_str(&text).unwrap();
+                            if msg["type"] == "connect" {
+                                let connected = json!({
+                                    "type": "connected",
+                                    "body": { "id": msg["body"]["id"] },
+                                });
+                                let _ = server_tx
+                                    .unbounded_send(WsMessage::Text(connected.to_string()));
+                            }
+                        }
+                        WsMessage::Close(_) => {
+                            let _ = server_tx.unbounded_send(WsMessage::Close(None));
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+            });
+            let connection = MockConnection {
+                rx: client_rx,
+                tx: client_tx,
+            };
+            Box::pin(async move { Ok(Box::pin(connection) as WebSocketConnection) })
+        }
+    }
+
+    fn client(runtime: &MockServerRuntime) -> HybridClient {
+        let mut client = HybridClient::builder("example.com")
+            .idle_timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        client.websocket.runtime(runtime.clone());
+        client
+    }
+
+    #[tokio::test]
+    async fn lazy_connect() {
+        let runtime = MockServerRuntime::default();
+        let client = client(&runtime);
+        assert_eq!(runtime.connections.load(Ordering::SeqCst), 0);
+
+        let _stream = client.channel(main::Request::default()).await.unwrap();
+        let _stream2 = client.channel(main::Request::default()).await.unwrap();
+        assert_eq!(runtime.connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn reconnect_after_idle_close() {
+        let runtime = MockServerRuntime::default();
+        let client = client(&runtime);
+
+        let stream = client.channel(main::Request::default()).await.unwrap();
+        let websocket = client.websocket().await.unwrap();
+        std::mem::drop(stream);
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !websocket.is_closed() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let _stream = client.channel(main::Request::default()).await.unwrap();
+        assert_eq!(runtime.connections.load(Ordering::SeqCst), 2);
+    }
+}
@@ -4,7 +4,7 @@
 //!
 //! - Clients that handles the connection between Misskey. As Misskey provides HTTP and WebSocket
 //!   interfaces to interact with, we have [`HttpClient`] and [`WebSocketClient`] implementations
-//!   correspondingly.
+//!   correspondingly. [`HybridClient`] combines both of them.
 //! - API bindings, including requests/responses of [endpoints][`endpoint`] and messages on
 //!   [channels][`streaming::channel`].
 //! - Abstraction that connects API datatypes and client implementations: [`Request`][`endpoint::Request`],
@@ -102,6 +102,10 @@
 //!
 //! | Feature                    | Supported Misskey versions (inclusive) | Tested Misskey version |
 //! | -------------------------- | -------------------------------------- | ---------------------- |
+//! | `13-10-0`                  | v13.10.0 ~                             | -                      |
+//! | `12-112-0`                 | v12.112.0 ~ v12.119.2                  | -                      |
+//! | `12-108-0`                 | v12.108.0 ~ v12.111.2                  | -                      |
+//! | `12-79-0`                  | v12.79.0 ~ v12.107.0                   | -                      |
 //! | `12-75-0`                  | v12.75.0 ~ v12.75.1                    | v12.75.0               |
 //! | `12-71-0`                  | v12.71.0 ~ v12.74.1                    | v12.71.0               |
 //! | `12-70-0`                  | v12.70.0                               | v12.70.0               |
@@ -311,6 +315,7 @@ pub mod model {
     pub use misskey_core::model::*;
 }
 
+pub use misskey_api::mfm;
 pub use misskey_core::streaming::StreamingClient;
 pub use misskey_core::{Client, UploadFileClient};
 
@@ -333,6 +338,7 @@ pub mod websocket {
     //!
     //! The underlying async runtime is determined by the feature flags.
     //! The [tokio](https://tokio.rs) runtime is enabled by default. For details, see the [feature flags section](../index.html#feature-flags).
+    //! You can also run the client on other executors by implementing [`runtime::Runtime`].
 
     pub use misskey_websocket::*;
 }
@@ -341,7 +347,26 @@ pub mod websocket {
 #[cfg_attr(docsrs, doc(cfg(feature = "websocket-client")))]
 pub use websocket::WebSocketClient;
 
-pub use misskey_util::{builder, pager, Error, TimelineCursor, TimelineRange};
+#[cfg(all(feature = "http-client", feature = "websocket-client"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "http-client", feature = "websocket-client")))
+)]
+pub mod hybrid;
+
+#[cfg(all(feature = "http-client", feature = "websocket-client"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "http-client", feature = "websocket-client")))
+)]
+pub use hybrid::HybridClient;
+
+#[cfg(feature = "12-112-0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "12-112-0")))]
+pub use misskey_util::webhook;
+pub use misskey_util::{
+    builder, pager, polling, reversi, Error, TimelineCursor, TimelineRange, TrendEvent,
+};
 pub use misskey_util::{ClientExt, StreamingClientExt, UploadFileClientExt};
 
 /// Prelude for crates using `misskey-rs`.